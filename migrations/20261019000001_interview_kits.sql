@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS interview_kits (
+    id SERIAL PRIMARY KEY,
+    resume_id INTEGER NOT NULL UNIQUE REFERENCES resumes(id) ON DELETE CASCADE,
+    created_by VARCHAR(50) NOT NULL,
+    questions JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_interview_kits_resume_id ON interview_kits(resume_id);
@@ -0,0 +1,17 @@
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS letter_template TEXT;
+
+CREATE TABLE IF NOT EXISTS candidate_letters (
+    id SERIAL PRIMARY KEY,
+    resume_id INTEGER NOT NULL UNIQUE REFERENCES resumes(id) ON DELETE CASCADE,
+    created_by VARCHAR(50) NOT NULL,
+    tone VARCHAR(20) NOT NULL DEFAULT 'neutral',
+    candidate_email VARCHAR(255),
+    subject TEXT NOT NULL,
+    body TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'draft',
+    sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_candidate_letters_resume_id ON candidate_letters(resume_id);
@@ -0,0 +1,25 @@
+-- single row describing which model produced the vectors in jobs.embedding and resumes.embedding
+CREATE TABLE IF NOT EXISTS embedding_metadata (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    provider VARCHAR(50) NOT NULL DEFAULT '',
+    model VARCHAR(255) NOT NULL DEFAULT '',
+    dimensions INTEGER NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO embedding_metadata (id, dimensions) VALUES (TRUE, 768) ON CONFLICT (id) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS reembed_runs (
+    id SERIAL PRIMARY KEY,
+    provider VARCHAR(50) NOT NULL,
+    model VARCHAR(255) NOT NULL,
+    dimensions INTEGER NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'running',
+    total INTEGER NOT NULL DEFAULT 0,
+    processed INTEGER NOT NULL DEFAULT 0,
+    failed INTEGER NOT NULL DEFAULT 0,
+    last_job_id INTEGER NOT NULL DEFAULT 0,
+    last_resume_id INTEGER NOT NULL DEFAULT 0,
+    started_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMPTZ
+);
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS ai_usage (
+    id BIGSERIAL PRIMARY KEY,
+    provider VARCHAR(50) NOT NULL,
+    model VARCHAR(255) NOT NULL,
+    operation VARCHAR(20) NOT NULL,
+    prompt_tokens INTEGER NOT NULL DEFAULT 0,
+    completion_tokens INTEGER NOT NULL DEFAULT 0,
+    latency_ms INTEGER NOT NULL DEFAULT 0,
+    cost_usd DOUBLE PRECISION NOT NULL DEFAULT 0,
+    project_id VARCHAR(50),
+    evaluation_id INTEGER REFERENCES evaluations(id) ON DELETE SET NULL,
+    resume_id INTEGER REFERENCES resumes(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_ai_usage_evaluation_id ON ai_usage(evaluation_id);
+CREATE INDEX idx_ai_usage_resume_id ON ai_usage(resume_id);
+CREATE INDEX idx_ai_usage_project_id ON ai_usage(project_id);
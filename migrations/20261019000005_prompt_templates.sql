@@ -0,0 +1,39 @@
+CREATE TABLE IF NOT EXISTS prompt_templates (
+    id SERIAL PRIMARY KEY,
+    project_id VARCHAR(50) NOT NULL REFERENCES projects(project_id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    version INTEGER NOT NULL,
+    body TEXT NOT NULL,
+    created_by VARCHAR(50) NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (project_id, name, version)
+);
+
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS prompt_version VARCHAR(100);
+
+CREATE TABLE IF NOT EXISTS prompt_comparisons (
+    id SERIAL PRIMARY KEY,
+    evaluation_id INTEGER NOT NULL REFERENCES evaluations(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    version_a VARCHAR(100) NOT NULL,
+    version_b VARCHAR(100) NOT NULL,
+    status VARCHAR(50) NOT NULL DEFAULT 'running',
+    created_by VARCHAR(50) NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS prompt_comparison_results (
+    id SERIAL PRIMARY KEY,
+    comparison_id INTEGER NOT NULL REFERENCES prompt_comparisons(id) ON DELETE CASCADE,
+    resume_id INTEGER NOT NULL REFERENCES resumes(id) ON DELETE CASCADE,
+    status_a VARCHAR(50),
+    score_a VARCHAR(50),
+    feedback_a TEXT,
+    status_b VARCHAR(50),
+    score_b VARCHAR(50),
+    feedback_b TEXT,
+    error TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    UNIQUE (comparison_id, resume_id)
+);
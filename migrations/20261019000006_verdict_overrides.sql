@@ -0,0 +1,19 @@
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS override_status VARCHAR(50);
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS override_score VARCHAR(50);
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS override_reason TEXT;
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS overridden_by VARCHAR(50);
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS overridden_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS verdict_overrides (
+    id SERIAL PRIMARY KEY,
+    resume_id INTEGER NOT NULL REFERENCES resumes(id) ON DELETE CASCADE,
+    changed_by VARCHAR(50) NOT NULL,
+    previous_status VARCHAR(50) NOT NULL,
+    previous_score VARCHAR(50),
+    new_status VARCHAR(50) NOT NULL,
+    new_score VARCHAR(50),
+    reason TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_verdict_overrides_resume_id ON verdict_overrides(resume_id);
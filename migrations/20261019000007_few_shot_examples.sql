@@ -0,0 +1 @@
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS few_shot_examples INTEGER[];
@@ -0,0 +1,28 @@
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS rank INTEGER;
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS rank_score DOUBLE PRECISION;
+
+CREATE TABLE IF NOT EXISTS ranking_runs (
+    id SERIAL PRIMARY KEY,
+    evaluation_id INTEGER NOT NULL REFERENCES evaluations(id) ON DELETE CASCADE,
+    top_n INTEGER NOT NULL,
+    budget INTEGER NOT NULL,
+    comparisons INTEGER NOT NULL DEFAULT 0,
+    failed INTEGER NOT NULL DEFAULT 0,
+    status VARCHAR(50) NOT NULL DEFAULT 'running',
+    created_by VARCHAR(50) NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ranking_comparisons (
+    id SERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES ranking_runs(id) ON DELETE CASCADE,
+    resume_a INTEGER NOT NULL REFERENCES resumes(id) ON DELETE CASCADE,
+    resume_b INTEGER NOT NULL REFERENCES resumes(id) ON DELETE CASCADE,
+    winner INTEGER NOT NULL REFERENCES resumes(id) ON DELETE CASCADE,
+    reason TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_ranking_runs_evaluation_id ON ranking_runs(evaluation_id);
+CREATE INDEX idx_ranking_comparisons_run_id ON ranking_comparisons(run_id);
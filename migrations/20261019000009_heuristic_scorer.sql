@@ -0,0 +1,3 @@
+ALTER TABLE evaluations ADD COLUMN IF NOT EXISTS scorer VARCHAR(20) NOT NULL DEFAULT 'llm';
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS scorer VARCHAR(20);
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS score_breakdown JSONB;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS resume_texts (
+    id SERIAL PRIMARY KEY,
+    resume_id INTEGER NOT NULL UNIQUE REFERENCES resumes(id) ON DELETE CASCADE,
+    text TEXT NOT NULL,
+    sections JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_resume_texts_resume_id ON resume_texts(resume_id);
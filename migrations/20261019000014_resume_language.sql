@@ -0,0 +1,4 @@
+ALTER TABLE resumes ADD COLUMN IF NOT EXISTS language VARCHAR(8);
+ALTER TABLE resume_texts ADD COLUMN IF NOT EXISTS translated_text TEXT;
+ALTER TABLE resume_texts ADD COLUMN IF NOT EXISTS translated_to VARCHAR(8);
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS translate_resumes BOOLEAN NOT NULL DEFAULT false;
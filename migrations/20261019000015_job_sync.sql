@@ -0,0 +1,18 @@
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS closed_at TIMESTAMP;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS last_synced_at TIMESTAMP;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS source_hash BIGINT;
+
+CREATE INDEX idx_jobs_sync ON jobs(last_synced_at) WHERE url IS NOT NULL AND closed_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS job_revisions (
+    id SERIAL PRIMARY KEY,
+    job_id INTEGER NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    department VARCHAR(255) NOT NULL,
+    description TEXT NOT NULL,
+    requirements TEXT NOT NULL,
+    changed TEXT[] NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_job_revisions_job_id ON job_revisions(job_id);
@@ -0,0 +1,22 @@
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS project_id VARCHAR(50) REFERENCES projects(project_id) ON DELETE CASCADE;
+ALTER TABLE evaluations ADD COLUMN IF NOT EXISTS project_id VARCHAR(50) REFERENCES projects(project_id) ON DELETE CASCADE;
+
+-- existing rows go to their creator's project when the creator has exactly one;
+-- anything else stays without a project and is no longer visible to anyone
+WITH sole_project AS (
+    SELECT user_id, MIN(project_id) AS project_id
+    FROM project_access
+    WHERE status = 'accepted'
+    GROUP BY user_id
+    HAVING COUNT(*) = 1
+)
+UPDATE jobs SET project_id = sole_project.project_id
+FROM sole_project
+WHERE jobs.project_id IS NULL AND jobs.created_by = sole_project.user_id;
+
+UPDATE evaluations SET project_id = jobs.project_id
+FROM jobs
+WHERE evaluations.project_id IS NULL AND evaluations.job_id = jobs.id;
+
+CREATE INDEX idx_jobs_project_id ON jobs(project_id);
+CREATE INDEX idx_evaluations_project_id ON evaluations(project_id);
@@ -0,0 +1 @@
+ALTER TABLE prompt_comparisons ADD COLUMN IF NOT EXISTS error TEXT;
@@ -0,0 +1,9 @@
+-- only the latest of several running runs per evaluation can still be alive
+UPDATE ranking_runs r SET status = 'failed', updated_at = CURRENT_TIMESTAMP
+WHERE r.status = 'running'
+  AND EXISTS (
+    SELECT 1 FROM ranking_runs newer
+    WHERE newer.evaluation_id = r.evaluation_id AND newer.status = 'running' AND newer.id > r.id
+  );
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_ranking_runs_running ON ranking_runs(evaluation_id) WHERE status = 'running';
@@ -0,0 +1,2 @@
+-- set by hand edits, which the re-sync then leaves alone
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS edited_at TIMESTAMP;
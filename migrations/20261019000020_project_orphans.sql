@@ -0,0 +1,14 @@
+-- reports jobs and evaluations the tenancy backfill could not place; they are only moved
+-- by an explicit `assign-orphans`, as guessing a project could show them to the wrong team
+DO $$
+DECLARE
+    orphaned_jobs BIGINT;
+    orphaned_evaluations BIGINT;
+BEGIN
+    SELECT COUNT(*) INTO orphaned_jobs FROM jobs WHERE project_id IS NULL;
+    SELECT COUNT(*) INTO orphaned_evaluations FROM evaluations WHERE project_id IS NULL;
+    IF orphaned_jobs > 0 OR orphaned_evaluations > 0 THEN
+        RAISE NOTICE '% jobs and % evaluations have no project, run assign-orphans to move them',
+            orphaned_jobs, orphaned_evaluations;
+    END IF;
+END $$;
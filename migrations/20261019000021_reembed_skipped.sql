@@ -0,0 +1,9 @@
+-- resumes a re-embedding run cannot embed at all, e.g. unreadable or missing files;
+-- they stay without an embedding instead of holding back the model swap forever
+CREATE TABLE IF NOT EXISTS reembed_skipped (
+    run_id INTEGER NOT NULL REFERENCES reembed_runs(id) ON DELETE CASCADE,
+    resume_id INTEGER NOT NULL REFERENCES resumes(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (run_id, resume_id)
+);
@@ -0,0 +1,23 @@
+use crate::{
+    pkg::{
+        internal::adaptors::jobs::{mutators::JobMutator, selectors::JobSelector},
+        server::state::{AppState, GetTxn},
+    },
+    prelude::Result,
+};
+
+/// Moves jobs and evaluations that belong to no project into one, printing how many
+pub async fn run(project: &str, user: Option<&str>) -> Result<()> {
+    let state = AppState::connect().await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let (jobs, evaluations) = JobMutator::new(&mut tx).assign_orphans(project, user).await?;
+    let (orphaned_jobs, orphaned_evaluations) = JobSelector::new(&mut tx).count_orphans().await?;
+    tx.commit().await?;
+    tracing::info!("assigned {} jobs and {} evaluations to project {}", jobs, evaluations, project);
+    println!("Moved {} jobs and {} evaluations to {}", jobs, evaluations, project);
+    println!(
+        "{} jobs and {} evaluations still have no project",
+        orphaned_jobs, orphaned_evaluations
+    );
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use std::io::Read;
+
+use standard_error::{Interpolate, StandardError};
+
+use crate::{
+    pkg::internal::ai::read::sandbox::{ParseOutcome, extract_guarded},
+    prelude::Result,
+};
+
+/// Parses the document on stdin and prints the outcome as one JSON line
+pub fn run(content_type: &str) -> Result<()> {
+    let mut data = vec![];
+    std::io::stdin()
+        .read_to_end(&mut data)
+        .map_err(|e| StandardError::new("ERR-AI-005").interpolate_err(e.to_string()))?;
+    let outcome = match extract_guarded(data, content_type) {
+        Ok(extraction) => ParseOutcome::Parsed(extraction),
+        Err(e) => ParseOutcome::Failed {
+            code: e.err_code,
+            message: e.message,
+        },
+    };
+    println!("{}", serde_json::to_string(&outcome)?);
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::{
+    pkg::server::{handlers::jobs::import, state::AppState},
+    prelude::Result,
+};
+
+/// Imports every posting a careers page, feed or board lists and prints what happened
+pub async fn run(url: &str, user: &str, project: &str) -> Result<()> {
+    let state = AppState::connect().await?;
+    let report = import(&state, user, project, url).await?;
+    for job in &report.created {
+        println!("created {:>6}  {}  {}", job.id, job.title, job.url.as_deref().unwrap_or("-"));
+    }
+    for url in &report.skipped {
+        println!("skipped         {} (already imported)", url);
+    }
+    for failure in &report.failed {
+        println!("failed          {}: {}", failure.url, failure.message);
+    }
+    println!(
+        "Found {} postings: {} created, {} skipped, {} failed",
+        report.found,
+        report.created.len(),
+        report.skipped.len(),
+        report.failed.len()
+    );
+    Ok(())
+}
@@ -1,6 +1,10 @@
 use std::sync::Arc;
 
-use crate::{conf::settings, prelude::Result, pkg::server::state::GetTxn};
+use crate::{
+    conf::settings,
+    pkg::{internal::adaptors::jobs::selectors::JobSelector, server::state::GetTxn},
+    prelude::Result,
+};
 use sqlx::{migrate::Migrator, postgres::PgPoolOptions};
 use standard_error::{Interpolate, StandardError};
 
@@ -19,10 +23,22 @@ pub async fn apply() -> Result<()> {
     let mut tx = pool.begin_txn().await?;
     MIGRATOR.run(&mut *tx).await
         .map_err(|e| StandardError::new("ERR-DB-000").interpolate_err(e.to_string()))?;
+    let (orphaned_jobs, orphaned_evaluations) = JobSelector::new(&mut tx).count_orphans().await?;
     
     tx.commit().await
         .map_err(|e| StandardError::new("ERR-DB-000").interpolate_err(e.to_string()))?;
     
     println!("Migrations applied successfully");
+    if orphaned_jobs > 0 || orphaned_evaluations > 0 {
+        tracing::warn!(
+            "{} jobs and {} evaluations have no project and are visible to no one",
+            orphaned_jobs,
+            orphaned_evaluations
+        );
+        println!(
+            "{} jobs and {} evaluations have no project, move them with `assign-orphans --project <id>`",
+            orphaned_jobs, orphaned_evaluations
+        );
+    }
     Ok(())
 }
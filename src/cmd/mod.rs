@@ -1,7 +1,12 @@
 use crate::{pkg::server::listen, prelude::Result};
 use clap::{Parser, Subcommand};
 
+mod assign_orphans;
+mod extract;
+mod import_jobs;
 mod migrate;
+mod reembed;
+mod usage;
 
 #[derive(Parser)]
 #[command(about = "starts resumeval")]
@@ -15,6 +20,43 @@ enum SubCommandType {
     Listen,
     Consume,
     Migrate,
+    /// re-embed all jobs and resumes after switching embedding models
+    Reembed {
+        #[arg(long, default_value_t = 20)]
+        batch_size: i64,
+    },
+    /// parse one document from stdin, used by PARSE_ISOLATION=process
+    #[command(hide = true)]
+    ExtractDocument {
+        #[arg(long)]
+        content_type: String,
+    },
+    /// create jobs from a careers page, RSS/Atom feed or Greenhouse/Lever board
+    ImportJobs {
+        url: String,
+        /// user recorded as the creator of the jobs
+        #[arg(long)]
+        user: String,
+        /// project the jobs are created in
+        #[arg(long)]
+        project: String,
+    },
+    /// move jobs and evaluations that belong to no project into one
+    AssignOrphans {
+        /// project the jobs and evaluations are moved to
+        #[arg(long)]
+        project: String,
+        /// only move jobs this user created
+        #[arg(long)]
+        user: Option<String>,
+    },
+    /// print AI token usage and estimated cost
+    UsageReport {
+        #[arg(long)]
+        evaluation: Option<i32>,
+        #[arg(long)]
+        project: Option<String>,
+    },
 }
 
 pub async fn run() -> Result<()> {
@@ -29,6 +71,21 @@ pub async fn run() -> Result<()> {
         Some(SubCommandType::Migrate) => {
             migrate::apply().await?;
         }
+        Some(SubCommandType::Reembed { batch_size }) => {
+            reembed::run(batch_size).await?;
+        }
+        Some(SubCommandType::ImportJobs { url, user, project }) => {
+            import_jobs::run(&url, &user, &project).await?;
+        }
+        Some(SubCommandType::AssignOrphans { project, user }) => {
+            assign_orphans::run(&project, user.as_deref()).await?;
+        }
+        Some(SubCommandType::UsageReport { evaluation, project }) => {
+            usage::report(evaluation, project).await?;
+        }
+        Some(SubCommandType::ExtractDocument { content_type }) => {
+            extract::run(&content_type)?;
+        }
         None => {
             tracing::error!("no subcommand passed");
         }
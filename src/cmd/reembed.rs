@@ -0,0 +1,229 @@
+use pgvector::Vector;
+use sqlx::PgConnection;
+
+use crate::{
+    conf::settings,
+    pkg::{
+        internal::{
+            adaptors::{
+                embeddings::{mutators::EmbeddingMutator, selectors::EmbeddingSelector},
+                jobs::{selectors::JobSelector, spec::JobEntry},
+                resumes::{selectors::ResumeSelector, spec::ResumeEntry},
+            },
+            ai::{
+                index::IndexOps,
+                usage::{UsageScope, scoped},
+            },
+        },
+        server::{
+            handlers::documents::resume_text,
+            state::{AppState, GetTxn},
+        },
+    },
+    prelude::Result,
+};
+
+/// Re-embeds every job and resume with the configured embedding model in batches.
+/// An interrupted run for the same model picks up from its last recorded batch.
+pub async fn run(batch_size: i64) -> Result<()> {
+    let state = AppState::connect().await?;
+    let dimensions = state.ai_client.embedding_dimensions().await?;
+    tracing::info!(
+        "re-embedding with {} ({} dimensions)",
+        &settings.ai_embedding_model,
+        dimensions
+    );
+
+    let mut tx = state.db_pool.begin_txn().await?;
+    let run = match EmbeddingSelector::new(&mut tx).get_running().await? {
+        Some(run) if run.model == settings.ai_embedding_model && run.dimensions == dimensions => {
+            tracing::info!(
+                "resuming run {} at {}/{} (job {}, resume {})",
+                run.id,
+                run.processed,
+                run.total,
+                run.last_job_id,
+                run.last_resume_id
+            );
+            run
+        }
+        _ => {
+            let total = JobSelector::new(&mut tx).count().await?
+                + ResumeSelector::new(&mut tx).count().await?;
+            EmbeddingMutator::new(&mut tx)
+                .start_run(
+                    &settings.ai_provider,
+                    &settings.ai_embedding_model,
+                    dimensions,
+                    total as i32,
+                )
+                .await?
+        }
+    };
+    tx.commit().await?;
+
+    let mut last_job_id = run.last_job_id;
+    let mut last_resume_id = run.last_resume_id;
+    loop {
+        let mut tx = state.db_pool.begin_txn().await?;
+        let jobs = JobSelector::new(&mut tx)
+            .get_batch_after(last_job_id, batch_size)
+            .await?;
+        if jobs.is_empty() {
+            break;
+        }
+        let (mut processed, mut failed) = (0, 0);
+        for job in jobs {
+            last_job_id = job.id;
+            match embed_job(&state, &job).await {
+                Ok(embedding) => {
+                    EmbeddingMutator::new(&mut tx)
+                        .stage_job_embedding(job.id, &embedding)
+                        .await?;
+                    processed += 1;
+                }
+                Err(e) => {
+                    tracing::error!("failed to embed job {}: {}", job.id, e.message);
+                    failed += 1;
+                }
+            }
+        }
+        let progress = EmbeddingMutator::new(&mut tx)
+            .record_progress(run.id, processed, failed, last_job_id, last_resume_id)
+            .await?;
+        tx.commit().await?;
+        tracing::info!("jobs: {}/{} processed", progress.processed, progress.total);
+    }
+
+    loop {
+        let mut tx = state.db_pool.begin_txn().await?;
+        let resumes = ResumeSelector::new(&mut tx)
+            .get_batch_after(last_resume_id, batch_size)
+            .await?;
+        if resumes.is_empty() {
+            break;
+        }
+        let (mut processed, mut failed) = (0, 0);
+        for resume in resumes {
+            last_resume_id = resume.id;
+            match reembed_resume(&state, &mut tx, run.id, &resume).await? {
+                Outcome::Staged => processed += 1,
+                Outcome::Skipped => {}
+                Outcome::Failed => failed += 1,
+            }
+        }
+        let progress = EmbeddingMutator::new(&mut tx)
+            .record_progress(run.id, processed, failed, last_job_id, last_resume_id)
+            .await?;
+        tx.commit().await?;
+        tracing::info!("resumes: {}/{} processed", progress.processed, progress.total);
+    }
+
+    // one more pass over rows that failed or were created behind the batches
+    let mut tx = state.db_pool.begin_txn().await?;
+    let jobs = EmbeddingSelector::new(&mut tx).get_unstaged_jobs().await?;
+    let resumes = EmbeddingSelector::new(&mut tx).get_unstaged_resumes(run.id).await?;
+    tx.commit().await?;
+    let mut remaining = 0;
+    for job in jobs {
+        match embed_job(&state, &job).await {
+            Ok(embedding) => {
+                let mut tx = state.db_pool.begin_txn().await?;
+                EmbeddingMutator::new(&mut tx)
+                    .stage_job_embedding(job.id, &embedding)
+                    .await?;
+                tx.commit().await?;
+            }
+            Err(e) => {
+                tracing::error!("failed to embed job {} again: {}", job.id, e.message);
+                remaining += 1;
+            }
+        }
+    }
+    for resume in resumes {
+        let mut tx = state.db_pool.begin_txn().await?;
+        if let Outcome::Failed = reembed_resume(&state, &mut tx, run.id, &resume).await? {
+            remaining += 1;
+        }
+        tx.commit().await?;
+    }
+
+    let mut tx = state.db_pool.begin_txn().await?;
+    let run = EmbeddingMutator::new(&mut tx)
+        .set_failed(run.id, remaining)
+        .await?;
+    let finished = EmbeddingMutator::new(&mut tx).finish_run(&run).await?;
+    let skipped = EmbeddingSelector::new(&mut tx).count_skipped(run.id).await?;
+    tx.commit().await?;
+    if skipped > 0 {
+        println!(
+            "{} resumes have no readable text and were left without an embedding, see reembed_skipped for run {}",
+            skipped, run.id
+        );
+    }
+    if finished.is_none() {
+        println!(
+            "Run {} left running, some documents still have no {} embedding ({} failed); run reembed again to retry",
+            run.id, run.model, run.failed
+        );
+        return Ok(());
+    }
+    println!(
+        "Re-embedded {} of {} documents with {}",
+        run.processed, run.total, run.model
+    );
+    Ok(())
+}
+
+async fn embed_job(state: &AppState, job: &JobEntry) -> Result<Vector> {
+    let scope = UsageScope {
+        project_id: job.project_id.clone(),
+        ..Default::default()
+    };
+    let jd = serde_json::to_string(job)?;
+    scoped(scope, state.ai_client.index_document(&jd)).await
+}
+
+enum Outcome {
+    Staged,
+    /// the resume has no text to embed, retrying will not help
+    Skipped,
+    /// the provider failed, the resume is retried
+    Failed,
+}
+
+/// Embeds a resume from its stored text, which is only extracted again for resumes parsed
+/// before text was kept. Unreadable resumes are recorded as skipped so they do not block
+/// the swap.
+async fn reembed_resume(
+    state: &AppState,
+    conn: &mut PgConnection,
+    run_id: i32,
+    resume: &ResumeEntry,
+) -> Result<Outcome> {
+    let content = if resume.status == "unreadable" {
+        Err("resume is unreadable".to_string())
+    } else {
+        resume_text(state, conn, resume).await.map(|stored| stored.text).map_err(|e| e.message)
+    };
+    let content = match content {
+        Ok(content) => content,
+        Err(reason) => {
+            tracing::warn!("skipping resume {}: {}", resume.id, reason);
+            EmbeddingMutator::new(conn).skip_resume(run_id, resume.id, &reason).await?;
+            return Ok(Outcome::Skipped);
+        }
+    };
+    match state.ai_client.index_document(&content).await {
+        Ok(embedding) => {
+            EmbeddingMutator::new(conn)
+                .stage_resume_embedding(resume.id, &embedding)
+                .await?;
+            Ok(Outcome::Staged)
+        }
+        Err(e) => {
+            tracing::error!("failed to embed resume {}: {}", resume.id, e.message);
+            Ok(Outcome::Failed)
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{
+    pkg::{
+        internal::adaptors::usage::{selectors::UsageSelector, spec::UsageSummary},
+        server::state::{AppState, GetTxn},
+    },
+    prelude::Result,
+};
+
+pub async fn report(evaluation: Option<i32>, project: Option<String>) -> Result<()> {
+    let state = AppState::connect().await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let (scope, rows): (String, Vec<UsageSummary>) = match (evaluation, project) {
+        (Some(evaluation_id), _) => (
+            format!("evaluation {}", evaluation_id),
+            UsageSelector::new(&mut tx)
+                .summary_for_evaluation(evaluation_id)
+                .await?,
+        ),
+        (None, Some(project_id)) => (
+            format!("project {}", &project_id),
+            UsageSelector::new(&mut tx)
+                .summary_for_project(&project_id)
+                .await?,
+        ),
+        (None, None) => ("all usage".into(), UsageSelector::new(&mut tx).summary_all().await?),
+    };
+    println!("AI usage for {}", scope);
+    println!(
+        "{:<12} {:<32} {:<10} {:>8} {:>12} {:>12} {:>10} {:>12}",
+        "provider", "model", "operation", "calls", "prompt", "completion", "avg ms", "cost usd"
+    );
+    for row in &rows {
+        println!(
+            "{:<12} {:<32} {:<10} {:>8} {:>12} {:>12} {:>10.0} {:>12.4}",
+            row.provider,
+            row.model,
+            row.operation,
+            row.calls,
+            row.prompt_tokens,
+            row.completion_tokens,
+            row.avg_latency_ms,
+            row.cost_usd
+        );
+    }
+    println!(
+        "total cost: {:.4} usd",
+        rows.iter().map(|r| r.cost_usd).sum::<f64>()
+    );
+    Ok(())
+}
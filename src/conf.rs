@@ -1,6 +1,8 @@
 use config::{Config, ConfigError, Environment};
 use lazy_static::lazy_static;
+use reqwest::Url;
 use serde::Deserialize;
+use standard_error::{Interpolate, StandardError};
 
 #[derive(Deserialize, Debug)]
 pub struct Settings {
@@ -22,8 +24,66 @@ pub struct Settings {
     pub smtp_port: u16,
     pub ai_endpoint: String,
     pub ai_provider: String,
+    /// chat model, or the deployment name for azure
     pub ai_model: String,
+    #[serde(default)]
+    pub ai_embedding_model: String,
+    /// only used by azure
+    #[serde(default)]
+    pub ai_api_version: String,
     pub ai_key: String,
+    /// json overrides for model prices in USD per million tokens,
+    /// e.g. {"gpt-4o-mini": {"prompt": 0.15, "completion": 0.6}}
+    #[serde(default)]
+    pub ai_price_table: String,
+    /// consecutive scoring failures before resumes are scored offline
+    #[serde(default = "default_ai_failure_threshold")]
+    pub ai_failure_threshold: u32,
+    /// resumes whose extracted text reads worse than this are not scored
+    #[serde(default = "default_min_extraction_quality")]
+    pub min_extraction_quality: f64,
+    /// tesseract binary used to read scanned resumes
+    #[serde(default = "default_ocr_command")]
+    pub ocr_command: String,
+    /// tesseract language codes joined with '+', e.g. eng+deu
+    #[serde(default = "default_ocr_languages")]
+    pub ocr_languages: String,
+    /// documents parsed at the same time, each on its own blocking thread or process
+    #[serde(default = "default_parse_workers")]
+    pub parse_workers: usize,
+    /// a document still parsing after this long is abandoned
+    #[serde(default = "default_parse_timeout_secs")]
+    pub parse_timeout_secs: u64,
+    /// PDFs with more pages than this are refused
+    #[serde(default = "default_max_document_pages")]
+    pub max_document_pages: usize,
+    /// address space of an isolated parser process, also the cap on unpacked DOCX/ODT size
+    #[serde(default = "default_parse_memory_mb")]
+    pub parse_memory_mb: u64,
+    /// `thread` parses in this process, `process` in a memory limited child process
+    #[serde(default = "default_parse_isolation")]
+    pub parse_isolation: String,
+    /// URL schemes job postings may be fetched over, comma separated
+    #[serde(default = "default_fetch_allowed_schemes")]
+    pub fetch_allowed_schemes: String,
+    /// redirects followed when fetching a job posting, each one checked again
+    #[serde(default = "default_fetch_max_redirects")]
+    pub fetch_max_redirects: usize,
+    /// a job posting fetch, redirects included, is abandoned after this long
+    #[serde(default = "default_fetch_timeout_secs")]
+    pub fetch_timeout_secs: u64,
+    /// job posting responses larger than this are refused
+    #[serde(default = "default_fetch_max_bytes")]
+    pub fetch_max_bytes: usize,
+    /// postings read from one careers page, feed or board in a bulk import
+    #[serde(default = "default_import_max_postings")]
+    pub import_max_postings: usize,
+    /// minutes between re-syncs of a job created from a URL, 0 turns re-sync off
+    #[serde(default = "default_job_sync_interval_mins")]
+    pub job_sync_interval_mins: u64,
+    /// jobs claimed per re-sync batch
+    #[serde(default = "default_job_sync_batch_size")]
+    pub job_sync_batch_size: i64,
     pub s3_endpoint: String,
     pub s3_access_key: String,
     pub s3_secret_key: String,
@@ -31,38 +91,225 @@ pub struct Settings {
     pub s3_region: String,
 }
 
+fn default_ai_failure_threshold() -> u32 {
+    3
+}
+
+fn default_min_extraction_quality() -> f64 {
+    0.5
+}
+
+fn default_ocr_command() -> String {
+    "tesseract".into()
+}
+
+fn default_ocr_languages() -> String {
+    "eng".into()
+}
+
+fn default_parse_workers() -> usize {
+    4
+}
+
+fn default_parse_timeout_secs() -> u64 {
+    120
+}
+
+fn default_max_document_pages() -> usize {
+    50
+}
+
+fn default_parse_memory_mb() -> u64 {
+    1024
+}
+
+fn default_parse_isolation() -> String {
+    "thread".into()
+}
+
+fn default_fetch_allowed_schemes() -> String {
+    "http,https".into()
+}
+
+fn default_fetch_max_redirects() -> usize {
+    5
+}
+
+fn default_fetch_timeout_secs() -> u64 {
+    15
+}
+
+fn default_fetch_max_bytes() -> usize {
+    5 * 1024 * 1024
+}
+
+fn default_import_max_postings() -> usize {
+    100
+}
+
+fn default_job_sync_interval_mins() -> u64 {
+    1440
+}
+
+fn default_job_sync_batch_size() -> i64 {
+    20
+}
+
 impl Settings {
     pub fn new() -> Result<Self, ConfigError> {
         let conf = Config::builder()
             .add_source(Environment::default())
             .build()?;
         let mut s: Settings = conf.try_deserialize()?;
-        match s.ai_provider.as_str() {
+        s.apply_ai_defaults();
+        Ok(s)
+    }
+
+    /// Fills in endpoint and models only where they were left empty, so explicit
+    /// values (remote ollama, vllm, lm studio...) are always respected
+    fn apply_ai_defaults(&mut self) {
+        let (endpoint, model, embedding_model) = match self.ai_provider.as_str() {
             "ollama" => {
-                s.ai_key = "ollama".into();
-                s.ai_endpoint = "http://localhost:11434/v1".into();
-                if s.ai_model.is_empty() {
-                    s.ai_model = "gemma3:12b".into();
+                if self.ai_key.is_empty() {
+                    self.ai_key = "ollama".into();
                 }
+                ("http://localhost:11434/v1", "gemma3:12b", "nomic-embed-text")
             }
-            "openai" => {
-                s.ai_endpoint = "https://api.openai.com/v1".into();
-                if s.ai_model.is_empty() {
-                    s.ai_model = "gpt-4o-mini".into();
+            "openai" => (
+                "https://api.openai.com/v1",
+                "gpt-4o-mini",
+                "text-embedding-3-large",
+            ),
+            "gemini" => (
+                "https://generativelanguage.googleapis.com/v1beta/openai",
+                "gemini-2.5-flash",
+                "text-embedding-004",
+            ),
+            "azure" => {
+                if self.ai_api_version.is_empty() {
+                    self.ai_api_version = "2024-10-21".into();
                 }
+                ("", "", "")
             }
-            "gemini" => {
-                s.ai_endpoint = "https://generativelanguage.googleapis.com/v1beta/openai".into();
-                if s.ai_model.is_empty() {
-                    s.ai_model = "gemini-2.5-flash".into();
-                }
+            _ => ("", "", ""),
+        };
+        if self.ai_endpoint.is_empty() {
+            self.ai_endpoint = endpoint.into();
+        }
+        if self.ai_model.is_empty() {
+            self.ai_model = model.into();
+        }
+        if self.ai_embedding_model.is_empty() {
+            self.ai_embedding_model = embedding_model.into();
+        }
+    }
+
+    pub fn validate_ai(&self) -> crate::prelude::Result<()> {
+        let invalid =
+            |reason: &str| StandardError::new("ERR-AI-006").interpolate_err(reason.to_string());
+        if !AI_PROVIDERS.contains(&self.ai_provider.as_str()) {
+            return Err(invalid(&format!(
+                "unknown AI_PROVIDER {}, expected one of {}",
+                &self.ai_provider,
+                AI_PROVIDERS.join(", ")
+            )));
+        }
+        match Url::parse(&self.ai_endpoint) {
+            Ok(url) if ["http", "https"].contains(&url.scheme()) => {}
+            _ => {
+                return Err(invalid(&format!(
+                    "AI_ENDPOINT must be an http(s) url, got {:?}",
+                    &self.ai_endpoint
+                )));
             }
-            _ => {}
         }
-        Ok(s)
+        if self.ai_model.is_empty() {
+            return Err(invalid("AI_MODEL is required for this provider"));
+        }
+        if self.ai_embedding_model.is_empty() {
+            return Err(invalid("AI_EMBEDDING_MODEL is required for this provider"));
+        }
+        if self.ai_key.is_empty() && !["ollama", "openai-compatible"].contains(&self.ai_provider.as_str()) {
+            return Err(invalid("AI_KEY is required for this provider"));
+        }
+        if self.ai_provider == "azure" && self.ai_api_version.is_empty() {
+            return Err(invalid("AI_API_VERSION is required for azure"));
+        }
+        Ok(())
     }
 }
 
+pub const AI_PROVIDERS: [&str; 5] = ["ollama", "openai", "gemini", "azure", "openai-compatible"];
+
 lazy_static! {
     pub static ref settings: Settings = Settings::new().expect("improperly configured");
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn ai_settings(provider: &str, endpoint: &str, model: &str) -> Settings {
+        let mut s: Settings = serde_json::from_value(json!({
+            "base_url": "http://localhost:3000",
+            "service_name": "resumeval",
+            "listen_port": "3000",
+            "database_url": "postgres://localhost/resumeval",
+            "database_schema": "public",
+            "database_pool_max_connections": 5,
+            "from_email": "noreply@example.com",
+            "smtp_user": "",
+            "smtp_pass": "",
+            "smtp_server": "localhost",
+            "smtp_port": 25,
+            "ai_endpoint": endpoint,
+            "ai_provider": provider,
+            "ai_model": model,
+            "ai_key": "secret",
+            "s3_endpoint": "http://localhost:9000",
+            "s3_access_key": "",
+            "s3_secret_key": "",
+            "s3_bucket_name": "resumes",
+            "s3_region": "us-east-1",
+        }))
+        .unwrap();
+        s.apply_ai_defaults();
+        s
+    }
+
+    #[test]
+    fn test_ai_defaults_are_applied() {
+        let s = ai_settings("openai", "", "");
+        assert_eq!(s.ai_endpoint, "https://api.openai.com/v1");
+        assert_eq!(s.ai_model, "gpt-4o-mini");
+        assert_eq!(s.ai_embedding_model, "text-embedding-3-large");
+        assert!(s.validate_ai().is_ok());
+
+        // explicit values are kept
+        let s = ai_settings("ollama", "http://gpu-box:11434/v1", "llama3");
+        assert_eq!(s.ai_endpoint, "http://gpu-box:11434/v1");
+        assert_eq!(s.ai_model, "llama3");
+        assert_eq!(s.ai_embedding_model, "nomic-embed-text");
+
+        let s = ai_settings("azure", "https://acme.openai.azure.com", "gpt-4o");
+        assert_eq!(s.ai_api_version, "2024-10-21");
+    }
+
+    #[test]
+    fn test_validate_ai_rejects_bad_configuration() {
+        let err = ai_settings("anthropicish", "https://example.com", "m")
+            .validate_ai()
+            .unwrap_err();
+        assert!(err.message.contains("unknown AI_PROVIDER"));
+
+        // azure has no default endpoint or deployment
+        let err = ai_settings("azure", "", "gpt-4o").validate_ai().unwrap_err();
+        assert!(err.message.contains("AI_ENDPOINT"));
+        let mut s = ai_settings("azure", "https://acme.openai.azure.com", "");
+        s.ai_embedding_model = "text-embedding-3-large".into();
+        let err = s.validate_ai().unwrap_err();
+        assert!(err.message.contains("AI_MODEL"));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod mutators;
+pub mod selectors;
+pub mod spec;
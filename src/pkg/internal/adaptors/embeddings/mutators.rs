@@ -0,0 +1,189 @@
+use crate::pkg::internal::adaptors::embeddings::spec::{EmbeddingMetadata, ReembedRun};
+use crate::prelude::Result;
+use pgvector::Vector;
+use sqlx::PgConnection;
+
+/// Re-embedding writes into `embedding_next` staging columns sized for the new model,
+/// the live `embedding` columns are only replaced once every row has been processed
+pub struct EmbeddingMutator<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> EmbeddingMutator<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        EmbeddingMutator { pool }
+    }
+
+    pub async fn set_metadata(
+        &mut self,
+        provider: &str,
+        model: &str,
+        dimensions: i32,
+    ) -> Result<EmbeddingMetadata> {
+        let row = sqlx::query_as::<_, EmbeddingMetadata>(
+            r#"
+            UPDATE embedding_metadata
+            SET provider = $1, model = $2, dimensions = $3, updated_at = CURRENT_TIMESTAMP
+            WHERE id
+            RETURNING provider, model, dimensions, updated_at
+            "#,
+        )
+        .bind(provider)
+        .bind(model)
+        .bind(dimensions)
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn start_run(
+        &mut self,
+        provider: &str,
+        model: &str,
+        dimensions: i32,
+        total: i32,
+    ) -> Result<ReembedRun> {
+        sqlx::query("UPDATE reembed_runs SET status = 'abandoned', finished_at = CURRENT_TIMESTAMP WHERE status = 'running'")
+            .execute(&mut *self.pool)
+            .await?;
+        for table in ["jobs", "resumes"] {
+            sqlx::query(&format!("ALTER TABLE {} DROP COLUMN IF EXISTS embedding_next", table))
+                .execute(&mut *self.pool)
+                .await?;
+            sqlx::query(&format!(
+                "ALTER TABLE {} ADD COLUMN embedding_next vector({})",
+                table, dimensions
+            ))
+            .execute(&mut *self.pool)
+            .await?;
+        }
+        let row = sqlx::query_as::<_, ReembedRun>(
+            r#"
+            INSERT INTO reembed_runs (provider, model, dimensions, total)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, provider, model, dimensions, status, total, processed, failed, last_job_id, last_resume_id, started_at, finished_at
+            "#,
+        )
+        .bind(provider)
+        .bind(model)
+        .bind(dimensions)
+        .bind(total)
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn stage_job_embedding(&mut self, job_id: i32, embedding: &Vector) -> Result<()> {
+        sqlx::query("UPDATE jobs SET embedding_next = $2 WHERE id = $1")
+            .bind(job_id)
+            .bind(embedding)
+            .execute(&mut *self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn stage_resume_embedding(&mut self, resume_id: i32, embedding: &Vector) -> Result<()> {
+        sqlx::query("UPDATE resumes SET embedding_next = $2 WHERE id = $1")
+            .bind(resume_id)
+            .bind(embedding)
+            .execute(&mut *self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Records a resume the run cannot embed, it no longer holds back the swap
+    pub async fn skip_resume(&mut self, run_id: i32, resume_id: i32, reason: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO reembed_skipped (run_id, resume_id, reason) VALUES ($1, $2, $3)
+            ON CONFLICT (run_id, resume_id) DO UPDATE SET reason = $3
+            "#,
+        )
+        .bind(run_id)
+        .bind(resume_id)
+        .bind(reason)
+        .execute(&mut *self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_progress(
+        &mut self,
+        run_id: i32,
+        processed: i32,
+        failed: i32,
+        last_job_id: i32,
+        last_resume_id: i32,
+    ) -> Result<ReembedRun> {
+        let row = sqlx::query_as::<_, ReembedRun>(
+            r#"
+            UPDATE reembed_runs
+            SET processed = processed + $2, failed = failed + $3, last_job_id = $4, last_resume_id = $5
+            WHERE id = $1
+            RETURNING id, provider, model, dimensions, status, total, processed, failed, last_job_id, last_resume_id, started_at, finished_at
+            "#,
+        )
+        .bind(run_id)
+        .bind(processed)
+        .bind(failed)
+        .bind(last_job_id)
+        .bind(last_resume_id)
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Sets the failure count to the rows that are still not staged after a retry pass
+    pub async fn set_failed(&mut self, run_id: i32, failed: i32) -> Result<ReembedRun> {
+        let row = sqlx::query_as::<_, ReembedRun>(
+            r#"
+            UPDATE reembed_runs SET failed = $2 WHERE id = $1
+            RETURNING id, provider, model, dimensions, status, total, processed, failed, last_job_id, last_resume_id, started_at, finished_at
+            "#,
+        )
+        .bind(run_id)
+        .bind(failed)
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Swaps the staging columns in and records the new model, meant to run in one transaction.
+    /// Both tables are locked first so no row can be added without a staged embedding; while
+    /// any row the run did not skip is still missing one the run stays `running` and `None`
+    /// is returned.
+    pub async fn finish_run(&mut self, run: &ReembedRun) -> Result<Option<EmbeddingMetadata>> {
+        sqlx::query("LOCK TABLE jobs, resumes IN ACCESS EXCLUSIVE MODE")
+            .execute(&mut *self.pool)
+            .await?;
+        let unstaged: i64 = sqlx::query_scalar(
+            "SELECT (SELECT COUNT(*) FROM jobs WHERE embedding_next IS NULL)
+                  + (SELECT COUNT(*) FROM resumes WHERE embedding_next IS NULL
+                       AND id NOT IN (SELECT resume_id FROM reembed_skipped WHERE run_id = $1))",
+        )
+        .bind(run.id)
+        .fetch_one(&mut *self.pool)
+        .await?;
+        if !run.ready_to_swap(unstaged) {
+            return Ok(None);
+        }
+        for table in ["jobs", "resumes"] {
+            sqlx::query(&format!("ALTER TABLE {} DROP COLUMN IF EXISTS embedding", table))
+                .execute(&mut *self.pool)
+                .await?;
+            sqlx::query(&format!(
+                "ALTER TABLE {} RENAME COLUMN embedding_next TO embedding",
+                table
+            ))
+            .execute(&mut *self.pool)
+            .await?;
+        }
+        sqlx::query("UPDATE reembed_runs SET status = 'completed', finished_at = CURRENT_TIMESTAMP WHERE id = $1")
+            .bind(run.id)
+            .execute(&mut *self.pool)
+            .await?;
+        self.set_metadata(&run.provider, &run.model, run.dimensions)
+            .await
+            .map(Some)
+    }
+}
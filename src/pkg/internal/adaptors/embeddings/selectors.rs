@@ -0,0 +1,70 @@
+use crate::pkg::internal::adaptors::{
+    embeddings::spec::{EmbeddingMetadata, ReembedRun},
+    jobs::spec::{JOB_COLUMNS, JobEntry},
+    resumes::spec::{RESUME_COLUMNS, ResumeEntry},
+};
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+pub struct EmbeddingSelector<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> EmbeddingSelector<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        EmbeddingSelector { pool }
+    }
+
+    pub async fn get_metadata(&mut self) -> Result<EmbeddingMetadata> {
+        let row = sqlx::query_as::<_, EmbeddingMetadata>(
+            "SELECT provider, model, dimensions, updated_at FROM embedding_metadata WHERE id",
+        )
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn count_skipped(&mut self, run_id: i32) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reembed_skipped WHERE run_id = $1")
+            .bind(run_id)
+            .fetch_one(&mut *self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn get_running(&mut self) -> Result<Option<ReembedRun>> {
+        let row = sqlx::query_as::<_, ReembedRun>(
+            "SELECT id, provider, model, dimensions, status, total, processed, failed, last_job_id, last_resume_id, started_at, finished_at
+             FROM reembed_runs WHERE status = 'running' ORDER BY id DESC LIMIT 1",
+        )
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Jobs without a staged embedding: failed in their batch or created after it
+    pub async fn get_unstaged_jobs(&mut self) -> Result<Vec<JobEntry>> {
+        let rows = sqlx::query_as::<_, JobEntry>(&format!(
+            "SELECT {} FROM jobs WHERE embedding_next IS NULL ORDER BY id",
+            JOB_COLUMNS
+        ))
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Resumes without a staged embedding that the run has not skipped
+    pub async fn get_unstaged_resumes(&mut self, run_id: i32) -> Result<Vec<ResumeEntry>> {
+        let rows = sqlx::query_as::<_, ResumeEntry>(&format!(
+            "SELECT {} FROM resumes
+             WHERE embedding_next IS NULL
+               AND id NOT IN (SELECT resume_id FROM reembed_skipped WHERE run_id = $1)
+             ORDER BY id",
+            RESUME_COLUMNS
+        ))
+        .bind(run_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
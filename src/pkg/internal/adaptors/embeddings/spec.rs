@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmbeddingMetadata {
+    pub provider: String,
+    pub model: String,
+    pub dimensions: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReembedRun {
+    pub id: i32,
+    pub provider: String,
+    pub model: String,
+    pub dimensions: i32,
+    pub status: String,
+    pub total: i32,
+    pub processed: i32,
+    pub failed: i32,
+    pub last_job_id: i32,
+    pub last_resume_id: i32,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl ReembedRun {
+    /// The live embeddings may only be replaced once every row has a staged one
+    pub fn ready_to_swap(&self, unstaged: i64) -> bool {
+        self.failed == 0 && unstaged == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_rows_block_the_swap() {
+        let mut run = ReembedRun {
+            id: 1,
+            provider: "openai".into(),
+            model: "text-embedding-3-large".into(),
+            dimensions: 3072,
+            status: "running".into(),
+            total: 10,
+            processed: 9,
+            failed: 1,
+            last_job_id: 4,
+            last_resume_id: 6,
+            started_at: Utc::now(),
+            finished_at: None,
+        };
+        assert!(!run.ready_to_swap(1));
+        // a retry pass staged the failed row, but one added meanwhile is still missing
+        run.failed = 0;
+        assert!(!run.ready_to_swap(1));
+        assert!(run.ready_to_swap(0));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::pkg::internal::adaptors::evaluations::spec::EvaluationEntry;
+use crate::pkg::internal::adaptors::evaluations::spec::{EVALUATION_COLUMNS, EvaluationEntry};
 use crate::prelude::Result;
 use sqlx::PgConnection;
 
@@ -13,20 +13,25 @@ impl<'a> EvaluationMutator<'a> {
 
     pub async fn create(
         &mut self,
+        project_id: &str,
         name: &str,
         job_id: i32,
         created_by: &str,
+        scorer: &str,
     ) -> Result<EvaluationEntry> {
-        let row = sqlx::query_as::<_, EvaluationEntry>(
+        let row = sqlx::query_as::<_, EvaluationEntry>(&format!(
             r#"
-            INSERT INTO evaluations (name, job_id, created_by, status, total_resumes, processed, accepted, rejected, pending)
-            VALUES ($1, $2, $3, 'pending', 0, 0, 0, 0, 0)
-            RETURNING id, name, job_id, created_by, status, total_resumes, processed, accepted, rejected, pending, created_at, updated_at
-            "#
-        )
+            INSERT INTO evaluations (name, job_id, created_by, status, scorer, total_resumes, processed, accepted, rejected, pending, project_id)
+            VALUES ($1, $2, $3, 'pending', $4, 0, 0, 0, 0, 0, $5)
+            RETURNING {}
+            "#,
+            EVALUATION_COLUMNS
+        ))
         .bind(name)
         .bind(job_id)
         .bind(created_by)
+        .bind(scorer)
+        .bind(project_id)
         .fetch_one(&mut *self.pool)
         .await?;
 
@@ -34,20 +39,21 @@ impl<'a> EvaluationMutator<'a> {
     }
 
     pub async fn update_counts(&mut self, evaluation_id: i32) -> Result<EvaluationEntry> {
-        let row = sqlx::query_as::<_, EvaluationEntry>(
+        let row = sqlx::query_as::<_, EvaluationEntry>(&format!(
             r#"
             UPDATE evaluations 
             SET 
                 total_resumes = (SELECT COUNT(*) FROM resumes WHERE evaluation_id = $1),
                 processed = (SELECT COUNT(*) FROM resumes WHERE evaluation_id = $1 AND status != 'pending'),
-                accepted = (SELECT COUNT(*) FROM resumes WHERE evaluation_id = $1 AND status = 'accepted'),
-                rejected = (SELECT COUNT(*) FROM resumes WHERE evaluation_id = $1 AND status = 'rejected'),
+                accepted = (SELECT COUNT(*) FROM resumes WHERE evaluation_id = $1 AND COALESCE(override_status, status) = 'accepted'),
+                rejected = (SELECT COUNT(*) FROM resumes WHERE evaluation_id = $1 AND COALESCE(override_status, status) = 'rejected'),
                 pending = (SELECT COUNT(*) FROM resumes WHERE evaluation_id = $1 AND status = 'pending'),
                 updated_at = CURRENT_TIMESTAMP
             WHERE id = $1
-            RETURNING id, name, job_id, created_by, status, total_resumes, processed, accepted, rejected, pending, created_at, updated_at
-            "#
-        )
+            RETURNING {}
+            "#,
+            EVALUATION_COLUMNS
+        ))
         .bind(evaluation_id)
         .fetch_one(&mut *self.pool)
         .await?;
@@ -60,14 +66,15 @@ impl<'a> EvaluationMutator<'a> {
         evaluation_id: i32,
         status: &str,
     ) -> Result<EvaluationEntry> {
-        let row = sqlx::query_as::<_, EvaluationEntry>(
+        let row = sqlx::query_as::<_, EvaluationEntry>(&format!(
             r#"
             update evaluations 
             set status = $2, updated_at = current_timestamp
             where id = $1
-            returning id, name, job_id, created_by, status, total_resumes, processed, accepted, rejected, pending, created_at, updated_at
-            "#
-        )
+            returning {}
+            "#,
+            EVALUATION_COLUMNS
+        ))
         .bind(evaluation_id)
         .bind(status)
         .fetch_one(&mut *self.pool)
@@ -80,14 +87,15 @@ impl<'a> EvaluationMutator<'a> {
         evaluation_id: i32,
         pending: i32
     ) -> Result<EvaluationEntry> {
-        let row = sqlx::query_as::<_, EvaluationEntry>(
+        let row = sqlx::query_as::<_, EvaluationEntry>(&format!(
             r#"
             update evaluations 
             set pending = $2, updated_at = current_timestamp
             where id = $1
-            returning id, name, job_id, created_by, status, total_resumes, processed, accepted, rejected, pending, created_at, updated_at
-            "#
-        )
+            returning {}
+            "#,
+            EVALUATION_COLUMNS
+        ))
         .bind(evaluation_id)
         .bind(pending)
         .fetch_one(&mut *self.pool)
@@ -96,3 +104,55 @@ impl<'a> EvaluationMutator<'a> {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::pkg::server::state::{GetTxn, db_pool};
+
+    #[tokio::test]
+    async fn test_update_counts_prefers_overridden_status() -> Result<()> {
+        let pool = Arc::new(db_pool()?);
+        // never committed, the fixtures roll back with the transaction
+        let mut tx = pool.begin_txn().await?;
+        let job_id: i32 = sqlx::query_scalar(
+            "INSERT INTO jobs (created_by, title, department, description, requirements)
+             VALUES ('u1', 'Engineer', 'Platform', 'Build', '- Rust') RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let evaluation_id: i32 = sqlx::query_scalar(
+            "INSERT INTO evaluations (name, job_id, created_by) VALUES ('counts', $1, 'u1') RETURNING id",
+        )
+        .bind(job_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        for (status, override_status) in [
+            ("accepted", Some("rejected")),
+            ("rejected", None),
+            ("rejected", Some("accepted")),
+            ("pending", None),
+        ] {
+            sqlx::query(
+                "INSERT INTO resumes (evaluation_id, filename, original_filename, file_path, file_size, mime_type, status, override_status)
+                 VALUES ($1, 'r.pdf', 'r.pdf', 'r.pdf', 1, 'application/pdf', $2, $3)",
+            )
+            .bind(evaluation_id)
+            .bind(status)
+            .bind(override_status)
+            .execute(&mut *tx)
+            .await?;
+        }
+        let evaluation = EvaluationMutator::new(&mut tx)
+            .update_counts(evaluation_id)
+            .await?;
+        assert_eq!(evaluation.total_resumes, 4);
+        assert_eq!(evaluation.processed, 3);
+        assert_eq!(evaluation.accepted, 1);
+        assert_eq!(evaluation.rejected, 2);
+        assert_eq!(evaluation.pending, 1);
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::pkg::internal::adaptors::evaluations::spec::EvaluationEntry;
+use crate::pkg::internal::adaptors::evaluations::spec::{EVALUATION_COLUMNS, EvaluationEntry};
 use crate::prelude::Result;
 use sqlx::PgConnection;
 
@@ -11,27 +11,29 @@ impl<'a> EvaluationSelector<'a> {
         EvaluationSelector { pool }
     }
 
-    pub async fn get_by_id(&mut self, id: i32) -> Result<Option<EvaluationEntry>> {
-        let row = sqlx::query_as::<_, EvaluationEntry>(
-            "SELECT id, name, job_id, created_by, status, total_resumes, processed, accepted, rejected, pending, created_at, updated_at 
-             FROM evaluations WHERE id = $1"
-        )
+    pub async fn get_by_id(&mut self, project_id: &str, id: i32) -> Result<Option<EvaluationEntry>> {
+        let row = sqlx::query_as::<_, EvaluationEntry>(&format!(
+            "SELECT {} FROM evaluations WHERE id = $1 AND project_id = $2",
+            EVALUATION_COLUMNS
+        ))
         .bind(id)
+        .bind(project_id)
         .fetch_optional(&mut *self.pool)
         .await?;
 
         Ok(row)
     }
 
-    pub async fn get_evaluations_for_user(
+    pub async fn get_evaluations_for_project(
         &mut self,
-        user_id: &str,
+        project_id: &str,
     ) -> Result<Vec<EvaluationEntry>> {
-        let rows = sqlx::query_as::<_, EvaluationEntry>(
-            "select id, name, job_id, created_by, status, total_resumes, processed, accepted, rejected, pending, created_at, updated_at from evaluations
-            where created_by = $1 order by created_at desc"
-        )
-            .bind(user_id)
+        let rows = sqlx::query_as::<_, EvaluationEntry>(&format!(
+            "select {} from evaluations
+            where project_id = $1 order by created_at desc",
+            EVALUATION_COLUMNS
+        ))
+            .bind(project_id)
             .fetch_all(&mut *self.pool)
             .await?;
         Ok(rows)
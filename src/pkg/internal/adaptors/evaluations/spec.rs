@@ -2,6 +2,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+/// Columns selected into `EvaluationEntry`, shared by every evaluation query
+pub const EVALUATION_COLUMNS: &str = "id, name, job_id, created_by, status, scorer, total_resumes, processed, accepted, rejected, pending, created_at, updated_at, project_id";
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct EvaluationEntry {
     pub id: i32,
@@ -9,6 +12,8 @@ pub struct EvaluationEntry {
     pub job_id: i32,
     pub created_by: String,
     pub status: String,
+    /// `llm`, or `heuristic` to score offline
+    pub scorer: String,
     pub total_resumes: i32,
     pub processed: i32,
     pub accepted: i32,
@@ -16,6 +21,7 @@ pub struct EvaluationEntry {
     pub pending: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub project_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
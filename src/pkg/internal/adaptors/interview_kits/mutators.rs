@@ -0,0 +1,57 @@
+use crate::pkg::internal::adaptors::interview_kits::spec::{InterviewKitEntry, InterviewQuestion};
+use crate::prelude::Result;
+use sqlx::{PgConnection, types::Json};
+
+pub struct InterviewKitMutator<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> InterviewKitMutator<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        InterviewKitMutator { pool }
+    }
+
+    /// Regenerating a kit for the same resume replaces the previous questions
+    pub async fn upsert(
+        &mut self,
+        resume_id: i32,
+        created_by: &str,
+        questions: Vec<InterviewQuestion>,
+    ) -> Result<InterviewKitEntry> {
+        let row = sqlx::query_as::<_, InterviewKitEntry>(
+            r#"
+            INSERT INTO interview_kits (resume_id, created_by, questions)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (resume_id) DO UPDATE
+            SET created_by = $2, questions = $3, updated_at = CURRENT_TIMESTAMP
+            RETURNING id, resume_id, created_by, questions, created_at, updated_at
+            "#,
+        )
+        .bind(resume_id)
+        .bind(created_by)
+        .bind(Json(questions))
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn update_questions(
+        &mut self,
+        resume_id: i32,
+        questions: Vec<InterviewQuestion>,
+    ) -> Result<Option<InterviewKitEntry>> {
+        let row = sqlx::query_as::<_, InterviewKitEntry>(
+            r#"
+            UPDATE interview_kits
+            SET questions = $2, updated_at = CURRENT_TIMESTAMP
+            WHERE resume_id = $1
+            RETURNING id, resume_id, created_by, questions, created_at, updated_at
+            "#,
+        )
+        .bind(resume_id)
+        .bind(Json(questions))
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+}
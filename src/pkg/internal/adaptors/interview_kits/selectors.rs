@@ -0,0 +1,24 @@
+use crate::pkg::internal::adaptors::interview_kits::spec::InterviewKitEntry;
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+pub struct InterviewKitSelector<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> InterviewKitSelector<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        InterviewKitSelector { pool }
+    }
+
+    pub async fn get_by_resume(&mut self, resume_id: i32) -> Result<Option<InterviewKitEntry>> {
+        let row = sqlx::query_as::<_, InterviewKitEntry>(
+            "SELECT id, resume_id, created_by, questions, created_at, updated_at
+             FROM interview_kits WHERE resume_id = $1",
+        )
+        .bind(resume_id)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+}
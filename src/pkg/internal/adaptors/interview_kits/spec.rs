@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, types::Json};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuestionCategory {
+    Technical,
+    Behavioural,
+    Gap,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterviewQuestion {
+    pub category: QuestionCategory,
+    pub question: String,
+    pub expected_answer: String,
+    pub rubric: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InterviewKitEntry {
+    pub id: i32,
+    pub resume_id: i32,
+    pub created_by: String,
+    pub questions: Json<Vec<InterviewQuestion>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl QuestionCategory {
+    fn heading(&self) -> &'static str {
+        match self {
+            QuestionCategory::Technical => "Technical",
+            QuestionCategory::Behavioural => "Behavioural",
+            QuestionCategory::Gap => "Gap probing",
+        }
+    }
+}
+
+impl InterviewKitEntry {
+    pub fn to_markdown(&self, candidate: &str, job_title: &str) -> String {
+        let mut out = format!("# Interview kit: {} for {}\n", candidate, job_title);
+        for category in [
+            QuestionCategory::Technical,
+            QuestionCategory::Behavioural,
+            QuestionCategory::Gap,
+        ] {
+            let questions: Vec<&InterviewQuestion> = self
+                .questions
+                .iter()
+                .filter(|q| q.category == category)
+                .collect();
+            if questions.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n## {}\n", category.heading()));
+            for (idx, q) in questions.iter().enumerate() {
+                out.push_str(&format!(
+                    "\n{}. {}\n   - Expected answer: {}\n   - Rubric: {}\n",
+                    idx + 1,
+                    q.question,
+                    q.expected_answer,
+                    q.rubric
+                ));
+            }
+        }
+        out
+    }
+}
@@ -1,4 +1,5 @@
-use crate::pkg::internal::adaptors::jobs::spec::JobEntry;
+use crate::pkg::internal::adaptors::jobs::spec::{JOB_COLUMNS, JobEntry};
+use crate::pkg::internal::ai::postings::Posting;
 use crate::pkg::server::handlers::jobs::{CreateJobInput, PatchJobInput};
 use crate::prelude::Result;
 use pgvector::Vector;
@@ -15,22 +16,25 @@ impl<'a> JobMutator<'a> {
 
     pub async fn create(
         &mut self, 
+        project_id: &str,
         create_by: &str, 
         job: CreateJobInput
     ) -> Result<JobEntry> {
-        let row = sqlx::query_as::<_, JobEntry>(
+        let row = sqlx::query_as::<_, JobEntry>(&format!(
             r#"
-            INSERT INTO jobs (title, created_by, department, description, requirements, url)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, created_by, title, department, description, requirements, url, created_at, updated_at
-            "#
-        )
+            INSERT INTO jobs (title, created_by, department, description, requirements, url, project_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
         .bind(&job.title)
         .bind(&create_by)
         .bind(&job.department)
         .bind(&job.description)
         .bind(&job.requirements)
         .bind(&job.url)
+        .bind(project_id)
         .fetch_one(&mut *self.pool)
         .await?;
         Ok(row)
@@ -41,14 +45,15 @@ impl<'a> JobMutator<'a> {
         job_id: i32,
         embedding: Vector
     ) -> Result<JobEntry> {
-        let row = sqlx::query_as::<_, JobEntry>(
+        let row = sqlx::query_as::<_, JobEntry>(&format!(
             r#"
             UPDATE jobs 
             SET embedding = $2, updated_at = CURRENT_TIMESTAMP
             WHERE id = $1
-            RETURNING id, created_by, title, department, description, requirements, url, created_at, updated_at
-            "#
-        )
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
         .bind(job_id)
         .bind(&embedding)
         .fetch_one(&mut *self.pool)
@@ -56,9 +61,9 @@ impl<'a> JobMutator<'a> {
         Ok(row)
     }
 
-    pub async fn update(&mut self, id: i32, job: PatchJobInput) -> Result<Option<JobEntry>> {
-        let mut query = String::from("UPDATE jobs SET updated_at = CURRENT_TIMESTAMP");
-        let mut param_count = 1;
+    pub async fn update(&mut self, project_id: &str, id: i32, job: PatchJobInput) -> Result<Option<JobEntry>> {
+        let mut query = String::from("UPDATE jobs SET updated_at = CURRENT_TIMESTAMP, edited_at = CURRENT_TIMESTAMP");
+        let mut param_count = 2;
 
         if job.title.is_some() {
             param_count += 1;
@@ -81,9 +86,9 @@ impl<'a> JobMutator<'a> {
             query.push_str(&format!(", url = ${}", param_count));
         }
 
-        query.push_str(" WHERE id = $1 RETURNING id, title, department, description, requirements, url, created_at, updated_at");
+        query.push_str(&format!(" WHERE id = $1 AND project_id = $2 RETURNING {}", JOB_COLUMNS));
 
-        let mut q = sqlx::query_as::<_, JobEntry>(&query).bind(id);
+        let mut q = sqlx::query_as::<_, JobEntry>(&query).bind(id).bind(project_id);
 
         if let Some(title) = job.title {
             q = q.bind(title);
@@ -104,20 +109,178 @@ impl<'a> JobMutator<'a> {
         Ok(row)
     }
 
-    pub async fn delete(&mut self, id: i32) -> Result<bool> {
-        let result = sqlx::query("DELETE FROM jobs WHERE id = $1")
+    /// Claims URL-sourced open jobs not synced within `interval_mins`, stamping them so
+    /// another instance does not sync them at the same time
+    pub async fn claim_due_for_sync(&mut self, interval_mins: i32, limit: i64) -> Result<Vec<JobEntry>> {
+        let rows = sqlx::query_as::<_, JobEntry>(&format!(
+            r#"
+            UPDATE jobs SET last_synced_at = CURRENT_TIMESTAMP
+            WHERE id IN (
+                SELECT id FROM jobs
+                WHERE url IS NOT NULL AND closed_at IS NULL
+                  AND (last_synced_at IS NULL OR last_synced_at < CURRENT_TIMESTAMP - make_interval(mins => $1))
+                ORDER BY last_synced_at NULLS FIRST, id
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(interval_mins)
+        .bind(limit)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn set_source_hash(&mut self, id: i32, source_hash: i64) -> Result<()> {
+        sqlx::query("UPDATE jobs SET source_hash = $2 WHERE id = $1")
+            .bind(id)
+            .bind(source_hash)
+            .execute(&mut *self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Keeps the job as it was in `job_revisions` and applies the re-synced posting.
+    /// `None` when the job was edited by hand in the meantime, the caller rolls back then.
+    pub async fn revise(
+        &mut self,
+        job: &JobEntry,
+        posting: &Posting,
+        changed: &[&str],
+        source_hash: i64,
+    ) -> Result<Option<JobEntry>> {
+        sqlx::query(
+            r#"
+            INSERT INTO job_revisions (job_id, title, department, description, requirements, changed)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(job.id)
+        .bind(&job.title)
+        .bind(&job.department)
+        .bind(&job.description)
+        .bind(&job.requirements)
+        .bind(changed)
+        .execute(&mut *self.pool)
+        .await?;
+        let row = sqlx::query_as::<_, JobEntry>(&format!(
+            r#"
+            UPDATE jobs
+            SET title = $2, department = $3, description = $4, requirements = $5,
+                source_hash = $6, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND edited_at IS NULL
+            RETURNING {}
+            "#,
+            JOB_COLUMNS
+        ))
+        .bind(job.id)
+        .bind(&posting.title)
+        .bind(&posting.department)
+        .bind(&posting.description)
+        .bind(&posting.requirements)
+        .bind(source_hash)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Marks a job whose posting page disappeared as closed
+    pub async fn close(&mut self, id: i32) -> Result<()> {
+        sqlx::query("UPDATE jobs SET closed_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE id = $1")
+            .bind(id)
+            .execute(&mut *self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Moves jobs without a project, only those `created_by` when given, into the project
+    /// along with their evaluations. Returns how many jobs and evaluations were moved.
+    pub async fn assign_orphans(&mut self, project_id: &str, created_by: Option<&str>) -> Result<(u64, u64)> {
+        let jobs: Vec<i32> = sqlx::query_scalar(
+            r#"
+            UPDATE jobs SET project_id = $1, updated_at = CURRENT_TIMESTAMP
+            WHERE project_id IS NULL AND ($2::VARCHAR IS NULL OR created_by = $2)
+            RETURNING id
+            "#,
+        )
+        .bind(project_id)
+        .bind(created_by)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        let evaluations = sqlx::query(
+            r#"
+            UPDATE evaluations SET project_id = $1
+            WHERE project_id IS NULL
+              AND (job_id = ANY($2) OR job_id IN (SELECT id FROM jobs WHERE project_id = $1))
+            "#,
+        )
+        .bind(project_id)
+        .bind(&jobs)
+        .execute(&mut *self.pool)
+        .await?;
+        Ok((jobs.len() as u64, evaluations.rows_affected()))
+    }
+
+    pub async fn delete(&mut self, project_id: &str, id: i32) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM jobs WHERE id = $1 AND project_id = $2")
             .bind(id)
+            .bind(project_id)
             .execute(&mut *self.pool)
             .await?;
 
         Ok(result.rows_affected() > 0)
     }
 
-    pub async fn delete_by_department(&mut self, department: &str) -> Result<u64> {
-        let result = sqlx::query("DELETE FROM jobs WHERE department = $1")
+    pub async fn delete_by_department(&mut self, project_id: &str, department: &str) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM jobs WHERE department = $1 AND project_id = $2")
             .bind(department)
+            .bind(project_id)
             .execute(&mut *self.pool)
             .await?;
         Ok(result.rows_affected())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::pkg::internal::adaptors::jobs::selectors::JobSelector;
+    use crate::pkg::server::state::{GetTxn, db_pool};
+
+    #[tokio::test]
+    async fn test_assign_orphans_moves_jobs_and_their_evaluations() -> Result<()> {
+        let pool = Arc::new(db_pool()?);
+        // never committed, the fixtures roll back with the transaction
+        let mut tx = pool.begin_txn().await?;
+        sqlx::query("INSERT INTO projects (project_id, name, description) VALUES ('test-p1', 'test-p1', 'one')")
+            .execute(&mut *tx)
+            .await?;
+        let mut job_ids = vec![];
+        for created_by in ["u1", "u2"] {
+            let job_id: i32 = sqlx::query_scalar(
+                "INSERT INTO jobs (created_by, title, department, description, requirements)
+                 VALUES ($1, 'Engineer', 'Platform', 'Build', '- Rust') RETURNING id",
+            )
+            .bind(created_by)
+            .fetch_one(&mut *tx)
+            .await?;
+            sqlx::query("INSERT INTO evaluations (name, job_id, created_by) VALUES ('orphan', $1, $2)")
+                .bind(job_id)
+                .bind(created_by)
+                .execute(&mut *tx)
+                .await?;
+            job_ids.push(job_id);
+        }
+
+        let moved = JobMutator::new(&mut tx).assign_orphans("test-p1", Some("u1")).await?;
+        assert_eq!(moved, (1, 1));
+        assert!(JobSelector::new(&mut tx).get_by_id("test-p1", job_ids[0]).await?.is_some());
+        assert!(JobSelector::new(&mut tx).get_by_id("test-p1", job_ids[1]).await?.is_none());
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use sqlx::PgConnection;
 
-use crate::pkg::internal::adaptors::jobs::spec::JobEntry;
+use crate::pkg::internal::adaptors::jobs::spec::{
+    JOB_COLUMNS, JOB_REVISION_COLUMNS, JobEntry, JobRevisionEntry,
+};
 use crate::prelude::Result;
 
 pub struct JobSelector<'a> {
@@ -12,37 +14,127 @@ impl<'a> JobSelector<'a> {
         JobSelector { pool }
     }
 
-    pub async fn get_by_id(&mut self, id: i32) -> Result<Option<JobEntry>> {
-        let row = sqlx::query_as::<_, JobEntry>(
-            "SELECT id, title, department, description, requirements, url, created_at, updated_at 
-             FROM jobs WHERE id = $1",
-        )
+    pub async fn get_by_id(&mut self, project_id: &str, id: i32) -> Result<Option<JobEntry>> {
+        let row = sqlx::query_as::<_, JobEntry>(&format!(
+            "SELECT {} FROM jobs WHERE id = $1 AND project_id = $2",
+            JOB_COLUMNS
+        ))
         .bind(id)
+        .bind(project_id)
         .fetch_optional(&mut *self.pool)
         .await?;
 
         Ok(row)
     }
 
-    pub async fn get_all(&mut self) -> Result<Vec<JobEntry>> {
-        let rows = sqlx::query_as::<_, JobEntry>(
-            "SELECT id, title, department, description, requirements, url, created_at, updated_at 
-             FROM jobs ORDER BY created_at DESC",
-        )
+    pub async fn get_all(&mut self, project_id: &str) -> Result<Vec<JobEntry>> {
+        let rows = sqlx::query_as::<_, JobEntry>(&format!(
+            "SELECT {} FROM jobs WHERE project_id = $1 ORDER BY created_at DESC",
+            JOB_COLUMNS
+        ))
+        .bind(project_id)
         .fetch_all(&mut *self.pool)
         .await?;
 
         Ok(rows)
     }
 
-    pub async fn get_by_department(&mut self, department: &str) -> Result<Vec<JobEntry>> {
-        let rows = sqlx::query_as::<_, JobEntry>(
-            "SELECT id, title, department, description, requirements, url, created_at, updated_at 
-             FROM jobs WHERE department = $1 ORDER BY created_at DESC",
-        )
+    pub async fn get_by_department(&mut self, project_id: &str, department: &str) -> Result<Vec<JobEntry>> {
+        let rows = sqlx::query_as::<_, JobEntry>(&format!(
+            "SELECT {} FROM jobs WHERE department = $1 AND project_id = $2 ORDER BY created_at DESC",
+            JOB_COLUMNS
+        ))
         .bind(department)
+        .bind(project_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_batch_after(&mut self, last_id: i32, limit: i64) -> Result<Vec<JobEntry>> {
+        let rows = sqlx::query_as::<_, JobEntry>(&format!(
+            "SELECT {} FROM jobs WHERE id > $1 ORDER BY id LIMIT $2",
+            JOB_COLUMNS
+        ))
+        .bind(last_id)
+        .bind(limit)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Which of these posting URLs already belong to a job of the project
+    pub async fn get_existing_urls(&mut self, project_id: &str, urls: &[String]) -> Result<Vec<String>> {
+        let rows: Vec<String> =
+            sqlx::query_scalar("SELECT url FROM jobs WHERE url = ANY($1) AND project_id = $2")
+                .bind(urls)
+                .bind(project_id)
+                .fetch_all(&mut *self.pool)
+                .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_revisions(&mut self, project_id: &str, job_id: i32) -> Result<Vec<JobRevisionEntry>> {
+        let rows = sqlx::query_as::<_, JobRevisionEntry>(&format!(
+            "SELECT {} FROM job_revisions
+             WHERE job_id = $1 AND job_id IN (SELECT id FROM jobs WHERE project_id = $2)
+             ORDER BY created_at DESC, id DESC",
+            JOB_REVISION_COLUMNS
+        ))
+        .bind(job_id)
+        .bind(project_id)
         .fetch_all(&mut *self.pool)
         .await?;
         Ok(rows)
     }
+
+    pub async fn count(&mut self) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs")
+            .fetch_one(&mut *self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// Jobs and evaluations that belong to no project and are visible to no one
+    pub async fn count_orphans(&mut self) -> Result<(i64, i64)> {
+        let counts: (i64, i64) = sqlx::query_as(
+            "SELECT (SELECT COUNT(*) FROM jobs WHERE project_id IS NULL),
+                    (SELECT COUNT(*) FROM evaluations WHERE project_id IS NULL)",
+        )
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::pkg::server::state::{GetTxn, db_pool};
+
+    #[tokio::test]
+    async fn test_jobs_are_not_visible_across_projects() -> Result<()> {
+        let pool = Arc::new(db_pool()?);
+        // never committed, the fixtures roll back with the transaction
+        let mut tx = pool.begin_txn().await?;
+        sqlx::query(
+            "INSERT INTO projects (project_id, name, description)
+             VALUES ('test-p1', 'test-p1', 'one'), ('test-p2', 'test-p2', 'two')",
+        )
+        .execute(&mut *tx)
+        .await?;
+        let job_id: i32 = sqlx::query_scalar(
+            "INSERT INTO jobs (created_by, title, department, description, requirements, project_id)
+             VALUES ('u1', 'Engineer', 'Platform', 'Build', '- Rust', 'test-p1') RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        assert!(JobSelector::new(&mut tx).get_by_id("test-p1", job_id).await?.is_some());
+        assert!(JobSelector::new(&mut tx).get_by_id("test-p2", job_id).await?.is_none());
+        assert!(JobSelector::new(&mut tx).get_all("test-p2").await?.is_empty());
+        Ok(())
+    }
 }
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+pub const JOB_COLUMNS: &str = "id, title, department, description, requirements, url, created_at, updated_at, closed_at, last_synced_at, source_hash, project_id, edited_at";
+
+pub const JOB_REVISION_COLUMNS: &str = "id, job_id, title, department, description, requirements, changed, created_at";
+
 #[derive(Serialize, Deserialize, FromRow)]
 pub struct JobEntry {
     pub id: i32,
@@ -11,4 +15,27 @@ pub struct JobEntry {
     pub url: Option<String>,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    /// set when the posting page disappeared
+    pub closed_at: Option<chrono::NaiveDateTime>,
+    pub last_synced_at: Option<chrono::NaiveDateTime>,
+    /// hash of the page as last read, so unchanged pages are not extracted again
+    #[serde(skip)]
+    pub source_hash: Option<i64>,
+    pub project_id: Option<String>,
+    /// last edit through the API; the re-sync does not overwrite hand-edited jobs
+    pub edited_at: Option<chrono::NaiveDateTime>,
+}
+
+/// A job as it was before a re-sync changed it
+#[derive(Serialize, Deserialize, FromRow)]
+pub struct JobRevisionEntry {
+    pub id: i32,
+    pub job_id: i32,
+    pub title: String,
+    pub department: String,
+    pub description: String,
+    pub requirements: String,
+    /// fields the re-sync changed
+    pub changed: Vec<String>,
+    pub created_at: chrono::NaiveDateTime,
 }
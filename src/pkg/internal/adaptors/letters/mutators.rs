@@ -0,0 +1,144 @@
+use crate::pkg::internal::adaptors::letters::spec::{CandidateLetterEntry, Tone};
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+pub struct CreateLetterData {
+    pub resume_id: i32,
+    pub tone: Tone,
+    pub candidate_email: Option<String>,
+    pub subject: String,
+    pub body: String,
+}
+
+pub struct CandidateLetterMutator<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> CandidateLetterMutator<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        CandidateLetterMutator { pool }
+    }
+
+    /// `None` when the letter was already sent, a sent letter is never redrafted
+    pub async fn upsert_draft(
+        &mut self,
+        created_by: &str,
+        letter: CreateLetterData,
+    ) -> Result<Option<CandidateLetterEntry>> {
+        let row = sqlx::query_as::<_, CandidateLetterEntry>(
+            r#"
+            INSERT INTO candidate_letters (resume_id, created_by, tone, candidate_email, subject, body, status)
+            VALUES ($1, $2, $3, $4, $5, $6, 'draft')
+            ON CONFLICT (resume_id) DO UPDATE
+            SET created_by = $2, tone = $3, candidate_email = $4, subject = $5, body = $6,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE candidate_letters.status <> 'sent'
+            RETURNING id, resume_id, created_by, tone, candidate_email, subject, body, status, sent_at, created_at, updated_at
+            "#,
+        )
+        .bind(letter.resume_id)
+        .bind(created_by)
+        .bind(letter.tone.as_str())
+        .bind(&letter.candidate_email)
+        .bind(&letter.subject)
+        .bind(&letter.body)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn update(
+        &mut self,
+        resume_id: i32,
+        candidate_email: Option<&str>,
+        subject: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<Option<CandidateLetterEntry>> {
+        let row = sqlx::query_as::<_, CandidateLetterEntry>(
+            r#"
+            UPDATE candidate_letters
+            SET candidate_email = COALESCE($2, candidate_email),
+                subject = COALESCE($3, subject),
+                body = COALESCE($4, body),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE resume_id = $1
+            RETURNING id, resume_id, created_by, tone, candidate_email, subject, body, status, sent_at, created_at, updated_at
+            "#,
+        )
+        .bind(resume_id)
+        .bind(candidate_email)
+        .bind(subject)
+        .bind(body)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// `None` when the letter was already sent
+    pub async fn mark_sent(&mut self, resume_id: i32) -> Result<Option<CandidateLetterEntry>> {
+        let row = sqlx::query_as::<_, CandidateLetterEntry>(
+            r#"
+            UPDATE candidate_letters
+            SET status = 'sent', sent_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+            WHERE resume_id = $1 AND status <> 'sent'
+            RETURNING id, resume_id, created_by, tone, candidate_email, subject, body, status, sent_at, created_at, updated_at
+            "#,
+        )
+        .bind(resume_id)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::pkg::server::state::{GetTxn, db_pool};
+
+    fn letter(resume_id: i32) -> CreateLetterData {
+        CreateLetterData {
+            resume_id,
+            tone: Tone::Neutral,
+            candidate_email: Some("jane@example.com".into()),
+            subject: "Your application".into(),
+            body: "Thank you".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sent_letters_are_not_redrafted() -> Result<()> {
+        let pool = Arc::new(db_pool()?);
+        // never committed, the fixtures roll back with the transaction
+        let mut tx = pool.begin_txn().await?;
+        let job_id: i32 = sqlx::query_scalar(
+            "INSERT INTO jobs (created_by, title, department, description, requirements)
+             VALUES ('u1', 'Engineer', 'Platform', 'Build', '- Rust') RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let evaluation_id: i32 = sqlx::query_scalar(
+            "INSERT INTO evaluations (name, job_id, created_by) VALUES ('letters', $1, 'u1') RETURNING id",
+        )
+        .bind(job_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let resume_id: i32 = sqlx::query_scalar(
+            "INSERT INTO resumes (evaluation_id, filename, original_filename, file_path, file_size, mime_type, status)
+             VALUES ($1, 'r.pdf', 'r.pdf', 'r.pdf', 1, 'application/pdf', 'accepted') RETURNING id",
+        )
+        .bind(evaluation_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut letters = CandidateLetterMutator::new(&mut tx);
+        assert!(letters.upsert_draft("u1", letter(resume_id)).await?.is_some());
+        assert!(letters.upsert_draft("u1", letter(resume_id)).await?.is_some());
+        assert!(letters.mark_sent(resume_id).await?.is_some());
+        assert!(letters.upsert_draft("u1", letter(resume_id)).await?.is_none());
+        assert!(letters.mark_sent(resume_id).await?.is_none());
+        Ok(())
+    }
+}
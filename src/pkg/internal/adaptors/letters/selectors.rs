@@ -0,0 +1,36 @@
+use crate::pkg::internal::adaptors::letters::spec::CandidateLetterEntry;
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+pub struct CandidateLetterSelector<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> CandidateLetterSelector<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        CandidateLetterSelector { pool }
+    }
+
+    pub async fn get_by_resume(&mut self, resume_id: i32) -> Result<Option<CandidateLetterEntry>> {
+        let row = sqlx::query_as::<_, CandidateLetterEntry>(
+            "SELECT id, resume_id, created_by, tone, candidate_email, subject, body, status, sent_at, created_at, updated_at
+             FROM candidate_letters WHERE resume_id = $1",
+        )
+        .bind(resume_id)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn get_by_evaluation(&mut self, evaluation_id: i32) -> Result<Vec<CandidateLetterEntry>> {
+        let rows = sqlx::query_as::<_, CandidateLetterEntry>(
+            "SELECT l.id, l.resume_id, l.created_by, l.tone, l.candidate_email, l.subject, l.body, l.status, l.sent_at, l.created_at, l.updated_at
+             FROM candidate_letters l JOIN resumes r ON r.id = l.resume_id
+             WHERE r.evaluation_id = $1 ORDER BY l.resume_id",
+        )
+        .bind(evaluation_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tone {
+    Warm,
+    #[default]
+    Neutral,
+    Formal,
+}
+
+impl Tone {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Tone::Warm => "warm",
+            Tone::Neutral => "neutral",
+            Tone::Formal => "formal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CandidateLetterEntry {
+    pub id: i32,
+    pub resume_id: i32,
+    pub created_by: String,
+    pub tone: String,
+    pub candidate_email: Option<String>,
+    pub subject: String,
+    pub body: String,
+    pub status: String,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -1,3 +1,10 @@
+pub mod embeddings;
 pub mod evaluations;
+pub mod interview_kits;
 pub mod jobs;
+pub mod letters;
+pub mod prompts;
+pub mod rankings;
+pub mod resume_texts;
 pub mod resumes;
+pub mod usage;
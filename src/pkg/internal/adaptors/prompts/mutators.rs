@@ -0,0 +1,112 @@
+use crate::pkg::internal::adaptors::prompts::spec::{
+    PromptComparisonEntry, PromptComparisonResult, PromptTemplateEntry,
+};
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+pub struct PromptMutator<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> PromptMutator<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        PromptMutator { pool }
+    }
+
+    /// Versions are append-only; every save becomes the next version for the project
+    pub async fn create_version(
+        &mut self,
+        project_id: &str,
+        name: &str,
+        body: &str,
+        created_by: &str,
+    ) -> Result<PromptTemplateEntry> {
+        let row = sqlx::query_as::<_, PromptTemplateEntry>(
+            r#"
+            INSERT INTO prompt_templates (project_id, name, version, body, created_by)
+            VALUES (
+                $1, $2,
+                (SELECT COALESCE(MAX(version), 0) + 1 FROM prompt_templates WHERE project_id = $1 AND name = $2),
+                $3, $4
+            )
+            RETURNING id, project_id, name, version, body, created_by, created_at
+            "#,
+        )
+        .bind(project_id)
+        .bind(name)
+        .bind(body)
+        .bind(created_by)
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn create_comparison(
+        &mut self,
+        evaluation_id: i32,
+        name: &str,
+        version_a: &str,
+        version_b: &str,
+        created_by: &str,
+    ) -> Result<PromptComparisonEntry> {
+        let row = sqlx::query_as::<_, PromptComparisonEntry>(
+            r#"
+            INSERT INTO prompt_comparisons (evaluation_id, name, version_a, version_b, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, evaluation_id, name, version_a, version_b, status, error, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(evaluation_id)
+        .bind(name)
+        .bind(version_a)
+        .bind(version_b)
+        .bind(created_by)
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn add_comparison_result(
+        &mut self,
+        comparison_id: i32,
+        result: &PromptComparisonResult,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO prompt_comparison_results
+                (comparison_id, resume_id, status_a, score_a, feedback_a, status_b, score_b, feedback_b, error)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (comparison_id, resume_id) DO NOTHING
+            "#,
+        )
+        .bind(comparison_id)
+        .bind(result.resume_id)
+        .bind(&result.status_a)
+        .bind(&result.score_a)
+        .bind(&result.feedback_a)
+        .bind(&result.status_b)
+        .bind(&result.score_b)
+        .bind(&result.feedback_b)
+        .bind(&result.error)
+        .execute(&mut *self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn finish_comparison(
+        &mut self,
+        comparison_id: i32,
+        status: &str,
+        error: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE prompt_comparisons SET status = $2, error = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        )
+        .bind(comparison_id)
+        .bind(status)
+        .bind(error)
+        .execute(&mut *self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use crate::pkg::internal::adaptors::prompts::spec::{
+    PromptComparisonEntry, PromptComparisonResult, PromptTemplateEntry,
+};
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+pub struct PromptSelector<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> PromptSelector<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        PromptSelector { pool }
+    }
+
+    pub async fn list_for_project(&mut self, project_id: &str) -> Result<Vec<PromptTemplateEntry>> {
+        let rows = sqlx::query_as::<_, PromptTemplateEntry>(
+            "SELECT id, project_id, name, version, body, created_by, created_at
+             FROM prompt_templates WHERE project_id = $1 ORDER BY name, version DESC",
+        )
+        .bind(project_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Latest version when `version` is None
+    pub async fn get_version(
+        &mut self,
+        project_id: &str,
+        name: &str,
+        version: Option<i32>,
+    ) -> Result<Option<PromptTemplateEntry>> {
+        let row = sqlx::query_as::<_, PromptTemplateEntry>(
+            "SELECT id, project_id, name, version, body, created_by, created_at
+             FROM prompt_templates
+             WHERE project_id = $1 AND name = $2 AND ($3::INTEGER IS NULL OR version = $3)
+             ORDER BY version DESC LIMIT 1",
+        )
+        .bind(project_id)
+        .bind(name)
+        .bind(version)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn get_comparison(&mut self, comparison_id: i32) -> Result<Option<PromptComparisonEntry>> {
+        let row = sqlx::query_as::<_, PromptComparisonEntry>(
+            "SELECT id, evaluation_id, name, version_a, version_b, status, error, created_by, created_at, updated_at
+             FROM prompt_comparisons WHERE id = $1",
+        )
+        .bind(comparison_id)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn get_comparison_results(
+        &mut self,
+        comparison_id: i32,
+    ) -> Result<Vec<PromptComparisonResult>> {
+        let rows = sqlx::query_as::<_, PromptComparisonResult>(
+            "SELECT resume_id, status_a, score_a, feedback_a, status_b, score_b, feedback_b, error
+             FROM prompt_comparison_results WHERE comparison_id = $1 ORDER BY resume_id",
+        )
+        .bind(comparison_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PromptTemplateEntry {
+    pub id: i32,
+    pub project_id: String,
+    pub name: String,
+    pub version: i32,
+    pub body: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PromptComparisonEntry {
+    pub id: i32,
+    pub evaluation_id: i32,
+    pub name: String,
+    pub version_a: String,
+    pub version_b: String,
+    pub status: String,
+    pub error: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PromptComparisonResult {
+    pub resume_id: i32,
+    pub status_a: Option<String>,
+    pub score_a: Option<String>,
+    pub feedback_a: Option<String>,
+    pub status_b: Option<String>,
+    pub score_b: Option<String>,
+    pub feedback_b: Option<String>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,167 @@
+use crate::pkg::internal::adaptors::rankings::spec::{RankingComparison, RankingRun};
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+pub struct RankingMutator<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> RankingMutator<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        RankingMutator { pool }
+    }
+
+    /// Fails a running run that made no progress for `stale_after_mins`, then starts a new
+    /// one. `None` when another run of the evaluation is still running.
+    pub async fn start_run(
+        &mut self,
+        evaluation_id: i32,
+        top_n: i32,
+        budget: i32,
+        created_by: &str,
+        stale_after_mins: i32,
+    ) -> Result<Option<RankingRun>> {
+        sqlx::query(
+            r#"
+            UPDATE ranking_runs SET status = 'failed', updated_at = CURRENT_TIMESTAMP
+            WHERE evaluation_id = $1 AND status = 'running'
+              AND updated_at < CURRENT_TIMESTAMP - make_interval(mins => $2)
+            "#,
+        )
+        .bind(evaluation_id)
+        .bind(stale_after_mins)
+        .execute(&mut *self.pool)
+        .await?;
+        let row = sqlx::query_as::<_, RankingRun>(
+            r#"
+            INSERT INTO ranking_runs (evaluation_id, top_n, budget, created_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (evaluation_id) WHERE status = 'running' DO NOTHING
+            RETURNING id, evaluation_id, top_n, budget, comparisons, failed, status, created_by, created_at, updated_at
+            "#,
+        )
+        .bind(evaluation_id)
+        .bind(top_n)
+        .bind(budget)
+        .bind(created_by)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn add_comparison(&mut self, run_id: i32, comparison: &RankingComparison) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO ranking_comparisons (run_id, resume_a, resume_b, winner, reason)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(run_id)
+        .bind(comparison.resume_a)
+        .bind(comparison.resume_b)
+        .bind(comparison.winner)
+        .bind(&comparison.reason)
+        .execute(&mut *self.pool)
+        .await?;
+        sqlx::query(
+            "UPDATE ranking_runs SET comparisons = comparisons + 1, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        )
+        .bind(run_id)
+        .execute(&mut *self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_failure(&mut self, run_id: i32) -> Result<()> {
+        sqlx::query(
+            "UPDATE ranking_runs SET failed = failed + 1, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        )
+        .bind(run_id)
+        .execute(&mut *self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Replaces the ranks of the whole evaluation; resumes outside `ranks` are unranked
+    pub async fn finish_run(
+        &mut self,
+        run: &RankingRun,
+        status: &str,
+        ranks: &[(i32, f64)],
+    ) -> Result<()> {
+        if !ranks.is_empty() {
+            sqlx::query("UPDATE resumes SET rank = NULL, rank_score = NULL WHERE evaluation_id = $1")
+                .bind(run.evaluation_id)
+                .execute(&mut *self.pool)
+                .await?;
+            for (position, (resume_id, strength)) in ranks.iter().enumerate() {
+                sqlx::query("UPDATE resumes SET rank = $2, rank_score = $3 WHERE id = $1")
+                    .bind(resume_id)
+                    .bind(position as i32 + 1)
+                    .bind(strength)
+                    .execute(&mut *self.pool)
+                    .await?;
+            }
+        }
+        sqlx::query(
+            "UPDATE ranking_runs SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        )
+        .bind(run.id)
+        .bind(status)
+        .execute(&mut *self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::pkg::server::state::{GetTxn, db_pool};
+
+    #[tokio::test]
+    async fn test_one_running_run_per_evaluation() -> Result<()> {
+        let pool = Arc::new(db_pool()?);
+        // never committed, the fixtures roll back with the transaction
+        let mut tx = pool.begin_txn().await?;
+        let job_id: i32 = sqlx::query_scalar(
+            "INSERT INTO jobs (created_by, title, department, description, requirements)
+             VALUES ('u1', 'Engineer', 'Platform', 'Build', '- Rust') RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let evaluation_id: i32 = sqlx::query_scalar(
+            "INSERT INTO evaluations (name, job_id, created_by) VALUES ('rank', $1, 'u1') RETURNING id",
+        )
+        .bind(job_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let first = RankingMutator::new(&mut tx)
+            .start_run(evaluation_id, 5, 10, "u1", 15)
+            .await?
+            .expect("first run starts");
+        let second = RankingMutator::new(&mut tx)
+            .start_run(evaluation_id, 5, 10, "u1", 15)
+            .await?;
+        assert!(second.is_none());
+
+        // a run that stopped making progress no longer blocks new ones
+        sqlx::query("UPDATE ranking_runs SET updated_at = CURRENT_TIMESTAMP - INTERVAL '1 hour' WHERE id = $1")
+            .bind(first.id)
+            .execute(&mut *tx)
+            .await?;
+        let third = RankingMutator::new(&mut tx)
+            .start_run(evaluation_id, 5, 10, "u1", 15)
+            .await?;
+        assert!(third.is_some());
+        let status: String = sqlx::query_scalar("SELECT status FROM ranking_runs WHERE id = $1")
+            .bind(first.id)
+            .fetch_one(&mut *tx)
+            .await?;
+        assert_eq!(status, "failed");
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::pkg::internal::adaptors::rankings::spec::{RankingComparison, RankingRun};
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+pub struct RankingSelector<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> RankingSelector<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        RankingSelector { pool }
+    }
+
+    pub async fn get_latest_run(&mut self, evaluation_id: i32) -> Result<Option<RankingRun>> {
+        let row = sqlx::query_as::<_, RankingRun>(
+            "SELECT id, evaluation_id, top_n, budget, comparisons, failed, status, created_by, created_at, updated_at
+             FROM ranking_runs WHERE evaluation_id = $1 ORDER BY id DESC LIMIT 1",
+        )
+        .bind(evaluation_id)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn get_comparisons(&mut self, run_id: i32) -> Result<Vec<RankingComparison>> {
+        let rows = sqlx::query_as::<_, RankingComparison>(
+            "SELECT resume_a, resume_b, winner, reason FROM ranking_comparisons WHERE run_id = $1 ORDER BY id",
+        )
+        .bind(run_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RankingRun {
+    pub id: i32,
+    pub evaluation_id: i32,
+    pub top_n: i32,
+    pub budget: i32,
+    pub comparisons: i32,
+    pub failed: i32,
+    pub status: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RankingComparison {
+    pub resume_a: i32,
+    pub resume_b: i32,
+    pub winner: i32,
+    pub reason: Option<String>,
+}
@@ -0,0 +1,57 @@
+use crate::pkg::internal::adaptors::resume_texts::spec::{RESUME_TEXT_COLUMNS, ResumeTextEntry};
+use crate::pkg::internal::ai::sections::Section;
+use crate::prelude::Result;
+use sqlx::{PgConnection, types::Json};
+
+pub struct ResumeTextMutator<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> ResumeTextMutator<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        ResumeTextMutator { pool }
+    }
+
+    /// Stores the latest extraction, replacing text from an earlier parse
+    pub async fn upsert(
+        &mut self,
+        resume_id: i32,
+        text: &str,
+        sections: &[Section],
+    ) -> Result<ResumeTextEntry> {
+        let row = sqlx::query_as::<_, ResumeTextEntry>(&format!(
+            r#"
+            INSERT INTO resume_texts (resume_id, text, sections)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (resume_id) DO UPDATE
+            SET text = $2, sections = $3, translated_text = NULL, translated_to = NULL, updated_at = CURRENT_TIMESTAMP
+            RETURNING {}
+            "#,
+            RESUME_TEXT_COLUMNS
+        ))
+        .bind(resume_id)
+        .bind(text)
+        .bind(Json(sections))
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn record_translation(
+        &mut self,
+        resume_id: i32,
+        language: &str,
+        translated_text: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE resume_texts SET translated_text = $2, translated_to = $3, updated_at = CURRENT_TIMESTAMP
+             WHERE resume_id = $1",
+        )
+        .bind(resume_id)
+        .bind(translated_text)
+        .bind(language)
+        .execute(&mut *self.pool)
+        .await?;
+        Ok(())
+    }
+}
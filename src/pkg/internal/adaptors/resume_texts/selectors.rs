@@ -0,0 +1,24 @@
+use crate::pkg::internal::adaptors::resume_texts::spec::{RESUME_TEXT_COLUMNS, ResumeTextEntry};
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+pub struct ResumeTextSelector<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> ResumeTextSelector<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        ResumeTextSelector { pool }
+    }
+
+    pub async fn get_by_resume(&mut self, resume_id: i32) -> Result<Option<ResumeTextEntry>> {
+        let row = sqlx::query_as::<_, ResumeTextEntry>(&format!(
+            "SELECT {} FROM resume_texts WHERE resume_id = $1",
+            RESUME_TEXT_COLUMNS
+        ))
+        .bind(resume_id)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+}
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, types::Json};
+
+use crate::pkg::internal::ai::sections::Section;
+
+pub const RESUME_TEXT_COLUMNS: &str = "id, resume_id, text, sections, translated_text, translated_to, created_at, updated_at";
+
+/// Text extracted from a resume file, kept so it is not downloaded and parsed again
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ResumeTextEntry {
+    pub id: i32,
+    pub resume_id: i32,
+    pub text: String,
+    pub sections: Json<Vec<Section>>,
+    /// the text as scored when it was translated to the job's language first
+    pub translated_text: Option<String>,
+    /// ISO 639-3 code of `translated_text`
+    pub translated_to: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -1,6 +1,9 @@
-use crate::{pkg::internal::adaptors::resumes::spec::ResumeEntry, prelude::Result};
+use crate::{
+    pkg::internal::adaptors::resumes::spec::{RESUME_COLUMNS, ResumeEntry},
+    prelude::Result,
+};
 use pgvector::Vector;
-use sqlx::PgConnection;
+use sqlx::{PgConnection, types::Json};
 
 pub struct CreateResumeData {
     pub evaluation_id: i32,
@@ -9,6 +12,8 @@ pub struct CreateResumeData {
     pub file_path: String,
     pub file_size: i64,
     pub mime_type: String,
+    /// MIME type sniffed from the file content
+    pub detected_type: String,
 }
 
 pub struct ResumeMutator<'a> {
@@ -28,7 +33,7 @@ impl<'a> ResumeMutator<'a> {
             return Ok(Vec::new());
         }
         let mut query_builder = sqlx::QueryBuilder::new(
-            "INSERT INTO resumes (evaluation_id, filename, original_filename, file_path, file_size, mime_type, status) ",
+            "INSERT INTO resumes (evaluation_id, filename, original_filename, file_path, file_size, mime_type, detected_type, status) ",
         );
         query_builder.push_values(resumes, |mut b, resume| {
             b.push_bind(resume.evaluation_id)
@@ -37,11 +42,10 @@ impl<'a> ResumeMutator<'a> {
                 .push_bind(resume.file_path)
                 .push_bind(resume.file_size)
                 .push_bind(resume.mime_type)
+                .push_bind(resume.detected_type)
                 .push_bind("pending");
         });
-        query_builder.push(
-            " RETURNING id, evaluation_id, filename, original_filename, file_path, file_size, mime_type, status, score, feedback, created_at, updated_at"
-        );
+        query_builder.push(format!(" RETURNING {}", RESUME_COLUMNS));
         let rows = query_builder
             .build_query_as::<ResumeEntry>()
             .fetch_all(&mut *self.pool)
@@ -54,14 +58,15 @@ impl<'a> ResumeMutator<'a> {
         resume_id: i32,
         embedding: Vector
     ) -> Result<ResumeEntry> {
-        let row = sqlx::query_as::<_, ResumeEntry>(
+        let row = sqlx::query_as::<_, ResumeEntry>(&format!(
             r#"
             UPDATE resumes 
             SET embedding = $2, status='indexed', updated_at = CURRENT_TIMESTAMP
             WHERE id = $1
-            RETURNING id, evaluation_id, filename, original_filename, file_path, file_size, mime_type, status, score, feedback, created_at, updated_at
-            "#
-        )
+            RETURNING {}
+            "#,
+            RESUME_COLUMNS
+        ))
         .bind(resume_id)
         .bind(&embedding)
         .fetch_one(&mut *self.pool)
@@ -69,6 +74,59 @@ impl<'a> ResumeMutator<'a> {
         Ok(row)
     }
 
+    /// Unlike `add_embedding`, leaves the scoring status alone
+    pub async fn store_embedding(&mut self, resume_id: i32, embedding: &Vector) -> Result<()> {
+        sqlx::query("UPDATE resumes SET embedding = $2 WHERE id = $1")
+            .bind(resume_id)
+            .bind(embedding)
+            .execute(&mut *self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Records how a verdict was produced: the scorer, its breakdown and any few-shot examples
+    pub async fn record_scoring(
+        &mut self,
+        resume_id: i32,
+        scorer: &str,
+        breakdown: Option<&serde_json::Value>,
+        example_ids: &[i32],
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE resumes SET scorer = $2, score_breakdown = $3, few_shot_examples = $4 WHERE id = $1",
+        )
+        .bind(resume_id)
+        .bind(scorer)
+        .bind(breakdown.map(Json))
+        .bind(example_ids)
+        .execute(&mut *self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_extraction(
+        &mut self,
+        resume_id: i32,
+        quality: f64,
+        ocr_confidence: Option<f64>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE resumes SET extraction_quality = $2, ocr_confidence = $3 WHERE id = $1")
+            .bind(resume_id)
+            .bind(quality)
+            .bind(ocr_confidence)
+            .execute(&mut *self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn record_language(&mut self, resume_id: i32, language: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE resumes SET language = $2 WHERE id = $1")
+            .bind(resume_id)
+            .bind(language)
+            .execute(&mut *self.pool)
+            .await?;
+        Ok(())
+    }
 
     pub async fn add_verdict(
         &mut self,
@@ -76,22 +134,69 @@ impl<'a> ResumeMutator<'a> {
         status: &str,
         score: Option<&str>,
         feedback: Option<&str>,
+        prompt_version: Option<&str>,
     ) -> Result<ResumeEntry> {
-        let row = sqlx::query_as::<_, ResumeEntry>(
+        let row = sqlx::query_as::<_, ResumeEntry>(&format!(
             r#"
             UPDATE resumes 
-            SET status = $2, score = $3, feedback = $4, updated_at = CURRENT_TIMESTAMP
+            SET status = $2, score = $3, feedback = $4, prompt_version = $5, updated_at = CURRENT_TIMESTAMP
             WHERE id = $1
-            RETURNING id, evaluation_id, filename, original_filename, file_path, file_size, mime_type, status, score, feedback, created_at, updated_at
-            "#
-        )
+            RETURNING {}
+            "#,
+            RESUME_COLUMNS
+        ))
         .bind(resume_id)
         .bind(status)
         .bind(score)
         .bind(feedback)
+        .bind(prompt_version)
         .fetch_one(&mut *self.pool)
         .await?;
 
         Ok(row)
     }
+
+    /// Records a recruiter verdict next to the AI one and appends it to the audit trail
+    pub async fn override_verdict(
+        &mut self,
+        resume: &ResumeEntry,
+        changed_by: &str,
+        status: &str,
+        score: Option<&str>,
+        reason: &str,
+    ) -> Result<ResumeEntry> {
+        sqlx::query(
+            r#"
+            INSERT INTO verdict_overrides (resume_id, changed_by, previous_status, previous_score, new_status, new_score, reason)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(resume.id)
+        .bind(changed_by)
+        .bind(resume.effective_status())
+        .bind(resume.effective_score())
+        .bind(status)
+        .bind(score)
+        .bind(reason)
+        .execute(&mut *self.pool)
+        .await?;
+        let row = sqlx::query_as::<_, ResumeEntry>(&format!(
+            r#"
+            UPDATE resumes
+            SET override_status = $2, override_score = $3, override_reason = $4,
+                overridden_by = $5, overridden_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            RESUME_COLUMNS
+        ))
+        .bind(resume.id)
+        .bind(status)
+        .bind(score)
+        .bind(reason)
+        .bind(changed_by)
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
 }
@@ -1,5 +1,8 @@
-use crate::pkg::internal::adaptors::resumes::spec::ResumeEntry;
+use crate::pkg::internal::adaptors::resumes::spec::{
+    EXAMPLE_EXCERPT_CHARS, FewShotExample, RESUME_COLUMNS, ResumeEntry, VerdictOverrideEntry,
+};
 use crate::prelude::Result;
+use pgvector::Vector;
 use sqlx::PgConnection;
 
 pub struct ResumeSelector<'a> {
@@ -11,34 +14,207 @@ impl<'a> ResumeSelector<'a> {
         ResumeSelector { pool }
     }
   
-    pub async fn get_resume_by_id(
+    pub async fn get_resumes_by_evaluation(
+        &mut self,
+        evaluation_id: i32,
+    ) -> Result<Vec<ResumeEntry>> {
+        let rows = sqlx::query_as::<_, ResumeEntry>(&format!(
+            "SELECT {} FROM resumes WHERE evaluation_id = $1 ORDER BY created_at DESC",
+            RESUME_COLUMNS
+        ))
+        .bind(evaluation_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    pub async fn get_resume_in_project(
         &mut self,
         resume_id: i32,
-    ) -> Result<ResumeEntry> {
-        let rows = sqlx::query_as::<_, ResumeEntry>(
-            "SELECT id, evaluation_id, filename, original_filename, file_path, file_size, 
-                    mime_type, status, score, feedback, created_at, updated_at 
-             FROM resumes WHERE id = $1 ORDER BY created_at DESC",
+        project_id: &str,
+    ) -> Result<Option<ResumeEntry>> {
+        let row = sqlx::query_as::<_, ResumeEntry>(&format!(
+            "SELECT {} FROM resumes
+             WHERE id = $1 AND evaluation_id IN (SELECT id FROM evaluations WHERE project_id = $2)",
+            RESUME_COLUMNS
+        ))
+        .bind(resume_id)
+        .bind(project_id)
+        .fetch_optional(&mut *self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn get_batch_after(&mut self, last_id: i32, limit: i64) -> Result<Vec<ResumeEntry>> {
+        let rows = sqlx::query_as::<_, ResumeEntry>(&format!(
+            "SELECT {} FROM resumes WHERE id > $1 ORDER BY id LIMIT $2",
+            RESUME_COLUMNS
+        ))
+        .bind(last_id)
+        .bind(limit)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn count(&mut self) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM resumes")
+            .fetch_one(&mut *self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// Accepted resumes by effective verdict, best score first
+    pub async fn get_shortlist(&mut self, evaluation_id: i32, limit: i64) -> Result<Vec<ResumeEntry>> {
+        let rows = sqlx::query_as::<_, ResumeEntry>(&format!(
+            r#"SELECT {} FROM resumes
+             WHERE evaluation_id = $1 AND COALESCE(override_status, status) = 'accepted'
+             ORDER BY CASE WHEN COALESCE(override_score, score) ~ '^[0-9]+(\.[0-9]+)?$'
+                           THEN COALESCE(override_score, score)::NUMERIC END DESC NULLS LAST, id
+             LIMIT $2"#,
+            RESUME_COLUMNS
+        ))
+        .bind(evaluation_id)
+        .bind(limit)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_overrides(&mut self, resume_id: i32) -> Result<Vec<VerdictOverrideEntry>> {
+        let rows = sqlx::query_as::<_, VerdictOverrideEntry>(
+            "SELECT id, resume_id, changed_by, previous_status, previous_score, new_status, new_score, reason, created_at
+             FROM verdict_overrides WHERE resume_id = $1 ORDER BY created_at DESC",
         )
         .bind(resume_id)
-        .fetch_one(&mut *self.pool)
+        .fetch_all(&mut *self.pool)
         .await?;
         Ok(rows)
     }
 
-    pub async fn get_resumes_by_evaluation(
+    /// Whether any overridden verdict could serve as an example, checked before embedding
+    pub async fn has_override_examples(
         &mut self,
-        evaluation_id: i32,
-    ) -> Result<Vec<ResumeEntry>> {
-        let rows = sqlx::query_as::<_, ResumeEntry>(
-            "SELECT id, evaluation_id, filename, original_filename, file_path, file_size, 
-                    mime_type, status, score, feedback, created_at, updated_at 
-             FROM resumes WHERE evaluation_id = $1 ORDER BY created_at DESC",
+        resume_id: i32,
+        job_id: i32,
+        department: &str,
+        project_id: Option<&str>,
+    ) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                SELECT 1 FROM resumes r
+                JOIN evaluations e ON e.id = r.evaluation_id
+                JOIN jobs j ON j.id = e.job_id
+                WHERE r.override_status IS NOT NULL AND r.embedding IS NOT NULL AND r.id <> $1
+                  AND (e.job_id = $2 OR j.department = $3) AND e.project_id = $4
+             )",
         )
-        .bind(evaluation_id)
-        .fetch_all(&mut *self.pool)
+        .bind(resume_id)
+        .bind(job_id)
+        .bind(department)
+        .bind(project_id)
+        .fetch_one(&mut *self.pool)
         .await?;
+        Ok(exists)
+    }
+
+    pub async fn has_embedding(&mut self, resume_id: i32) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM resumes WHERE id = $1 AND embedding IS NOT NULL)",
+        )
+        .bind(resume_id)
+        .fetch_one(&mut *self.pool)
+        .await?;
+        Ok(exists)
+    }
 
+    /// Overridden verdicts on the same job or department, nearest to `embedding` first
+    pub async fn get_override_examples(
+        &mut self,
+        resume_id: i32,
+        job_id: i32,
+        department: &str,
+        project_id: Option<&str>,
+        embedding: &Vector,
+        limit: i64,
+    ) -> Result<Vec<FewShotExample>> {
+        let rows = sqlx::query_as::<_, FewShotExample>(&format!(
+            "SELECT r.id AS resume_id, r.status, r.score, r.feedback, r.override_status,
+                    r.override_score, r.override_reason,
+                    LEFT(COALESCE(t.translated_text, t.text), {}) AS excerpt,
+                    (1 - (r.embedding <=> $1))::FLOAT8 AS similarity
+             FROM resumes r
+             JOIN evaluations e ON e.id = r.evaluation_id
+             JOIN jobs j ON j.id = e.job_id
+             LEFT JOIN resume_texts t ON t.resume_id = r.id
+             WHERE r.override_status IS NOT NULL AND r.embedding IS NOT NULL AND r.id <> $2
+               AND (e.job_id = $3 OR j.department = $4) AND e.project_id = $6
+             ORDER BY r.embedding <=> $1
+             LIMIT $5",
+            EXAMPLE_EXCERPT_CHARS
+        ))
+        .bind(embedding)
+        .bind(resume_id)
+        .bind(job_id)
+        .bind(department)
+        .bind(limit)
+        .bind(project_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
         Ok(rows)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::pkg::server::state::{GetTxn, db_pool};
+
+    #[tokio::test]
+    async fn test_resumes_are_not_visible_across_projects() -> Result<()> {
+        let pool = Arc::new(db_pool()?);
+        // never committed, the fixtures roll back with the transaction
+        let mut tx = pool.begin_txn().await?;
+        sqlx::query(
+            "INSERT INTO projects (project_id, name, description)
+             VALUES ('test-p1', 'test-p1', 'one'), ('test-p2', 'test-p2', 'two')",
+        )
+        .execute(&mut *tx)
+        .await?;
+        let job_id: i32 = sqlx::query_scalar(
+            "INSERT INTO jobs (created_by, title, department, description, requirements, project_id)
+             VALUES ('u1', 'Engineer', 'Platform', 'Build', '- Rust', 'test-p1') RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let mut resume_ids = vec![];
+        // the second evaluation has no project, like rows the tenancy backfill could not place
+        for project_id in [Some("test-p1"), None] {
+            let evaluation_id: i32 = sqlx::query_scalar(
+                "INSERT INTO evaluations (name, job_id, created_by, project_id)
+                 VALUES ('tenancy', $1, 'u1', $2) RETURNING id",
+            )
+            .bind(job_id)
+            .bind(project_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            let resume_id: i32 = sqlx::query_scalar(
+                "INSERT INTO resumes (evaluation_id, filename, original_filename, file_path, file_size, mime_type, status)
+                 VALUES ($1, 'r.pdf', 'r.pdf', 'r.pdf', 1, 'application/pdf', 'pending') RETURNING id",
+            )
+            .bind(evaluation_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            resume_ids.push(resume_id);
+        }
+
+        let mut selector = ResumeSelector::new(&mut tx);
+        assert!(selector.get_resume_in_project(resume_ids[0], "test-p1").await?.is_some());
+        assert!(selector.get_resume_in_project(resume_ids[0], "test-p2").await?.is_none());
+        assert!(selector.get_resume_in_project(resume_ids[1], "test-p1").await?.is_none());
+        Ok(())
+    }
+}
@@ -1,6 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, types::Json};
+
+/// How much of an example resume's text is shown next to its corrected verdict
+pub const EXAMPLE_EXCERPT_CHARS: i32 = 1200;
+
+/// Columns selected into `ResumeEntry`, shared by every resume query
+pub const RESUME_COLUMNS: &str = "id, evaluation_id, filename, original_filename, file_path, file_size, mime_type, detected_type, status, score, feedback, prompt_version, override_status, override_score, override_reason, overridden_by, overridden_at, few_shot_examples, rank, rank_score, scorer, score_breakdown, extraction_quality, ocr_confidence, language, created_at, updated_at";
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ResumeEntry {
@@ -11,9 +17,124 @@ pub struct ResumeEntry {
     pub file_path: String,
     pub file_size: i64,
     pub mime_type: String,
+    /// MIME type sniffed from the content at upload, unset for older uploads
+    pub detected_type: Option<String>,
     pub status: String,
     pub score: Option<String>, 
     pub feedback: Option<String>,
+    pub prompt_version: Option<String>,
+    pub override_status: Option<String>,
+    pub override_score: Option<String>,
+    pub override_reason: Option<String>,
+    pub overridden_by: Option<String>,
+    pub overridden_at: Option<DateTime<Utc>>,
+    /// Overridden resumes shown to the model as examples when this one was scored
+    pub few_shot_examples: Option<Vec<i32>>,
+    /// Position from the latest pairwise ranking run, 1 is best
+    pub rank: Option<i32>,
+    pub rank_score: Option<f64>,
+    /// `llm` or `heuristic`, with the keyword breakdown when scored offline
+    pub scorer: Option<String>,
+    pub score_breakdown: Option<Json<serde_json::Value>>,
+    /// 0 to 1, how readable the extracted text looked
+    pub extraction_quality: Option<f64>,
+    /// 0 to 1, mean OCR word confidence when the text was read from images
+    pub ocr_confidence: Option<f64>,
+    /// ISO 639-3 code detected from the extracted text, e.g. `deu`
+    pub language: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+impl ResumeEntry {
+    /// The recruiter's verdict when one exists, otherwise the AI's
+    pub fn effective_status(&self) -> &str {
+        self.override_status.as_deref().unwrap_or(&self.status)
+    }
+
+    pub fn effective_score(&self) -> Option<&str> {
+        self.override_score.as_deref().or(self.score.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct VerdictOverrideEntry {
+    pub id: i32,
+    pub resume_id: i32,
+    pub changed_by: String,
+    pub previous_status: String,
+    pub previous_score: Option<String>,
+    pub new_status: String,
+    pub new_score: Option<String>,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A past recruiter correction, close to the resume being scored
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FewShotExample {
+    pub resume_id: i32,
+    pub status: String,
+    pub score: Option<String>,
+    pub feedback: Option<String>,
+    pub override_status: String,
+    pub override_score: Option<String>,
+    pub override_reason: Option<String>,
+    /// start of the example's extracted text, so the model can relate the verdicts to it
+    pub excerpt: Option<String>,
+    pub similarity: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scored(status: &str, score: Option<&str>) -> ResumeEntry {
+        let now = Utc::now();
+        ResumeEntry {
+            id: 1,
+            evaluation_id: 1,
+            filename: "resume.pdf".into(),
+            original_filename: "resume.pdf".into(),
+            file_path: "1/resume.pdf".into(),
+            file_size: 1024,
+            mime_type: "application/pdf".into(),
+            detected_type: None,
+            status: status.into(),
+            score: score.map(Into::into),
+            feedback: None,
+            prompt_version: None,
+            override_status: None,
+            override_score: None,
+            override_reason: None,
+            overridden_by: None,
+            overridden_at: None,
+            few_shot_examples: None,
+            rank: None,
+            rank_score: None,
+            scorer: None,
+            score_breakdown: None,
+            extraction_quality: None,
+            ocr_confidence: None,
+            language: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_override_falls_back_to_ai_verdict() {
+        let mut resume = scored("accepted", Some("82"));
+        assert_eq!(resume.effective_status(), "accepted");
+        assert_eq!(resume.effective_score(), Some("82"));
+
+        // a status-only override keeps the AI score
+        resume.override_status = Some("rejected".into());
+        assert_eq!(resume.effective_status(), "rejected");
+        assert_eq!(resume.effective_score(), Some("82"));
+
+        resume.override_score = Some("40".into());
+        assert_eq!(resume.effective_score(), Some("40"));
+        assert_eq!(scored("pending", None).effective_score(), None);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::pkg::internal::adaptors::usage::spec::UsageRecord;
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+pub struct UsageMutator<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> UsageMutator<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        UsageMutator { pool }
+    }
+
+    pub async fn bulk_create(&mut self, records: Vec<UsageRecord>) -> Result<u64> {
+        if records.is_empty() {
+            return Ok(0);
+        }
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO ai_usage (provider, model, operation, prompt_tokens, completion_tokens, latency_ms, cost_usd, project_id, evaluation_id, resume_id) ",
+        );
+        query_builder.push_values(records, |mut b, record| {
+            b.push_bind(record.provider)
+                .push_bind(record.model)
+                .push_bind(record.operation)
+                .push_bind(record.prompt_tokens)
+                .push_bind(record.completion_tokens)
+                .push_bind(record.latency_ms)
+                .push_bind(record.cost_usd)
+                .push_bind(record.project_id)
+                .push_bind(record.evaluation_id)
+                .push_bind(record.resume_id);
+        });
+        let result = query_builder.build().execute(&mut *self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
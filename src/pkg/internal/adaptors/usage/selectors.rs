@@ -0,0 +1,68 @@
+use crate::pkg::internal::adaptors::usage::spec::{ResumeUsage, UsageSummary};
+use crate::prelude::Result;
+use sqlx::PgConnection;
+
+const SUMMARY_COLUMNS: &str = "provider, model, operation, COUNT(*) AS calls,
+    COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+    COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens,
+    COALESCE(AVG(latency_ms), 0)::DOUBLE PRECISION AS avg_latency_ms,
+    COALESCE(SUM(cost_usd), 0)::DOUBLE PRECISION AS cost_usd";
+
+pub struct UsageSelector<'a> {
+    pool: &'a mut PgConnection,
+}
+
+impl<'a> UsageSelector<'a> {
+    pub fn new(pool: &'a mut PgConnection) -> Self {
+        UsageSelector { pool }
+    }
+
+    pub async fn summary_for_evaluation(&mut self, evaluation_id: i32) -> Result<Vec<UsageSummary>> {
+        let rows = sqlx::query_as::<_, UsageSummary>(&format!(
+            "SELECT {} FROM ai_usage WHERE evaluation_id = $1
+             GROUP BY provider, model, operation ORDER BY cost_usd DESC",
+            SUMMARY_COLUMNS
+        ))
+        .bind(evaluation_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn summary_for_project(&mut self, project_id: &str) -> Result<Vec<UsageSummary>> {
+        let rows = sqlx::query_as::<_, UsageSummary>(&format!(
+            "SELECT {} FROM ai_usage WHERE project_id = $1
+             GROUP BY provider, model, operation ORDER BY cost_usd DESC",
+            SUMMARY_COLUMNS
+        ))
+        .bind(project_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn summary_all(&mut self) -> Result<Vec<UsageSummary>> {
+        let rows = sqlx::query_as::<_, UsageSummary>(&format!(
+            "SELECT {} FROM ai_usage GROUP BY provider, model, operation ORDER BY cost_usd DESC",
+            SUMMARY_COLUMNS
+        ))
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn per_resume(&mut self, evaluation_id: i32) -> Result<Vec<ResumeUsage>> {
+        let rows = sqlx::query_as::<_, ResumeUsage>(
+            "SELECT resume_id, COUNT(*) AS calls,
+                    COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+                    COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens,
+                    COALESCE(SUM(cost_usd), 0)::DOUBLE PRECISION AS cost_usd
+             FROM ai_usage WHERE evaluation_id = $1 AND resume_id IS NOT NULL
+             GROUP BY resume_id ORDER BY resume_id",
+        )
+        .bind(evaluation_id)
+        .fetch_all(&mut *self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
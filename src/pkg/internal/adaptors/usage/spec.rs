@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub provider: String,
+    pub model: String,
+    pub operation: String,
+    pub prompt_tokens: i32,
+    pub completion_tokens: i32,
+    pub latency_ms: i32,
+    pub cost_usd: f64,
+    pub project_id: Option<String>,
+    pub evaluation_id: Option<i32>,
+    pub resume_id: Option<i32>,
+}
+
+/// Totals for one provider/model pair, which is what managers compare on
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UsageSummary {
+    pub provider: String,
+    pub model: String,
+    pub operation: String,
+    pub calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub avg_latency_ms: f64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ResumeUsage {
+    pub resume_id: i32,
+    pub calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+}
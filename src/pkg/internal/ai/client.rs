@@ -0,0 +1,90 @@
+use std::pin::Pin;
+
+use ai::{
+    chat_completions::{
+        ChatCompletion, ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse,
+    },
+    clients::{azure_openai, openai},
+    embeddings::{Embeddings, EmbeddingsRequest, EmbeddingsResponse},
+};
+use futures::Stream;
+use standard_error::{Interpolate, StandardError};
+
+use crate::{conf::Settings, prelude::Result};
+
+/// Providers speaking the OpenAI chat/embeddings protocol, Azure differs only in
+/// url layout (deployments) and auth header, so it gets its own client
+#[derive(Debug, Clone)]
+pub enum AIClient {
+    OpenAI(openai::Client),
+    Azure(azure_openai::Client),
+}
+
+impl AIClient {
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        settings.validate_ai()?;
+        let client = match settings.ai_provider.as_str() {
+            "azure" => AIClient::Azure(
+                azure_openai::ClientBuilder::default()
+                    .auth(azure_openai::Auth::ApiKey(settings.ai_key.clone().into()))
+                    .base_url(settings.ai_endpoint.trim_end_matches('/'))
+                    .api_version(settings.ai_api_version.clone())
+                    .build()
+                    .map_err(|e| StandardError::new("ERR-AI-000").interpolate_err(e.to_string()))?,
+            ),
+            "gemini" => AIClient::OpenAI(
+                openai::ClientBuilder::default()
+                    .http_client(
+                        reqwest::Client::builder()
+                            .http1_title_case_headers()
+                            .build()?,
+                    )
+                    .api_key(settings.ai_key.clone().into())
+                    .base_url(settings.ai_endpoint.trim_end_matches('/').into())
+                    .build()
+                    .map_err(|e| StandardError::new("ERR-AI-000").interpolate_err(e.to_string()))?,
+            ),
+            _ => AIClient::OpenAI(
+                openai::Client::from_url(&settings.ai_key, &settings.ai_endpoint)
+                    .map_err(|e| StandardError::new("ERR-AI-000").interpolate_err(e.to_string()))?,
+            ),
+        };
+        Ok(client)
+    }
+}
+
+#[async_trait::async_trait]
+impl ChatCompletion for AIClient {
+    async fn chat_completions(
+        &self,
+        request: &ChatCompletionRequest,
+    ) -> ai::Result<ChatCompletionResponse> {
+        match self {
+            AIClient::OpenAI(client) => client.chat_completions(request).await,
+            AIClient::Azure(client) => client.chat_completions(request).await,
+        }
+    }
+
+    async fn stream_chat_completions(
+        &self,
+        request: &ChatCompletionRequest,
+    ) -> ai::Result<Pin<Box<dyn Stream<Item = ai::Result<ChatCompletionChunk>> + Send>>> {
+        match self {
+            AIClient::OpenAI(client) => client.stream_chat_completions(request).await,
+            AIClient::Azure(client) => client.stream_chat_completions(request).await,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Embeddings for AIClient {
+    async fn create_embeddings(
+        &self,
+        request: &EmbeddingsRequest,
+    ) -> ai::Result<EmbeddingsResponse> {
+        match self {
+            AIClient::OpenAI(client) => client.create_embeddings(request).await,
+            AIClient::Azure(client) => client.create_embeddings(request).await,
+        }
+    }
+}
@@ -1,37 +1,242 @@
-use reqwest::Url;
+//! Fetching job postings from user supplied URLs.
+//!
+//! URLs come straight from users, so every hop is checked before it is requested: the
+//! scheme must be allowed and the host must only resolve to public addresses. The
+//! connection is pinned to the addresses that were checked, so DNS cannot be switched
+//! to an internal address in between. Redirects are followed by hand for the same
+//! reason, and responses are capped in size and time.
+
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use reqwest::{Url, header::LOCATION, redirect::Policy};
 use scraper::{Html, Selector};
+use standard_error::{Interpolate, StandardError};
+use url::Host;
+
+use crate::{
+    conf::settings,
+    pkg::internal::ai::{
+        postings::{Posting, extract_posting},
+        read::decode_text,
+        readability::main_content,
+    },
+    prelude::Result,
+};
+
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36";
+
+pub enum Fetched {
+    /// read straight from the page by a structured extractor
+    Posting {
+        posting: Posting,
+        extractor: &'static str,
+    },
+    /// page text for the LLM, or the input itself when it is not a reachable url
+    Text(String),
+}
+
+enum FetchError {
+    /// the fetch policy forbids the request
+    Refused(StandardError),
+    /// the page answered 404 or 410
+    Gone(reqwest::StatusCode),
+    /// the page could not be loaded
+    Failed(String),
+}
+
+impl<E: std::error::Error> From<E> for FetchError {
+    fn from(e: E) -> Self {
+        FetchError::Failed(e.to_string())
+    }
+}
+
+fn refuse(url: &Url, reason: impl Display) -> FetchError {
+    tracing::warn!("refused fetch of {}: {}", url, reason);
+    FetchError::Refused(StandardError::new("ERR-FETCH-001").interpolate_err(reason.to_string()))
+}
 
-pub async fn process(url: &str) -> String {
+/// Fails only when the URL is refused by the fetch policy; pages that cannot be
+/// loaded fall back to using the input as posting text
+pub async fn process(url: &str) -> Result<Fetched> {
     if Url::parse(url).is_err() {
-        return url.into();
+        return Ok(Fetched::Text(url.into()));
     }
-    match fetch_and_extract(&url).await {
-        Ok(text) => text,
-        Err(_) => url.into(),
+    match fetch_posting(url).await {
+        Err(e) if e.err_code == "ERR-FETCH-001" => Err(e),
+        Err(e) => {
+            tracing::debug!("could not fetch {}: {}", url, e.message);
+            Ok(Fetched::Text(url.into()))
+        }
+        fetched => fetched,
     }
 }
 
-async fn fetch_and_extract(url: &str) -> Result<String, Box<dyn std::error::Error>> {
-    tracing::debug!("Fetching URL: {}", url);
+/// Reads a posting page, failing when it cannot be loaded or is gone (`ERR-FETCH-003`)
+pub async fn fetch_posting(url: &str) -> Result<Fetched> {
+    let html_content = fetch_page(url).await?;
+    if let Some((posting, extractor)) = extract_posting(url, &html_content) {
+        tracing::debug!("posting read by the {} extractor", extractor);
+        return Ok(Fetched::Posting { posting, extractor });
+    }
+    Ok(match main_content(&html_content) {
+        Some(text) => Fetched::Text(text),
+        None => Fetched::Text(page_text(&html_content)),
+    })
+}
 
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-        .build()?;
+/// The raw body of a URL under the fetch policy, for sources that are not a single posting
+pub async fn fetch_page(url: &str) -> Result<String> {
+    match fetch_html(url).await {
+        Ok(body) => Ok(body),
+        Err(FetchError::Refused(e)) => Err(e),
+        Err(FetchError::Gone(status)) => {
+            Err(StandardError::new("ERR-FETCH-003").interpolate_err(format!("{}: {}", url, status)))
+        }
+        Err(FetchError::Failed(reason)) => {
+            Err(StandardError::new("ERR-FETCH-002").interpolate_err(format!("{}: {}", url, reason)))
+        }
+    }
+}
 
-    let response = client.get(url).send().await?;
-    let status = response.status();
-    tracing::debug!("Response status: {}", status);
-    if status.as_u16() == 999 {
-        tracing::warn!("Received 999 status - likely bot detection");
-        return Err("Bot detection - 999 status code".into());
+/// Whether an address is reachable on the public internet, as opposed to private,
+/// loopback, link-local (cloud metadata), shared, documentation or reserved ranges
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public_v4(v4);
+            }
+            let segments = ip.segments();
+            // NAT64 embeds an IPv4 address in the last 32 bits
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [a, b] = segments[6].to_be_bytes();
+                let [c, d] = segments[7].to_be_bytes();
+                return is_public_v4(Ipv4Addr::new(a, b, c, d));
+            }
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (segments[0] & 0xfe00) == 0xfc00 // unique local
+                || (segments[0] & 0xffc0) == 0xfe80 // link-local
+                || (segments[0] & 0xffc0) == 0xfec0 // site-local
+                || (segments[0] == 0x2001 && segments[1] == 0x0db8) // documentation
+                || ip == Ipv6Addr::from(1u128)) // ::1 spelled out
+        }
     }
-    let html_content = response.text().await?;
-    tracing::debug!("HTML content length: {} bytes", html_content.len());
-    tracing::debug!(
-        "First 200 chars of HTML: {}",
-        &html_content.chars().take(200).collect::<String>()
-    );
-    let document = Html::parse_document(&html_content);
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (b & 0xc0) == 64) // shared address space (CGNAT)
+        || (a == 192 && b == 0 && c == 0) // IETF protocol assignments
+        || (a == 198 && (b & 0xfe) == 18) // benchmarking
+        || a >= 240) // reserved
+}
+
+/// Checks scheme and host, resolving it, and returns the addresses to connect to
+async fn vet(url: &Url) -> std::result::Result<Vec<SocketAddr>, FetchError> {
+    let allowed = settings
+        .fetch_allowed_schemes
+        .split(',')
+        .any(|scheme| scheme.trim().eq_ignore_ascii_case(url.scheme()));
+    if !allowed {
+        return Err(refuse(url, format!("scheme {} is not allowed", url.scheme())));
+    }
+    let Some(port) = url.port_or_known_default() else {
+        return Err(refuse(url, "no port"));
+    };
+    let addrs: Vec<SocketAddr> = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![SocketAddr::new(ip.into(), port)],
+        Some(Host::Ipv6(ip)) => vec![SocketAddr::new(ip.into(), port)],
+        Some(Host::Domain(domain)) => tokio::net::lookup_host((domain, port)).await?.collect(),
+        None => return Err(refuse(url, "no host")),
+    };
+    if addrs.is_empty() {
+        return Err(FetchError::Failed(format!("{} did not resolve", url)));
+    }
+    if let Some(blocked) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+        return Err(refuse(url, format!("{} is not a public address", blocked.ip())));
+    }
+    Ok(addrs)
+}
+
+async fn fetch_html(url: &str) -> std::result::Result<String, FetchError> {
+    let timeout = Duration::from_secs(settings.fetch_timeout_secs);
+    match tokio::time::timeout(timeout, follow(url)).await {
+        Ok(result) => result,
+        Err(_) => Err(FetchError::Failed(format!("timed out after {:?}", timeout))),
+    }
+}
+
+async fn follow(url: &str) -> std::result::Result<String, FetchError> {
+    tracing::debug!("Fetching URL: {}", url);
+    let mut current = Url::parse(url)?;
+    for _ in 0..=settings.fetch_max_redirects {
+        let addrs = vet(&current).await?;
+        let mut builder = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .redirect(Policy::none())
+            .no_proxy();
+        if let Some(Host::Domain(domain)) = current.host() {
+            builder = builder.resolve_to_addrs(domain, &addrs);
+        }
+        let mut response = builder.build()?.get(current.clone()).send().await?;
+        let status = response.status();
+        tracing::debug!("Response status: {}", status);
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|l| l.to_str().ok())
+                .ok_or_else(|| FetchError::Failed("redirect without a location".into()))?;
+            current = current.join(location)?;
+            continue;
+        }
+        if matches!(status.as_u16(), 404 | 410) {
+            return Err(FetchError::Gone(status));
+        }
+        if status.as_u16() == 999 {
+            tracing::warn!("Received 999 status - likely bot detection");
+            return Err(FetchError::Failed("Bot detection - 999 status code".into()));
+        }
+
+        let limit = settings.fetch_max_bytes;
+        if response.content_length().is_some_and(|length| length as usize > limit) {
+            return Err(refuse(&current, format!("response is larger than {} bytes", limit)));
+        }
+        let mut body = vec![];
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            if body.len() > limit {
+                return Err(refuse(&current, format!("response is larger than {} bytes", limit)));
+            }
+        }
+        let html_content = decode_text(&body);
+        tracing::debug!("HTML content length: {} bytes", html_content.len());
+        return Ok(html_content);
+    }
+    Err(refuse(
+        &current,
+        format!("more than {} redirects", settings.fetch_max_redirects),
+    ))
+}
+
+/// Every text node of the body, for pages without a recognisable main content block
+fn page_text(html_content: &str) -> String {
+    let document = Html::parse_document(html_content);
     tracing::debug!("HTML parsed successfully");
     let text_selector = Selector::parse("body").unwrap();
     let mut text_parts = Vec::new();
@@ -78,5 +283,24 @@ async fn fetch_and_extract(url: &str) -> Result<String, Box<dyn std::error::Erro
     let result = text_parts.join("\n");
     tracing::debug!("Final result length: {} chars", result.len());
 
-    Ok(result)
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public_blocks_internal_ranges() {
+        for blocked in [
+            "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254",
+            "100.64.0.1", "0.0.0.0", "255.255.255.255", "::1", "fd00::1", "fe80::1",
+            "::ffff:169.254.169.254", "64:ff9b::a00:1",
+        ] {
+            assert!(!is_public(blocked.parse().unwrap()), "{} should be blocked", blocked);
+        }
+        for allowed in ["93.184.216.34", "8.8.8.8", "2606:4700:4700::1111"] {
+            assert!(is_public(allowed.parse().unwrap()), "{} should be allowed", allowed);
+        }
+    }
 }
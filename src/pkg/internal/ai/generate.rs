@@ -1,9 +1,9 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
-use ai::{
-    chat_completions::{ChatCompletion, ChatCompletionMessage, ChatCompletionRequestBuilder}, clients::openai::Client};
+use ai::chat_completions::{ChatCompletion, ChatCompletionMessage, ChatCompletionRequestBuilder};
 use standard_error::{Interpolate, StandardError};
 
+use super::{client::AIClient, usage};
 use crate::{conf::settings, prelude::Result};
 
 #[async_trait::async_trait]
@@ -23,7 +23,7 @@ pub trait GenerateOps {
 }
 
 #[async_trait::async_trait]
-impl GenerateOps for Arc<Client>{
+impl GenerateOps for Arc<AIClient>{
 
     async fn direct_query(
         &self,
@@ -40,10 +40,18 @@ impl GenerateOps for Arc<Client>{
             .messages(vec![ChatCompletionMessage::User(prompt.into())])
             .build()
             .map_err(|e| StandardError::new("ERR-AI-001").interpolate_err(e.to_string()))?;
+        let started = Instant::now();
         let response = self 
             .chat_completions(&request)
             .await
             .map_err(|e| StandardError::new("ERR-AI-002").interpolate_err(e.to_string()))?;
+        usage::record(
+            "chat",
+            &settings.ai_model,
+            response.usage.prompt_tokens,
+            response.usage.completion_tokens,
+            started.elapsed(),
+        );
         let answer = response.choices[0]
             .message
             .content
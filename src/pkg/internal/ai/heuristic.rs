@@ -0,0 +1,285 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::internal::{adaptors::jobs::spec::JobEntry, ai::score::Verdict};
+
+pub const VERSION: &str = "heuristic@v1";
+/// Share of weighted requirements a resume must cover to be accepted
+pub const ACCEPT_THRESHOLD: f64 = 60.0;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "or", "the", "of", "in", "on", "for", "to", "with", "at", "by", "from",
+    "as", "is", "are", "be", "we", "you", "our", "your", "will", "can", "should", "must", "have",
+    "has", "strong", "good", "solid", "excellent", "proven", "knowledge", "experience",
+    "experienced", "understanding", "familiarity", "familiar", "ability", "skills", "skill",
+    "working", "work", "plus", "nice", "years", "year", "etc", "including", "such", "like",
+    "least", "preferred", "required", "requirements", "bonus", "hands", "deep", "using", "use",
+    "other", "related",
+];
+
+/// Canonical term first, then the spellings that mean the same thing
+const SYNONYMS: &[&[&str]] = &[
+    &["javascript", "js", "ecmascript"],
+    &["typescript", "ts"],
+    &["kubernetes", "k8s"],
+    &["postgresql", "postgres", "psql"],
+    &["golang", "go"],
+    &["python", "py"],
+    &["machine learning", "ml"],
+    &["artificial intelligence", "ai"],
+    &["natural language processing", "nlp"],
+    &["amazon web services", "aws"],
+    &["google cloud platform", "gcp", "google cloud"],
+    &["microsoft azure", "azure"],
+    &["continuous integration", "ci", "ci/cd", "cicd"],
+    &["node.js", "nodejs", "node"],
+    &["react", "reactjs", "react.js"],
+    &["vue", "vuejs", "vue.js"],
+    &["c#", "csharp", "dotnet", ".net"],
+    &["c++", "cpp"],
+    &["rest", "restful", "rest api"],
+    &["sql", "mysql", "sqlite"],
+    &["docker", "containers", "containerization"],
+    &["user experience", "ux"],
+    &["user interface", "ui"],
+    &["rust", "rustlang"],
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeywordMatch {
+    pub keyword: String,
+    pub weight: f64,
+    pub matched: bool,
+    /// the spelling found in the resume, when it differs from the keyword
+    pub matched_as: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Breakdown {
+    pub scorer: String,
+    pub score: f64,
+    pub threshold: f64,
+    pub keywords: Vec<KeywordMatch>,
+}
+
+/// Light suffix stripping, enough to match "deploying" with "deployment" and "apis" with "api".
+/// Both sides of a comparison go through it, so the stems only need to be consistent.
+pub fn stem(word: &str) -> String {
+    let mut word = word.to_lowercase();
+    if !word.ends_with("ss") {
+        for (suffix, replacement) in [
+            ("ies", "y"),
+            ("ments", ""),
+            ("ment", ""),
+            ("ations", "ate"),
+            ("ation", "ate"),
+            ("ings", ""),
+            ("ing", ""),
+            ("ers", ""),
+            ("er", ""),
+            ("ed", ""),
+            ("s", ""),
+        ] {
+            if let Some(base) = word.strip_suffix(suffix)
+                && base.chars().count() >= 3
+            {
+                word = format!("{}{}", base, replacement);
+                break;
+            }
+        }
+    }
+    if word.chars().count() > 3 && word.ends_with('e') {
+        word.pop();
+    }
+    word
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || "+#./".contains(c)))
+        .map(|t| t.trim_matches(|c: char| c == '.' || c == '/'))
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn stemmed(phrase: &str) -> String {
+    tokens(phrase)
+        .iter()
+        .map(|t| stem(t))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Every stemmed 1-3 word sequence in the text
+fn ngrams(text: &str) -> HashSet<String> {
+    let words: Vec<String> = tokens(text).iter().map(|t| stem(t)).collect();
+    let mut grams = HashSet::new();
+    for n in 1..=3 {
+        for window in words.windows(n) {
+            grams.insert(window.join(" "));
+        }
+    }
+    grams
+}
+
+fn variants(keyword: &str) -> Vec<String> {
+    let mut spellings = vec![keyword.to_string()];
+    for group in SYNONYMS.iter().filter(|group| group.contains(&keyword)) {
+        spellings.extend(group.iter().map(|s| s.to_string()));
+    }
+    spellings
+}
+
+/// Finds the keyword, or one of its synonyms, in the resume. A multi word keyword also
+/// matches when each of its words appears on its own, e.g. "rest apis" in "restful api".
+fn find(keyword: &str, grams: &HashSet<String>) -> Option<String> {
+    if let Some(found) = variants(keyword)
+        .into_iter()
+        .find(|s| grams.contains(&stemmed(s)))
+    {
+        return Some(found);
+    }
+    let words = tokens(keyword);
+    if words.len() > 1 && words.iter().all(|w| variants(w).iter().any(|s| grams.contains(&stemmed(s)))) {
+        return Some(keyword.to_string());
+    }
+    None
+}
+
+/// Splits requirements into short skill phrases, dropping filler words at the edges
+pub fn extract_keywords(requirements: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut keywords = vec![];
+    let normalised = requirements
+        .to_lowercase()
+        .replace(" and ", ",")
+        .replace(" or ", ",")
+        .replace(" with ", ",")
+        .replace(" in ", ",");
+    for phrase in normalised.split(|c: char| "\n,;:()•*|".contains(c)) {
+        let words: Vec<String> = tokens(phrase)
+            .into_iter()
+            .filter(|t| !STOPWORDS.contains(&t.as_str()))
+            .filter(|t| !t.chars().all(|c| c.is_ascii_digit() || c == '+'))
+            .collect();
+        // long sentences are split into their individual terms instead
+        let candidates: Vec<String> = if words.len() <= 3 {
+            vec![words.join(" ")]
+        } else {
+            words
+        };
+        for keyword in candidates {
+            if keyword.len() < 2 || !seen.insert(stemmed(&keyword)) {
+                continue;
+            }
+            keywords.push(keyword);
+        }
+    }
+    keywords
+}
+
+/// Scores a resume against the job without any AI provider
+pub fn score(content: &str, job: &JobEntry) -> (Verdict, Breakdown) {
+    let grams = ngrams(content);
+    let mut weighted: Vec<(String, f64)> = extract_keywords(&job.requirements)
+        .into_iter()
+        .map(|k| (k, 1.0))
+        .collect();
+    // well known skills named only in the description still count, at half weight
+    let description = ngrams(&job.description);
+    for group in SYNONYMS {
+        let canonical = group[0];
+        let covered = weighted
+            .iter()
+            .any(|(k, _)| group.contains(&k.as_str()));
+        if !covered && group.iter().any(|s| description.contains(&stemmed(s))) {
+            weighted.push((canonical.to_string(), 0.5));
+        }
+    }
+
+    let keywords: Vec<KeywordMatch> = weighted
+        .into_iter()
+        .map(|(keyword, weight)| {
+            let found = find(&keyword, &grams);
+            KeywordMatch {
+                matched: found.is_some(),
+                matched_as: found.filter(|s| *s != keyword),
+                keyword,
+                weight,
+            }
+        })
+        .collect();
+    let total: f64 = keywords.iter().map(|k| k.weight).sum();
+    let matched: f64 = keywords.iter().filter(|k| k.matched).map(|k| k.weight).sum();
+    let score = if total > 0.0 {
+        (matched / total * 1000.0).round() / 10.0
+    } else {
+        0.0
+    };
+    let (hits, misses): (Vec<&KeywordMatch>, Vec<&KeywordMatch>) =
+        keywords.iter().partition(|k| k.matched);
+    let list = |items: &[&KeywordMatch]| {
+        items
+            .iter()
+            .map(|k| k.keyword.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let feedback = format!(
+        "Scored offline by keyword matching. Matched {} of {} job requirements. Matched: {}. Missing: {}.",
+        hits.len(),
+        keywords.len(),
+        if hits.is_empty() { "none".into() } else { list(&hits) },
+        if misses.is_empty() { "none".into() } else { list(&misses) },
+    );
+    let verdict = Verdict {
+        score: format!("{:.1}", score),
+        status: if score >= ACCEPT_THRESHOLD { "accepted" } else { "rejected" }.to_string(),
+        feedback,
+    };
+    let breakdown = Breakdown {
+        scorer: VERSION.to_string(),
+        score,
+        threshold: ACCEPT_THRESHOLD,
+        keywords,
+    };
+    (verdict, breakdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_matches_stems_and_synonyms() {
+        let job = JobEntry {
+            id: 1,
+            title: "Backend Engineer".into(),
+            department: "Engineering".into(),
+            description: "You will run services on Kubernetes.".into(),
+            requirements: "3+ years of Rust\nExperience with PostgreSQL and REST APIs\nMachine learning"
+                .into(),
+            url: None,
+            created_at: chrono::NaiveDateTime::default(),
+            updated_at: chrono::NaiveDateTime::default(),
+            closed_at: None,
+            last_synced_at: None,
+            source_hash: None,
+            project_id: None,
+            edited_at: None,
+        };
+        let resume = "Built restful api services in rust backed by postgres, deployed on k8s.";
+        let (verdict, breakdown) = score(resume, &job);
+        let matched: Vec<&str> = breakdown
+            .keywords
+            .iter()
+            .filter(|k| k.matched)
+            .map(|k| k.keyword.as_str())
+            .collect();
+        assert_eq!(matched, vec!["rust", "postgresql", "rest apis", "kubernetes"]);
+        assert!(verdict.feedback.contains("Missing: machine learning"));
+        assert_eq!(verdict.status, "accepted");
+    }
+}
@@ -1,11 +1,17 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
-use ai::{
-    clients::openai::Client, embeddings::{Embeddings, EmbeddingsRequestBuilder}
-};
+use ai::embeddings::{Embeddings, EmbeddingsRequestBuilder};
 use pgvector::Vector;
 use standard_error::{Interpolate, StandardError};
-use crate::{conf::settings, prelude::Result};
+use super::{client::AIClient, usage};
+use crate::{
+    conf::settings,
+    pkg::{
+        internal::adaptors::embeddings::{mutators::EmbeddingMutator, selectors::EmbeddingSelector},
+        server::state::{AppState, GetTxn},
+    },
+    prelude::Result,
+};
 
 
 #[async_trait::async_trait]
@@ -14,28 +20,32 @@ pub trait IndexOps {
         &self,
         content: &str,
     ) -> Result<Vector>;
+
+    async fn embedding_dimensions(&self) -> Result<i32>;
 }
 
 #[async_trait::async_trait]
-impl IndexOps for Arc<Client>{
+impl IndexOps for Arc<AIClient>{
 
     async fn index_document(
         &self,
         content: &str,
     ) -> Result<Vector> {
-        let model = match settings.ai_provider.as_str(){
-            "ollama" => "nomic-embed-text",
-            "gemini" => "text-embedding-004",
-            "openai" => "text-embedding-3-large",
-            _ => {return Err(StandardError::new("ERR-AI-004").interpolate_err("invalid model".into()))}
-        };
         let request = EmbeddingsRequestBuilder::default()
-            .model(model)
+            .model(&settings.ai_embedding_model)
             .input(vec![content.to_string()])
             .build()
             .map_err(|e|StandardError::new("ERR-AI-004").interpolate_err(e.to_string()))
             ?;
+        let started = Instant::now();
         let response = self.create_embeddings(&request).await.map_err(|e|StandardError::new("ERR-AI-004").interpolate_err(e.to_string()))?;
+        usage::record(
+            "embedding",
+            &settings.ai_embedding_model,
+            response.usage.prompt_tokens,
+            0,
+            started.elapsed(),
+        );
         let embedding_vec: Vec<f32> = response.data[0]
             .embedding
             .iter()
@@ -43,4 +53,45 @@ impl IndexOps for Arc<Client>{
             .collect();
         Ok(Vector::from(embedding_vec))
     }
+
+    async fn embedding_dimensions(&self) -> Result<i32> {
+        let probe = self.index_document("dimension probe").await?;
+        Ok(probe.as_slice().len() as i32)
+    }
+}
+
+/// Stored vectors are only comparable with ones from the same model, so refuse to
+/// start when the configured model differs from what the tables were built with
+pub async fn check_embedding_metadata(state: &AppState) -> Result<()> {
+    let dimensions = match state.ai_client.embedding_dimensions().await {
+        Ok(dimensions) => dimensions,
+        Err(e) => {
+            tracing::warn!("could not probe embedding model, skipping check: {}", e.message);
+            return Ok(());
+        }
+    };
+    let mut tx = state.db_pool.begin_txn().await?;
+    let metadata = EmbeddingSelector::new(&mut tx).get_metadata().await?;
+    if metadata.model.is_empty() && metadata.dimensions == dimensions {
+        tracing::info!(
+            "recording {} ({} dimensions) as the embedding model",
+            &settings.ai_embedding_model,
+            dimensions
+        );
+        EmbeddingMutator::new(&mut tx)
+            .set_metadata(&settings.ai_provider, &settings.ai_embedding_model, dimensions)
+            .await?;
+        tx.commit().await?;
+        return Ok(());
+    }
+    if metadata.dimensions != dimensions || metadata.model != settings.ai_embedding_model {
+        return Err(StandardError::new("ERR-AI-007").interpolate_err(format!(
+            "stored embeddings use {} ({} dimensions) but {} returns {} dimensions, run `resumeval reembed`",
+            if metadata.model.is_empty() { "an unknown model" } else { &metadata.model },
+            metadata.dimensions,
+            &settings.ai_embedding_model,
+            dimensions
+        )));
+    }
+    Ok(())
 }
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use whatlang::Lang;
+
+use crate::{
+    pkg::internal::ai::{client::AIClient, generate::GenerateOps, prompts::PromptChoice},
+    prelude::Result,
+};
+
+/// Characters looked at when detecting, enough for a stable guess on a resume
+const SAMPLE_CHARS: usize = 4000;
+/// Guesses below this confidence are treated as unknown
+const MIN_CONFIDENCE: f64 = 0.5;
+
+/// ISO 639-3 code of the text's language, e.g. `deu`, or None when unsure
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let sample: String = text.chars().take(SAMPLE_CHARS).collect();
+    let info = whatlang::detect(&sample)?;
+    (info.confidence() >= MIN_CONFIDENCE).then(|| info.lang().code())
+}
+
+/// English name of an ISO 639-3 code, falling back to the code itself
+pub fn language_name(code: &str) -> &str {
+    Lang::from_code(code).map(|lang| lang.eng_name()).unwrap_or(code)
+}
+
+/// Translates resume text into the language with the given ISO 639-3 code
+pub async fn translate(
+    ai_client: &Arc<AIClient>,
+    prompt: &PromptChoice,
+    text: &str,
+    target: &str,
+) -> Result<String> {
+    let prompt = prompt.render_translate(text, language_name(target))?;
+    let translated = ai_client.direct_query(&prompt, None).await?;
+    Ok(translated.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language(
+                "Ich bin Softwareentwickler mit fünf Jahren Berufserfahrung in der Entwicklung \
+                 von verteilten Systemen und arbeite gerne im Team."
+            ),
+            Some("deu")
+        );
+        assert_eq!(
+            detect_language(
+                "Backend engineer with five years of experience building distributed systems \
+                 and a strong interest in developer tooling."
+            ),
+            Some("eng")
+        );
+        assert_eq!(language_name("fra"), "French");
+    }
+}
@@ -1,5 +1,15 @@
+pub mod client;
 pub mod read;
 pub mod fetch;
 pub mod generate;
+pub mod heuristic;
 pub mod index;
+pub mod language;
+pub mod postings;
+pub mod prompts;
+pub mod rank;
+pub mod readability;
+pub mod score;
+pub mod sections;
 pub mod spec;
+pub mod usage;
@@ -0,0 +1,33 @@
+//! Ashby job boards (`jobs.ashbyhq.com`). The page is rendered client side from a
+//! `window.__appData` object, which carries the posting.
+
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+use super::{Posting, posting};
+use crate::pkg::internal::ai::read::html::html_to_text;
+
+const APP_DATA: &str = "window.__appData";
+
+pub fn extract(document: &Html) -> Option<Posting> {
+    let selector = Selector::parse("script").ok()?;
+    let script = document
+        .select(&selector)
+        .map(|s| s.text().collect::<String>())
+        .find(|s| s.contains(APP_DATA))?;
+    let at = script.find(APP_DATA)?;
+    let start = at + script[at..].find('{')?;
+    let end = script.rfind('}')?;
+    let data: Value = serde_json::from_str(script.get(start..=end)?).ok()?;
+    let job = data.get("posting")?;
+    let field = |key: &str| job.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+    let description = match job.get("descriptionHtml").and_then(Value::as_str) {
+        Some(html) => html_to_text(html),
+        None => field("descriptionPlainText"),
+    };
+    let department = [field("departmentName"), field("teamName")]
+        .into_iter()
+        .find(|d| !d.is_empty())
+        .unwrap_or_default();
+    posting(field("title"), department, &description)
+}
@@ -0,0 +1,19 @@
+//! Greenhouse job boards, both the classic `boards.greenhouse.io` layout and the newer
+//! `job-boards.greenhouse.io` one.
+
+use scraper::Html;
+
+use super::{Posting, element_markup_text, element_text, posting, select_first};
+
+pub fn extract(document: &Html) -> Option<Posting> {
+    let title = select_first(document, "h1.app-title, .job__title h1, h1.section-header")
+        .map(element_text)
+        .unwrap_or_default();
+    let department = select_first(document, ".job__department, .department")
+        .map(element_text)
+        .unwrap_or_default();
+    let body = select_first(document, "#content, .job__description")
+        .map(element_markup_text)
+        .unwrap_or_default();
+    posting(title, department, &body)
+}
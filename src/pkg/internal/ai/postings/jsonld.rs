@@ -0,0 +1,92 @@
+//! schema.org `JobPosting` embedded as JSON-LD, which most career sites publish for search engines.
+
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+use super::{Posting, posting};
+use crate::pkg::internal::ai::read::html::html_to_text;
+
+/// Properties that describe what the candidate needs to bring
+const REQUIREMENT_FIELDS: &[&str] = &[
+    "qualifications",
+    "skills",
+    "experienceRequirements",
+    "educationRequirements",
+];
+
+fn is_job_posting(value: &Value) -> bool {
+    match value.get("@type") {
+        Some(Value::String(t)) => t == "JobPosting",
+        Some(Value::Array(types)) => types.iter().any(|t| t == "JobPosting"),
+        _ => false,
+    }
+}
+
+/// Finds the first `JobPosting` node, looking through arrays and `@graph`
+fn find_posting(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.iter().find_map(find_posting),
+        Value::Object(map) => {
+            if is_job_posting(value) {
+                return Some(value);
+            }
+            map.get("@graph").and_then(find_posting)
+        }
+        _ => None,
+    }
+}
+
+/// Text of a property that may be a string, an object with a name or description, or a list
+fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(s) => html_to_text(s),
+        Value::Array(items) => items
+            .iter()
+            .map(text_of)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Object(map) => ["description", "name", "credentialCategory"]
+            .iter()
+            .find_map(|key| map.get(*key).map(text_of))
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+pub fn extract(document: &Html) -> Option<Posting> {
+    let selector = Selector::parse(r#"script[type="application/ld+json"]"#).ok()?;
+    for script in document.select(&selector) {
+        let raw = script.text().collect::<String>();
+        // publishers often leave raw control characters in strings, which JSON forbids
+        let raw = raw.replace(['\n', '\r', '\t'], " ");
+        let Ok(value) = serde_json::from_str::<Value>(&raw) else {
+            continue;
+        };
+        let Some(job) = find_posting(&value) else {
+            continue;
+        };
+        let field = |key: &str| job.get(key).map(text_of).unwrap_or_default();
+        let department = ["occupationalCategory", "industry"]
+            .iter()
+            .map(|key| field(key))
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        let Some(mut found) = posting(field("title"), department, &field("description")) else {
+            continue;
+        };
+        let listed: Vec<String> = REQUIREMENT_FIELDS
+            .iter()
+            .map(|key| field(key))
+            .filter(|value| !value.is_empty())
+            .collect();
+        if !listed.is_empty() {
+            if !found.requirements.is_empty() {
+                found.requirements.push('\n');
+            }
+            found.requirements.push_str(&listed.join("\n"));
+        }
+        return Some(found);
+    }
+    None
+}
@@ -0,0 +1,31 @@
+//! Lever hosted job pages (`jobs.lever.co`): a headline with categories, a description
+//! block, then one `section` per list such as requirements or benefits.
+
+use scraper::{Html, Selector};
+
+use super::{Posting, element_markup_text, element_text, posting, select_first};
+
+pub fn extract(document: &Html) -> Option<Posting> {
+    let title = select_first(document, ".posting-headline h2")
+        .map(element_text)
+        .unwrap_or_default();
+    let department = select_first(
+        document,
+        ".posting-categories .department, .posting-categories .sort-by-team",
+    )
+    .map(element_text)
+    .unwrap_or_default();
+
+    let mut body = vec![];
+    if let Some(description) = select_first(document, r#"[data-qa="job-description"]"#) {
+        body.push(element_markup_text(description));
+    }
+    let sections = Selector::parse(".section.page-centered").ok()?;
+    for section in document.select(&sections) {
+        if section.value().attr("data-qa").is_some() {
+            continue;
+        }
+        body.push(element_markup_text(section));
+    }
+    posting(title, department.trim_end_matches(" /").to_string(), &body.join("\n"))
+}
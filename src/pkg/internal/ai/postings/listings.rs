@@ -0,0 +1,335 @@
+//! Finding the individual postings behind a careers source for bulk import.
+//!
+//! Greenhouse and Lever boards publish every open role as JSON, RSS and Atom feeds
+//! carry one item per role, and careers pages only link to them. Board pages are read
+//! through their JSON API instead of the page itself.
+
+use std::collections::HashSet;
+
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde_json::Value;
+use xml::reader::{EventReader, XmlEvent};
+
+use super::{Posting, SITES, posting};
+use crate::pkg::internal::ai::read::html::html_to_text;
+
+pub const EXTRACTOR_GREENHOUSE_API: &str = "greenhouse-api";
+pub const EXTRACTOR_LEVER_API: &str = "lever-api";
+pub const EXTRACTOR_FEED: &str = "feed";
+
+/// Path segments that mark a link on a careers page as a job posting
+const JOB_SEGMENTS: &[&str] = &[
+    "job", "jobs", "career", "careers", "position", "positions", "opening", "openings",
+    "vacancy", "vacancies", "role", "roles", "stellen", "stellenangebote",
+];
+
+pub enum Listing {
+    /// read from the source itself
+    Posting {
+        posting: Posting,
+        url: Option<String>,
+        extractor: &'static str,
+    },
+    /// only linked from the source, the page still has to be read
+    Link(String),
+}
+
+impl Listing {
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Listing::Posting { url, .. } => url.as_deref(),
+            Listing::Link(url) => Some(url),
+        }
+    }
+}
+
+/// The JSON feed of a Greenhouse or Lever board page, which lists every posting with
+/// its full content
+pub fn board_api_url(url: &Url) -> Option<String> {
+    let host = url.host_str()?.to_lowercase();
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let [board] = segments.as_slice() else {
+        return None;
+    };
+    match host.as_str() {
+        "boards.greenhouse.io" | "job-boards.greenhouse.io" => Some(format!(
+            "https://boards-api.greenhouse.io/v1/boards/{}/jobs?content=true",
+            board
+        )),
+        "jobs.lever.co" => Some(format!("https://api.lever.co/v0/postings/{}?mode=json", board)),
+        _ => None,
+    }
+}
+
+/// The postings a source lists, whatever its format
+pub fn discover(source: &Url, body: &str) -> Vec<Listing> {
+    let trimmed = body.trim_start();
+    if trimmed.starts_with(['{', '[']) {
+        return serde_json::from_str::<Value>(trimmed)
+            .map(|value| from_board_json(&value))
+            .unwrap_or_default();
+    }
+    let head: String = trimmed.chars().take(512).collect::<String>().to_lowercase();
+    if head.starts_with("<?xml") && (head.contains("<rss") || head.contains("<feed"))
+        || head.starts_with("<rss")
+        || head.starts_with("<feed")
+    {
+        return from_feed(body);
+    }
+    from_careers_page(source, body)
+}
+
+fn string(value: &Value, key: &str) -> String {
+    value.get(key).and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+/// Greenhouse escapes the HTML of `content` once more
+fn unescape(html: &str) -> String {
+    html.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn listing(posting: Option<Posting>, url: String, extractor: &'static str) -> Option<Listing> {
+    let url = (!url.is_empty()).then_some(url);
+    match (posting, url) {
+        (Some(posting), url) => Some(Listing::Posting {
+            posting,
+            url,
+            extractor,
+        }),
+        (None, Some(url)) => Some(Listing::Link(url)),
+        (None, None) => None,
+    }
+}
+
+fn from_board_json(value: &Value) -> Vec<Listing> {
+    // Greenhouse: {"jobs": [{"title", "absolute_url", "content", "departments": [{"name"}]}]}
+    if let Some(jobs) = value.get("jobs").and_then(Value::as_array) {
+        return jobs
+            .iter()
+            .filter_map(|job| {
+                let department = job
+                    .get("departments")
+                    .and_then(Value::as_array)
+                    .and_then(|departments| departments.first())
+                    .map(|department| string(department, "name"))
+                    .unwrap_or_default();
+                let body = html_to_text(&unescape(&string(job, "content")));
+                listing(
+                    posting(string(job, "title"), department, &body),
+                    string(job, "absolute_url"),
+                    EXTRACTOR_GREENHOUSE_API,
+                )
+            })
+            .collect();
+    }
+    // Lever: [{"text", "hostedUrl", "categories": {"team"}, "description", "lists": [{"text", "content"}], "additional"}]
+    let Some(postings) = value.as_array() else {
+        return vec![];
+    };
+    postings
+        .iter()
+        .filter_map(|job| {
+            let department = job
+                .get("categories")
+                .map(|categories| {
+                    let team = string(categories, "team");
+                    if team.is_empty() { string(categories, "department") } else { team }
+                })
+                .unwrap_or_default();
+            let mut html = string(job, "description");
+            for list in job.get("lists").and_then(Value::as_array).into_iter().flatten() {
+                html.push_str(&format!(
+                    "<h3>{}</h3><ul>{}</ul>",
+                    string(list, "text"),
+                    string(list, "content")
+                ));
+            }
+            html.push_str(&string(job, "additional"));
+            listing(
+                posting(string(job, "text"), department, &html_to_text(&html)),
+                string(job, "hostedUrl"),
+                EXTRACTOR_LEVER_API,
+            )
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Item {
+    title: String,
+    link: String,
+    body: String,
+    category: String,
+}
+
+/// RSS `<item>`s and Atom `<entry>`s
+fn from_feed(xml: &str) -> Vec<Listing> {
+    let mut items = vec![];
+    let mut item: Option<Item> = None;
+    let mut field = String::new();
+    let mut text = String::new();
+    for event in EventReader::new(xml.as_bytes()) {
+        let Ok(event) = event else {
+            break;
+        };
+        match event {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => match name.local_name.as_str() {
+                "item" | "entry" => item = Some(Item::default()),
+                "link" if item.is_some() => {
+                    let rel = attributes.iter().find(|a| a.name.local_name == "rel");
+                    let href = attributes.iter().find(|a| a.name.local_name == "href");
+                    if let (Some(current), Some(href)) = (item.as_mut(), href)
+                        && rel.is_none_or(|rel| rel.value == "alternate")
+                    {
+                        current.link = href.value.clone();
+                    }
+                    field = "link".into();
+                    text.clear();
+                }
+                local => {
+                    field = local.to_string();
+                    text.clear();
+                }
+            },
+            XmlEvent::Characters(chunk) | XmlEvent::CData(chunk) => text.push_str(&chunk),
+            XmlEvent::EndElement { name } => {
+                let local = name.local_name.as_str();
+                if matches!(local, "item" | "entry") {
+                    items.extend(item.take());
+                    continue;
+                }
+                let Some(current) = item.as_mut() else {
+                    continue;
+                };
+                if local == field {
+                    let value = text.trim().to_string();
+                    match local {
+                        "title" => current.title = value,
+                        "link" if !value.is_empty() => current.link = value,
+                        "category" if current.category.is_empty() => current.category = value,
+                        // the full content wins over a summary
+                        "encoded" | "content" => current.body = value,
+                        "description" | "summary" if current.body.is_empty() => {
+                            current.body = value
+                        }
+                        _ => {}
+                    }
+                }
+                text.clear();
+            }
+            _ => {}
+        }
+    }
+    items
+        .into_iter()
+        .filter_map(|item| {
+            listing(
+                posting(item.title, item.category, &html_to_text(&item.body)),
+                item.link,
+                EXTRACTOR_FEED,
+            )
+        })
+        .collect()
+}
+
+fn is_ats_host(host: &str) -> bool {
+    SITES
+        .iter()
+        .flat_map(|site| site.hosts)
+        .any(|h| host == *h || host.ends_with(&format!(".{}", h)))
+}
+
+/// Links that look like individual postings: on the same site below a job path, or on
+/// an applicant tracking system
+fn from_careers_page(source: &Url, html: &str) -> Vec<Listing> {
+    let document = Html::parse_document(html);
+    let Ok(anchors) = Selector::parse("a[href]") else {
+        return vec![];
+    };
+    let source_host = source.host_str().unwrap_or_default().to_lowercase();
+    let mut seen = HashSet::new();
+    let mut links = vec![];
+    for anchor in document.select(&anchors) {
+        let Some(mut url) = anchor.value().attr("href").and_then(|href| source.join(href).ok())
+        else {
+            continue;
+        };
+        url.set_fragment(None);
+        if !matches!(url.scheme(), "http" | "https") || url == *source {
+            continue;
+        }
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let segments: Vec<String> = url
+            .path_segments()
+            .map(|s| s.filter(|s| !s.is_empty()).map(str::to_lowercase).collect())
+            .unwrap_or_default();
+        let is_posting = if is_ats_host(&host) {
+            segments.len() >= 2
+        } else {
+            // below a job segment, not the job index itself
+            host == source_host
+                && segments
+                    .iter()
+                    .position(|s| JOB_SEGMENTS.contains(&s.as_str()))
+                    .is_some_and(|i| i + 1 < segments.len())
+                && !segments.last().is_some_and(|s| JOB_SEGMENTS.contains(&s.as_str()))
+        };
+        if is_posting && seen.insert(url.to_string()) {
+            links.push(Listing::Link(url.to_string()));
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_reads_boards_feeds_and_careers_pages() {
+        let source = Url::parse("https://boards.greenhouse.io/acme").unwrap();
+        assert_eq!(
+            board_api_url(&source).as_deref(),
+            Some("https://boards-api.greenhouse.io/v1/boards/acme/jobs?content=true")
+        );
+        let greenhouse = r#"{"jobs": [{"title": "Backend Engineer",
+            "absolute_url": "https://boards.greenhouse.io/acme/jobs/1",
+            "departments": [{"name": "Platform"}],
+            "content": "&lt;p&gt;Build APIs.&lt;/p&gt;&lt;h3&gt;Requirements&lt;/h3&gt;&lt;ul&gt;&lt;li&gt;Rust&lt;/li&gt;&lt;/ul&gt;"}]}"#;
+        let listings = discover(&source, greenhouse);
+        let Listing::Posting { posting, url, extractor } = &listings[0] else {
+            panic!("expected a posting");
+        };
+        assert_eq!(*extractor, EXTRACTOR_GREENHOUSE_API);
+        assert_eq!(url.as_deref(), Some("https://boards.greenhouse.io/acme/jobs/1"));
+        assert_eq!(posting.department, "Platform");
+        assert_eq!(posting.requirements, "- Rust");
+
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Jobs</title>
+            <item><title>Data Engineer</title><link>https://acme.example/jobs/data</link>
+              <description><![CDATA[<p>Own our pipelines.</p>]]></description></item>
+            <item><title>Designer</title><link>https://acme.example/jobs/design</link></item>
+            </channel></rss>"#;
+        let listings = discover(&Url::parse("https://acme.example/jobs.rss").unwrap(), rss);
+        assert_eq!(listings.len(), 2);
+        assert!(matches!(&listings[0], Listing::Posting { posting, .. } if posting.description == "Own our pipelines."));
+        assert_eq!(listings[1].url(), Some("https://acme.example/jobs/design"));
+
+        let page = r#"<html><body><a href="/careers">All jobs</a>
+            <a href="/careers/backend-engineer">Backend</a><a href="/careers/backend-engineer#apply">Apply</a>
+            <a href="https://jobs.lever.co/acme/123">Designer</a><a href="/about">About</a></body></html>"#;
+        let listings = discover(&Url::parse("https://acme.example/careers").unwrap(), page);
+        let urls: Vec<_> = listings.iter().filter_map(Listing::url).collect();
+        assert_eq!(
+            urls,
+            ["https://acme.example/careers/backend-engineer", "https://jobs.lever.co/acme/123"]
+        );
+    }
+}
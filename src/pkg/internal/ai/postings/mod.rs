@@ -0,0 +1,199 @@
+//! Structured job posting extraction.
+//!
+//! Known applicant tracking systems are read with their own page layout, then any page
+//! is checked for a schema.org `JobPosting` in JSON-LD. Only when both fail does the
+//! caller fall back to asking the LLM about the page text.
+
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+
+use crate::pkg::internal::ai::read::html::html_to_text;
+
+mod ashby;
+mod greenhouse;
+mod jsonld;
+mod lever;
+pub mod listings;
+mod workable;
+
+pub const EXTRACTOR_JSON_LD: &str = "json-ld";
+pub const EXTRACTOR_LLM: &str = "llm";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Posting {
+    pub title: String,
+    pub department: String,
+    pub description: String,
+    pub requirements: String,
+}
+
+impl Posting {
+    /// A title and a description at least, as anything less is no posting
+    pub fn is_complete(&self) -> bool {
+        !self.title.trim().is_empty() && !self.description.trim().is_empty()
+    }
+}
+
+/// An applicant tracking system with a page layout we can read directly
+struct Site {
+    name: &'static str,
+    /// the page host is one of these or a subdomain of one
+    hosts: &'static [&'static str],
+    extract: fn(&Html) -> Option<Posting>,
+}
+
+const SITES: &[Site] = &[
+    Site {
+        name: "greenhouse",
+        hosts: &["greenhouse.io"],
+        extract: greenhouse::extract,
+    },
+    Site {
+        name: "lever",
+        hosts: &["lever.co"],
+        extract: lever::extract,
+    },
+    Site {
+        name: "workable",
+        hosts: &["workable.com"],
+        extract: workable::extract,
+    },
+    Site {
+        name: "ashby",
+        hosts: &["ashbyhq.com"],
+        extract: ashby::extract,
+    },
+];
+
+/// Headings that start the requirements part of a description
+const REQUIREMENT_HEADINGS: &[&str] = &[
+    "requirements", "qualifications", "what you bring", "what you'll bring",
+    "what we're looking for", "what we are looking for", "who you are", "you have",
+    "you should have", "must have", "must-haves", "skills", "minimum qualifications",
+    "preferred qualifications", "your profile", "about you", "nice to have",
+];
+
+/// Headings that end it again
+const OTHER_HEADINGS: &[&str] = &[
+    "benefits", "what we offer", "perks", "about us", "about the company", "compensation",
+    "salary", "how to apply", "why join us", "our values", "the team", "responsibilities",
+    "what you'll do", "what you will do",
+];
+
+fn heading_name(line: &str) -> Option<String> {
+    let name = line
+        .trim()
+        .trim_start_matches('#')
+        .trim()
+        .trim_end_matches(':')
+        .trim()
+        .to_lowercase()
+        .replace('’', "'");
+    (!name.is_empty() && name.split_whitespace().count() <= 6).then_some(name)
+}
+
+/// Moves the requirement sections of a posting's text out of the description
+pub fn split_requirements(text: &str) -> (String, String) {
+    let mut description = vec![];
+    let mut requirements = vec![];
+    let mut in_requirements = false;
+    for line in text.lines() {
+        if let Some(name) = heading_name(line) {
+            if REQUIREMENT_HEADINGS.iter().any(|h| name == *h || name.starts_with(&format!("{} ", h))) {
+                in_requirements = true;
+                continue;
+            }
+            if OTHER_HEADINGS.iter().any(|h| name.starts_with(h)) {
+                in_requirements = false;
+            }
+        }
+        if in_requirements {
+            requirements.push(line);
+        } else {
+            description.push(line);
+        }
+    }
+    (description.join("\n").trim().to_string(), requirements.join("\n").trim().to_string())
+}
+
+pub(crate) fn select_first<'a>(document: &'a Html, selectors: &str) -> Option<ElementRef<'a>> {
+    let selector = Selector::parse(selectors).ok()?;
+    document.select(&selector).next()
+}
+
+pub(crate) fn element_text(element: ElementRef) -> String {
+    element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub(crate) fn element_markup_text(element: ElementRef) -> String {
+    html_to_text(&element.html())
+}
+
+/// Fills a posting from a title, department and the full description text, splitting
+/// the requirements out of it. Postings without a title or body are not usable.
+pub(crate) fn posting(title: String, department: String, body: &str) -> Option<Posting> {
+    let (description, requirements) = split_requirements(body);
+    if title.trim().is_empty() || description.is_empty() {
+        return None;
+    }
+    Some(Posting {
+        title: title.trim().to_string(),
+        department: department.trim().to_string(),
+        description,
+        requirements,
+    })
+}
+
+/// Reads a posting from the page without the LLM, naming the extractor that matched
+pub fn extract_posting(url: &str, html: &str) -> Option<(Posting, &'static str)> {
+    let document = Html::parse_document(html);
+    let host = Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        .unwrap_or_default();
+    let site = SITES.iter().find(|site| {
+        site.hosts
+            .iter()
+            .any(|h| host == *h || host.ends_with(&format!(".{}", h)))
+    });
+    if let Some(site) = site
+        && let Some(posting) = (site.extract)(&document)
+    {
+        return Some((posting, site.name));
+    }
+    jsonld::extract(&document).map(|posting| (posting, EXTRACTOR_JSON_LD))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_posting_prefers_site_layout_then_json_ld() {
+        let lever = r#"<html><body>
+            <div class="posting-headline"><h2>Backend Engineer</h2>
+              <div class="posting-categories"><div class="sort-by-team department">Platform</div></div></div>
+            <div data-qa="job-description"><p>Build our APIs.</p></div>
+            <div class="section page-centered"><h3>Requirements</h3><ul><li>Rust</li></ul></div>
+            <div class="section page-centered"><h3>Benefits</h3><ul><li>Remote</li></ul></div>
+        </body></html>"#;
+        let (posting, extractor) = extract_posting("https://jobs.lever.co/acme/123", lever).unwrap();
+        assert_eq!(extractor, "lever");
+        assert_eq!(posting.title, "Backend Engineer");
+        assert_eq!(posting.department, "Platform");
+        assert_eq!(posting.requirements, "- Rust");
+
+        let jsonld = r#"<html><head><script type="application/ld+json">
+            {"@context": "https://schema.org", "@graph": [{"@type": "Organization"},
+             {"@type": "JobPosting", "title": "Data Engineer", "occupationalCategory": "Data",
+              "description": "<p>Own our pipelines.</p><h3>Qualifications</h3><ul><li>SQL</li></ul>"}]}
+        </script></head><body><nav>Home</nav></body></html>"#;
+        let (posting, extractor) = extract_posting("https://careers.example.com/7", jsonld).unwrap();
+        assert_eq!(extractor, EXTRACTOR_JSON_LD);
+        assert_eq!(posting.description, "Own our pipelines.");
+        assert_eq!(posting.requirements, "- SQL");
+
+        assert!(extract_posting("https://example.com", "<p>Hello</p>").is_none());
+    }
+}
@@ -0,0 +1,32 @@
+//! Workable job pages (`apply.workable.com`), which mark each part with a `data-ui` attribute.
+
+use scraper::Html;
+
+use super::{Posting, element_markup_text, element_text, posting, select_first};
+
+pub fn extract(document: &Html) -> Option<Posting> {
+    let title = select_first(document, r#"[data-ui="job-title"]"#)
+        .map(element_text)
+        .unwrap_or_default();
+    let department = select_first(document, r#"[data-ui="job-department"]"#)
+        .map(element_text)
+        .unwrap_or_default();
+    let description = select_first(document, r#"[data-ui="job-description"]"#)
+        .map(element_markup_text)
+        .unwrap_or_default();
+    let mut found = posting(title, department, &description)?;
+    if let Some(requirements) = select_first(document, r#"[data-ui="job-requirements"]"#) {
+        let requirements = element_markup_text(requirements);
+        // the block repeats its own "Requirements" heading
+        let requirements = requirements
+            .lines()
+            .skip_while(|line| line.trim_start_matches('#').trim().eq_ignore_ascii_case("requirements"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !found.requirements.is_empty() {
+            found.requirements.push('\n');
+        }
+        found.requirements.push_str(&requirements);
+    }
+    Some(found)
+}
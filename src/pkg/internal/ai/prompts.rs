@@ -0,0 +1,286 @@
+use askama::Template;
+use serde::Serialize;
+use sqlx::PgConnection;
+use standard_error::{Interpolate, StandardError};
+
+use crate::{
+    pkg::internal::adaptors::prompts::selectors::PromptSelector, prelude::Result,
+};
+
+pub const SCORE_RESUME: &str = "score_resume";
+pub const EXTRACT_JOB: &str = "extract_job";
+pub const RANK_PAIR: &str = "rank_pair";
+pub const TRANSLATE_RESUME: &str = "translate_resume";
+
+/// Prompts that can be overridden, with the variables an override must use
+pub const PROMPTS: [(&str, &[&str]); 4] = [
+    (SCORE_RESUME, &["resume", "job"]),
+    (EXTRACT_JOB, &["posting"]),
+    (RANK_PAIR, &["job", "candidate_a", "candidate_b"]),
+    (TRANSLATE_RESUME, &["resume", "language"]),
+];
+
+#[derive(Template)]
+#[template(path = "prompts/score_resume.txt")]
+struct ScoreResumePrompt<'a> {
+    resume: &'a str,
+    job: &'a str,
+    examples: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "prompts/extract_job.txt")]
+struct ExtractJobPrompt<'a> {
+    posting: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "prompts/rank_pair.txt")]
+struct RankPairPrompt<'a> {
+    job: &'a str,
+    candidate_a: &'a str,
+    candidate_b: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "prompts/translate_resume.txt")]
+struct TranslateResumePrompt<'a> {
+    resume: &'a str,
+    language: &'a str,
+}
+
+/// A resolved prompt: the builtin template, or a project override when `body` is set
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptChoice {
+    pub name: String,
+    pub version: i32,
+    #[serde(skip)]
+    pub body: Option<String>,
+}
+
+impl PromptChoice {
+    pub fn builtin(name: &str) -> Self {
+        PromptChoice {
+            name: name.to_string(),
+            version: 0,
+            body: None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self.version {
+            0 => "builtin".to_string(),
+            v => format!("v{}", v),
+        }
+    }
+
+    /// What gets stored next to a verdict, e.g. `score_resume@v3`
+    pub fn version_tag(&self) -> String {
+        format!("{}@{}", self.name, self.label())
+    }
+
+    pub fn render_score(&self, resume: &str, job: &str, examples: &str) -> Result<String> {
+        match &self.body {
+            Some(body) => Ok(render_override(
+                body,
+                &[("resume", resume), ("job", job), ("examples", examples)],
+            )),
+            None => Ok(ScoreResumePrompt {
+                resume,
+                job,
+                examples,
+            }
+            .render()?),
+        }
+    }
+
+    pub fn render_extract(&self, posting: &str) -> Result<String> {
+        match &self.body {
+            Some(body) => Ok(render_override(body, &[("posting", posting)])),
+            None => Ok(ExtractJobPrompt { posting }.render()?),
+        }
+    }
+
+    pub fn render_pair(&self, job: &str, candidate_a: &str, candidate_b: &str) -> Result<String> {
+        match &self.body {
+            Some(body) => Ok(render_override(
+                body,
+                &[("job", job), ("candidate_a", candidate_a), ("candidate_b", candidate_b)],
+            )),
+            None => Ok(RankPairPrompt {
+                job,
+                candidate_a,
+                candidate_b,
+            }
+            .render()?),
+        }
+    }
+
+    pub fn render_translate(&self, resume: &str, language: &str) -> Result<String> {
+        match &self.body {
+            Some(body) => Ok(render_override(
+                body,
+                &[("resume", resume), ("language", language)],
+            )),
+            None => Ok(TranslateResumePrompt { resume, language }.render()?),
+        }
+    }
+}
+
+/// Source of the builtin template, for projects to start an override from
+pub fn builtin_source(name: &str) -> &'static str {
+    match name {
+        SCORE_RESUME => include_str!("../../../../templates/prompts/score_resume.txt"),
+        EXTRACT_JOB => include_str!("../../../../templates/prompts/extract_job.txt"),
+        RANK_PAIR => include_str!("../../../../templates/prompts/rank_pair.txt"),
+        TRANSLATE_RESUME => include_str!("../../../../templates/prompts/translate_resume.txt"),
+        _ => "",
+    }
+}
+
+pub fn variables(name: &str) -> &'static [&'static str] {
+    PROMPTS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, vars)| *vars)
+        .unwrap_or_default()
+}
+
+/// Variables an override may use but can leave out
+pub fn optional_variables(name: &str) -> &'static [&'static str] {
+    match name {
+        SCORE_RESUME => &["examples"],
+        _ => &[],
+    }
+}
+
+pub fn validate_name(name: &str) -> Result<()> {
+    if PROMPTS.iter().any(|(n, _)| *n == name) {
+        Ok(())
+    } else {
+        Err(StandardError::new("ERR-PROMPT-001").interpolate_err(name.to_string()))
+    }
+}
+
+enum Piece<'a> {
+    Text(&'a str),
+    Var(&'a str),
+}
+
+/// Splits a body at its `{{ name }}` placeholders in a single pass, whitespace inside the
+/// braces is ignored. Only names `known` accepts are placeholders, anything else is text.
+fn scan<'a>(body: &'a str, known: impl Fn(&str) -> bool) -> Vec<Piece<'a>> {
+    let mut pieces = vec![];
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        pieces.push(Piece::Text(&rest[..start]));
+        let tail = &rest[start..];
+        let name = tail
+            .find("}}")
+            .map(|end| (tail[2..end].trim(), end + 2))
+            .filter(|(name, _)| known(name));
+        match name {
+            Some((name, consumed)) => {
+                pieces.push(Piece::Var(name));
+                rest = &tail[consumed..];
+            }
+            None => {
+                pieces.push(Piece::Text("{{"));
+                rest = &tail[2..];
+            }
+        }
+    }
+    pieces.push(Piece::Text(rest));
+    pieces
+}
+
+/// Whether an override body uses `var` the way `render_override` will find it
+pub fn uses_variable(body: &str, var: &str) -> bool {
+    scan(body, |name| name == var)
+        .iter()
+        .any(|piece| matches!(piece, Piece::Var(_)))
+}
+
+/// Overrides are stored text, so they only support plain `{{ var }}` substitution.
+/// The body is scanned once, so placeholders inside substituted values stay as they are.
+pub fn render_override(body: &str, vars: &[(&str, &str)]) -> String {
+    let value = |name: &str| vars.iter().find(|(var, _)| *var == name).map(|(_, value)| *value);
+    scan(body, |name| value(name).is_some())
+        .into_iter()
+        .map(|piece| match piece {
+            Piece::Text(text) => text,
+            Piece::Var(name) => value(name).unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// `version` 0 forces the builtin template, None picks the project's latest override
+pub async fn resolve(
+    conn: &mut PgConnection,
+    project_id: Option<&str>,
+    name: &str,
+    version: Option<i32>,
+) -> Result<PromptChoice> {
+    validate_name(name)?;
+    let project_id = match (project_id, version) {
+        (_, Some(0)) | (None, None) => return Ok(PromptChoice::builtin(name)),
+        (None, Some(v)) => {
+            return Err(StandardError::new("ERR-PROMPT-002").interpolate_err(format!("{}@v{}", name, v)));
+        }
+        (Some(project_id), _) => project_id,
+    };
+    match PromptSelector::new(conn)
+        .get_version(project_id, name, version)
+        .await?
+    {
+        Some(entry) => Ok(PromptChoice {
+            name: entry.name,
+            version: entry.version,
+            body: Some(entry.body),
+        }),
+        None if version.is_none() => Ok(PromptChoice::builtin(name)),
+        None => Err(StandardError::new("ERR-PROMPT-002")
+            .interpolate_err(format!("{}@v{}", name, version.unwrap_or_default()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_override_substitutes_known_variables() {
+        let body = "Resume: {{ resume }}\nJob: {{job}}\nReturn {\"score\": \"1\"} and {{ other }}";
+        let rendered = render_override(body, &[("resume", "R"), ("job", "J")]);
+        assert_eq!(rendered, "Resume: R\nJob: J\nReturn {\"score\": \"1\"} and {{ other }}");
+    }
+
+    #[test]
+    fn test_render_override_does_not_expand_substituted_values() {
+        let body = "A: {{ candidate_a }}\nB: {{candidate_b}}\nJob: {{ job }}";
+        let rendered = render_override(
+            body,
+            &[("job", "J"), ("candidate_a", "see {{ candidate_b }} and {{job}}"), ("candidate_b", "B")],
+        );
+        assert_eq!(rendered, "A: see {{ candidate_b }} and {{job}}\nB: B\nJob: J");
+    }
+
+    #[test]
+    fn test_uses_variable_accepts_any_spacing() {
+        for body in ["{{resume}}", "{{ resume }}", "{{  resume }}", "{{resume }}", "{{\tresume\n}}"] {
+            assert!(uses_variable(body, "resume"), "{:?}", body);
+            assert_eq!(render_override(body, &[("resume", "R")]), "R");
+        }
+        assert!(!uses_variable("{{ resumes }} and {resume}", "resume"));
+    }
+
+    #[test]
+    fn test_builtin_prompt_renders_braces_verbatim() {
+        let prompt = PromptChoice::builtin(SCORE_RESUME)
+            .render_score("my resume", "my job", "")
+            .unwrap();
+        assert!(prompt.contains("my resume"));
+        assert!(prompt.contains("\"status\": \"accepted or rejected\""));
+        assert!(!prompt.contains("PAST RECRUITER CORRECTIONS"));
+    }
+}
@@ -0,0 +1,131 @@
+use std::{collections::HashSet, sync::Arc};
+
+use serde::Deserialize;
+use standard_error::{Interpolate, StandardError};
+
+use crate::{
+    pkg::internal::ai::{client::AIClient, generate::GenerateOps, prompts::PromptChoice},
+    prelude::Result,
+};
+
+pub const DEFAULT_TOP_N: usize = 10;
+pub const MAX_TOP_N: usize = 50;
+pub const MAX_BUDGET: usize = 300;
+/// A running run without a recorded comparison for this long is taken as dead, e.g. after a restart
+pub const STALE_RUN_MINS: i32 = 15;
+
+#[derive(Debug, Deserialize)]
+pub struct PairVerdict {
+    pub winner: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Asks the model which of two candidates fits the job better; true when `a` wins
+pub async fn compare_pair(
+    ai_client: &Arc<AIClient>,
+    prompt: &PromptChoice,
+    job: &str,
+    a: &str,
+    b: &str,
+) -> Result<(bool, String)> {
+    let prompt = prompt.render_pair(job, a, b)?;
+    let res = ai_client.direct_query(&prompt, None).await?;
+    let cleaned_json = res.trim_start_matches("```json").trim_end_matches("```");
+    tracing::debug!("AI Result: \n {}", &cleaned_json);
+    let verdict: PairVerdict = serde_json::from_str(cleaned_json)?;
+    match verdict.winner.trim().to_uppercase().as_str() {
+        "A" => Ok((true, verdict.reason)),
+        "B" => Ok((false, verdict.reason)),
+        other => Err(StandardError::new("ERR-AI-008").interpolate_err(format!("invalid winner {}", other))),
+    }
+}
+
+/// Enough comparisons for a few Swiss rounds, never more than every pair once
+pub fn default_budget(n: usize) -> usize {
+    let all_pairs = n * n.saturating_sub(1) / 2;
+    (n * 3).min(all_pairs).min(MAX_BUDGET)
+}
+
+/// Swiss-style round: neighbours in the current order meet, skipping pairs already played
+pub fn next_round(order: &[usize], played: &HashSet<(usize, usize)>) -> Vec<(usize, usize)> {
+    let mut paired = vec![false; order.len()];
+    let mut round = vec![];
+    for i in 0..order.len() {
+        if paired[i] {
+            continue;
+        }
+        for j in (i + 1)..order.len() {
+            let (a, b) = (order[i], order[j]);
+            if paired[j] || played.contains(&(a.min(b), a.max(b))) {
+                continue;
+            }
+            paired[i] = true;
+            paired[j] = true;
+            round.push((a, b));
+            break;
+        }
+    }
+    round
+}
+
+/// Bradley–Terry strengths from `(winner, loser)` results, fitted with the MM algorithm.
+/// Every player also draws once against a virtual opponent of strength 1, which keeps
+/// unbeaten and winless players finite.
+pub fn bradley_terry(n: usize, results: &[(usize, usize)]) -> Vec<f64> {
+    let mut wins = vec![0.5; n];
+    let mut games = vec![vec![0u32; n]; n];
+    for &(winner, loser) in results {
+        wins[winner] += 1.0;
+        games[winner][loser] += 1;
+        games[loser][winner] += 1;
+    }
+    let mut strength = vec![1.0; n];
+    for _ in 0..200 {
+        let mut next = vec![0.0; n];
+        for i in 0..n {
+            let mut denominator = 1.0 / (strength[i] + 1.0);
+            for j in 0..n {
+                if games[i][j] > 0 {
+                    denominator += games[i][j] as f64 / (strength[i] + strength[j]);
+                }
+            }
+            next[i] = wins[i] / denominator;
+        }
+        let delta = next
+            .iter()
+            .zip(&strength)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        strength = next;
+        if delta < 1e-9 {
+            break;
+        }
+    }
+    strength
+}
+
+/// Indices ordered strongest first; ties keep the incoming order, so the result is stable
+pub fn order_by_strength(strength: &[f64]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..strength.len()).collect();
+    order.sort_by(|a, b| strength[*b].total_cmp(&strength[*a]));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bradley_terry_orders_by_results() {
+        // 2 beats everyone, 0 beats 1
+        let results = [(2, 0), (2, 1), (0, 1), (2, 0)];
+        let order = order_by_strength(&bradley_terry(3, &results));
+        assert_eq!(order, vec![2, 0, 1]);
+
+        let mut played = HashSet::new();
+        played.insert((0, 1));
+        assert_eq!(next_round(&[0, 1, 2, 3], &played), vec![(0, 2), (1, 3)]);
+        assert_eq!(default_budget(4), 6);
+    }
+}
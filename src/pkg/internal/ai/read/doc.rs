@@ -0,0 +1,271 @@
+//! Word 97-2003 (`.doc`) text extraction.
+//!
+//! The file is an OLE2 compound file. Text lives in the `WordDocument` stream and is
+//! located through the piece table (CLX) stored in the `0Table` or `1Table` stream.
+//! See [MS-DOC] 2.4.1 "Retrieving Text".
+
+use std::io::{Cursor, Read};
+
+use encoding_rs::WINDOWS_1252;
+use standard_error::{Interpolate, StandardError};
+
+use crate::prelude::Result;
+
+const WORD_IDENT: u16 = 0xA5EC;
+const FIB_FLAGS: usize = 0x0A;
+const F_ENCRYPTED: u16 = 0x0100;
+const F_WHICH_TBL_STM: u16 = 0x0200;
+/// FibRgLw97.ccpText, the character count of the main document
+const CCP_TEXT: usize = 3;
+/// FibRgFcLcb97.fcClx / lcbClx pair index
+const CLX_PAIR: usize = 33;
+
+fn doc_err(reason: &str) -> StandardError {
+    StandardError::new("ERR-AI-005").interpolate_err(format!("doc: {}", reason))
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| doc_err("truncated file"))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| doc_err("truncated file"))
+}
+
+fn read_stream(file: &mut cfb::CompoundFile<Cursor<&[u8]>>, name: &str) -> Result<Vec<u8>> {
+    let mut stream = file
+        .open_stream(name)
+        .map_err(|_| doc_err(&format!("missing {} stream", name)))?;
+    let mut buf = vec![];
+    stream
+        .read_to_end(&mut buf)
+        .map_err(|e| doc_err(&e.to_string()))?;
+    Ok(buf)
+}
+
+struct Piece {
+    cp_start: u32,
+    cp_end: u32,
+    offset: usize,
+    compressed: bool,
+}
+
+/// Walks the CLX: skips the Prc property blocks and parses the PlcPcd piece table
+fn pieces(clx: &[u8]) -> Result<Vec<Piece>> {
+    let mut pos = 0;
+    while clx.get(pos) == Some(&0x01) {
+        let cb = u16_at(clx, pos + 1)? as usize;
+        pos += 3 + cb;
+    }
+    if clx.get(pos) != Some(&0x02) {
+        return Err(doc_err("piece table not found"));
+    }
+    let lcb = u32_at(clx, pos + 1)? as usize;
+    let plc = clx
+        .get(pos + 5..pos + 5 + lcb)
+        .ok_or_else(|| doc_err("truncated piece table"))?;
+    // (n + 1) character positions followed by n 8-byte piece descriptors
+    let n = (lcb.saturating_sub(4)) / 12;
+    let mut result = Vec::with_capacity(n);
+    for i in 0..n {
+        let cp_start = u32_at(plc, i * 4)?;
+        let cp_end = u32_at(plc, (i + 1) * 4)?;
+        let fc = u32_at(plc, (n + 1) * 4 + i * 8 + 2)?;
+        let compressed = fc & 0x4000_0000 != 0;
+        let fc = (fc & 0x3FFF_FFFF) as usize;
+        result.push(Piece {
+            cp_start,
+            cp_end,
+            offset: if compressed { fc / 2 } else { fc },
+            compressed,
+        });
+    }
+    Ok(result)
+}
+
+fn decode_pieces(word: &[u8], pieces: &[Piece], limit: u32) -> Result<Vec<char>> {
+    let mut chars = vec![];
+    for piece in pieces {
+        if piece.cp_start >= limit {
+            break;
+        }
+        let count = piece
+            .cp_end
+            .min(limit)
+            .checked_sub(piece.cp_start)
+            .ok_or_else(|| doc_err("invalid piece table"))? as usize;
+        if piece.compressed {
+            let bytes = word
+                .get(piece.offset..piece.offset.saturating_add(count))
+                .ok_or_else(|| doc_err("piece outside WordDocument stream"))?;
+            chars.extend(WINDOWS_1252.decode_without_bom_handling(bytes).0.chars());
+        } else {
+            let bytes = word
+                .get(piece.offset..piece.offset.saturating_add(count.saturating_mul(2)))
+                .ok_or_else(|| doc_err("piece outside WordDocument stream"))?;
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .collect();
+            chars.extend(char::decode_utf16(units).map(|c| c.unwrap_or('\u{FFFD}')));
+        }
+    }
+    Ok(chars)
+}
+
+/// Turns Word control characters into plain text. Field instructions are dropped and
+/// only their results kept; table cells become tabs and rows become lines.
+fn clean(chars: &[char]) -> String {
+    let mut text = String::with_capacity(chars.len());
+    // one entry per open field, true once its separator was seen
+    let mut fields: Vec<bool> = vec![];
+    for &c in chars {
+        match c {
+            '\u{13}' => {
+                fields.push(false);
+                continue;
+            }
+            '\u{14}' => {
+                if let Some(in_result) = fields.last_mut() {
+                    *in_result = true;
+                }
+                continue;
+            }
+            '\u{15}' => {
+                fields.pop();
+                continue;
+            }
+            _ => {}
+        }
+        if fields.last() == Some(&false) {
+            continue;
+        }
+        match c {
+            '\r' | '\u{0B}' | '\u{0C}' => text.push('\n'),
+            // a cell mark right after another one closes the table row
+            '\u{07}' => {
+                if text.ends_with('\t') {
+                    text.pop();
+                    text.push('\n');
+                } else {
+                    text.push('\t');
+                }
+            }
+            '\t' => text.push('\t'),
+            '\u{1E}' => text.push('-'),
+            '\u{A0}' => text.push(' '),
+            c if (c as u32) < 0x20 => {}
+            c => text.push(c),
+        }
+    }
+    text.lines()
+        .map(|l| l.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+pub fn extract_text_from_doc(data: &[u8]) -> Result<String> {
+    let mut file = cfb::CompoundFile::open(Cursor::new(data))
+        .map_err(|e| doc_err(&format!("not an OLE2 file: {}", e)))?;
+    let word = read_stream(&mut file, "WordDocument")?;
+    if u16_at(&word, 0)? != WORD_IDENT {
+        return Err(doc_err("not a Word document"));
+    }
+    let flags = u16_at(&word, FIB_FLAGS)?;
+    if flags & F_ENCRYPTED != 0 {
+        return Err(doc_err("document is password protected"));
+    }
+    let table_name = if flags & F_WHICH_TBL_STM != 0 { "1Table" } else { "0Table" };
+    let table = read_stream(&mut file, table_name)?;
+
+    // FibBase is 32 bytes, followed by the variable length fibRgW, fibRgLw and fibRgFcLcb
+    let csw = u16_at(&word, 32)? as usize;
+    let rg_lw = 34 + csw * 2 + 2;
+    let cslw = u16_at(&word, rg_lw - 2)? as usize;
+    let ccp_text = u32_at(&word, rg_lw + CCP_TEXT * 4)?;
+    let rg_fc_lcb = rg_lw + cslw * 4 + 2;
+    let fc_clx = u32_at(&word, rg_fc_lcb + CLX_PAIR * 8)? as usize;
+    let lcb_clx = u32_at(&word, rg_fc_lcb + CLX_PAIR * 8 + 4)? as usize;
+    if lcb_clx == 0 {
+        return Err(doc_err("unsupported pre-97 Word format"));
+    }
+    let clx = table
+        .get(fc_clx..fc_clx + lcb_clx)
+        .ok_or_else(|| doc_err("piece table outside table stream"))?;
+
+    let chars = decode_pieces(&word, &pieces(clx)?, ccp_text)?;
+    let text = clean(&chars);
+    if text.is_empty() {
+        return Err(doc_err("no text found"));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    /// Builds a minimal Word 97 file: one compressed piece holding `text`
+    fn build_doc(text: &[u8]) -> Vec<u8> {
+        let text_offset = 1024usize;
+        let mut word = vec![0u8; text_offset + text.len()];
+        word[0..2].copy_from_slice(&WORD_IDENT.to_le_bytes());
+        word[FIB_FLAGS..FIB_FLAGS + 2].copy_from_slice(&F_WHICH_TBL_STM.to_le_bytes());
+        let csw = 14usize;
+        word[32..34].copy_from_slice(&(csw as u16).to_le_bytes());
+        let rg_lw = 34 + csw * 2 + 2;
+        word[rg_lw - 2..rg_lw].copy_from_slice(&22u16.to_le_bytes());
+        word[rg_lw + CCP_TEXT * 4..rg_lw + CCP_TEXT * 4 + 4]
+            .copy_from_slice(&(text.len() as u32).to_le_bytes());
+        let rg_fc_lcb = rg_lw + 22 * 4 + 2;
+        word[rg_fc_lcb - 2..rg_fc_lcb].copy_from_slice(&93u16.to_le_bytes());
+        word[text_offset..].copy_from_slice(text);
+
+        let mut plc = vec![];
+        plc.extend(0u32.to_le_bytes());
+        plc.extend((text.len() as u32).to_le_bytes());
+        plc.extend(0u16.to_le_bytes());
+        plc.extend(((text_offset as u32 * 2) | 0x4000_0000).to_le_bytes());
+        plc.extend(0u16.to_le_bytes());
+        let mut clx = vec![0x02];
+        clx.extend((plc.len() as u32).to_le_bytes());
+        clx.extend(plc);
+        let clx_at = rg_fc_lcb + CLX_PAIR * 8;
+        word[clx_at..clx_at + 4].copy_from_slice(&0u32.to_le_bytes());
+        word[clx_at + 4..clx_at + 8].copy_from_slice(&(clx.len() as u32).to_le_bytes());
+
+        let mut file = cfb::CompoundFile::create(Cursor::new(Vec::new())).unwrap();
+        file.create_stream("WordDocument").unwrap().write_all(&word).unwrap();
+        file.create_stream("1Table").unwrap().write_all(&clx).unwrap();
+        file.flush().unwrap();
+        file.into_inner().into_inner()
+    }
+
+    #[test]
+    fn test_extract_text_from_doc_with_table_and_field() {
+        let doc = build_doc(
+            b"Jane Doe\rSkills\rRust\x07Go\x07\x07\x13 HYPERLINK \"x\" \x14jane@example.com\x15\r",
+        );
+        let text = extract_text_from_doc(&doc).unwrap();
+        assert_eq!(text, "Jane Doe\nSkills\nRust\tGo\njane@example.com");
+    }
+
+    #[test]
+    fn test_reversed_piece_is_rejected() {
+        let piece = Piece {
+            cp_start: 10,
+            cp_end: 4,
+            offset: 0,
+            compressed: true,
+        };
+        assert!(decode_pieces(&[0u8; 64], &[piece], 20).is_err());
+    }
+}
@@ -0,0 +1,408 @@
+//! Word 2007+ (`.docx`) text extraction.
+//!
+//! Walks every block the document holds, in reading order: paragraphs, tables, content
+//! controls, text boxes and the table of contents, framed by the page headers and footers.
+//! Headings come out as `#` lines and list items as `-` or `1.` lines so that section
+//! detection downstream sees the same structure a reader would.
+
+use std::collections::HashMap;
+
+use docx_rs::{
+    DocumentChild, Docx, DrawingData, InsertChild, Paragraph, ParagraphChild, Run, RunChild,
+    StructuredDataTag, StructuredDataTagChild, Table, TableCellContent, TableChild,
+    TableOfContents, TableRowChild, TextBoxContentChild, TocContent,
+};
+use standard_error::{Interpolate, StandardError};
+
+use crate::prelude::Result;
+
+/// Bullet glyphs some templates type by hand instead of using list numbering
+const BULLETS: &[char] = &['•', '●', '○', '▪', '■', '◦', '–', '➢', '✓', '\u{F0B7}'];
+
+fn docx_err(reason: &str) -> StandardError {
+    StandardError::new("ERR-AI-005").interpolate_err(format!("docx: {}", reason))
+}
+
+struct Extractor<'a> {
+    docx: &'a Docx,
+    links: HashMap<&'a str, &'a str>,
+    /// next number per (numbering id, level) for ordered lists
+    counters: HashMap<(usize, usize), usize>,
+    blocks: Vec<String>,
+}
+
+impl<'a> Extractor<'a> {
+    fn new(docx: &'a Docx) -> Self {
+        Self {
+            docx,
+            links: docx
+                .hyperlinks
+                .iter()
+                .map(|(id, target, _)| (id.as_str(), target.as_str()))
+                .collect(),
+            counters: HashMap::new(),
+            blocks: vec![],
+        }
+    }
+
+    /// Renders blocks into a scratch buffer, e.g. the inside of a table cell
+    fn capture(&mut self, render: impl FnOnce(&mut Self)) -> Vec<String> {
+        let outer = std::mem::take(&mut self.blocks);
+        render(self);
+        std::mem::replace(&mut self.blocks, outer)
+    }
+
+    fn heading_level(&self, paragraph: &Paragraph) -> Option<usize> {
+        let style_id = paragraph.property.style.as_ref().map(|s| s.val.as_str())?;
+        let style = self
+            .docx
+            .styles
+            .styles
+            .iter()
+            .find(|s| s.style_id == style_id);
+        let outline = paragraph
+            .property
+            .outline_lvl
+            .as_ref()
+            .or_else(|| style.and_then(|s| s.paragraph_property.outline_lvl.as_ref()))
+            .map(|o| o.v + 1);
+        let id = style_id.to_lowercase();
+        if id == "title" {
+            return Some(1);
+        }
+        if let Some(level) = id.strip_prefix("heading") {
+            return Some(level.parse().unwrap_or(1));
+        }
+        outline.filter(|level| *level <= 6)
+    }
+
+    fn list_marker(&mut self, paragraph: &Paragraph) -> Option<String> {
+        let numbering = paragraph.property.numbering_property.as_ref().or_else(|| {
+            let style_id = paragraph.property.style.as_ref()?.val.as_str();
+            self.docx
+                .styles
+                .styles
+                .iter()
+                .find(|s| s.style_id == style_id)?
+                .paragraph_property
+                .numbering_property
+                .as_ref()
+        })?;
+        let id = numbering.id.as_ref()?.id;
+        // numbering id 0 explicitly turns numbering off
+        if id == 0 {
+            return None;
+        }
+        let level = numbering.level.as_ref().map(|l| l.val).unwrap_or(0);
+        let numberings = &self.docx.numberings;
+        let format = numberings
+            .numberings
+            .iter()
+            .find(|n| n.id == id)
+            .and_then(|n| {
+                numberings
+                    .abstract_nums
+                    .iter()
+                    .find(|a| a.id == n.abstract_num_id)
+            })
+            .and_then(|a| a.levels.iter().find(|l| l.level == level))
+            .map(|l| l.format.val.as_str())
+            .unwrap_or("bullet");
+        let indent = "  ".repeat(level);
+        if matches!(format, "bullet" | "none") {
+            return Some(format!("{}- ", indent));
+        }
+        // a new item resets the deeper levels of the same list
+        self.counters
+            .retain(|(list, deeper), _| *list != id || *deeper <= level);
+        let counter = self.counters.entry((id, level)).or_insert(0);
+        *counter += 1;
+        Some(format!("{}{}. ", indent, counter))
+    }
+
+    fn run(&mut self, run: &Run, line: &mut String) {
+        for child in &run.children {
+            match child {
+                RunChild::Text(t) => line.push_str(&t.text),
+                RunChild::Tab(_) | RunChild::PTab(_) => line.push('\t'),
+                RunChild::Break(_) => line.push('\n'),
+                RunChild::Drawing(drawing) => {
+                    if let Some(DrawingData::TextBox(text_box)) = &drawing.data {
+                        let inner = self.capture(|this| {
+                            for child in &text_box.children {
+                                match child {
+                                    TextBoxContentChild::Paragraph(p) => this.paragraph(p),
+                                    TextBoxContentChild::Table(t) => this.table(t),
+                                }
+                            }
+                        });
+                        // text boxes float, so they read as blocks of their own
+                        self.blocks.extend(inner);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn inline(&mut self, children: &[ParagraphChild], line: &mut String) {
+        for child in children {
+            match child {
+                ParagraphChild::Run(run) => self.run(run, line),
+                ParagraphChild::Insert(insert) => {
+                    for child in &insert.children {
+                        if let InsertChild::Run(run) = child {
+                            self.run(run, line);
+                        }
+                    }
+                }
+                ParagraphChild::Hyperlink(link) => {
+                    let mut label = String::new();
+                    self.inline(&link.children, &mut label);
+                    line.push_str(&label);
+                    if let docx_rs::HyperlinkData::External { rid, .. } = &link.link
+                        && let Some(target) = self.links.get(rid.as_str())
+                    {
+                        let shown = target.trim_start_matches("mailto:");
+                        if !label.contains(shown) {
+                            line.push_str(&format!(" ({})", shown));
+                        }
+                    }
+                }
+                ParagraphChild::StructuredDataTag(tag) => {
+                    for child in &tag.children {
+                        if let StructuredDataTagChild::Run(run) = child {
+                            self.run(run, line);
+                        }
+                    }
+                }
+                // tracked deletions, bookmarks, comments and page fields carry no content
+                _ => {}
+            }
+        }
+    }
+
+    fn paragraph(&mut self, paragraph: &Paragraph) {
+        let mut line = String::new();
+        // text boxes anchored in this paragraph land before it
+        self.inline(&paragraph.children, &mut line);
+        let text = line.trim();
+        if text.is_empty() {
+            return;
+        }
+        let block = if let Some(level) = self.heading_level(paragraph) {
+            format!("{} {}", "#".repeat(level), text)
+        } else if let Some(marker) = self.list_marker(paragraph) {
+            format!("{}{}", marker, text)
+        } else if let Some(rest) = text.strip_prefix(BULLETS) {
+            format!("- {}", rest.trim_start())
+        } else {
+            text.to_string()
+        };
+        self.blocks.push(block);
+    }
+
+    /// Data tables come out one row per line with tab separated cells. Layout tables,
+    /// where cells hold several blocks, are read cell by cell instead so that the
+    /// sections of a two-column CV stay together.
+    fn table(&mut self, table: &Table) {
+        for row in &table.rows {
+            let TableChild::TableRow(row) = row;
+            let cells: Vec<Vec<String>> = row
+                .cells
+                .iter()
+                .map(|cell| {
+                    let TableRowChild::TableCell(cell) = cell;
+                    self.capture(|this| {
+                        for content in &cell.children {
+                            match content {
+                                TableCellContent::Paragraph(p) => this.paragraph(p),
+                                TableCellContent::Table(t) => this.table(t),
+                                TableCellContent::StructuredDataTag(tag) => this.tag(tag),
+                                TableCellContent::TableOfContents(toc) => this.toc(toc),
+                            }
+                        }
+                    })
+                })
+                .collect();
+            let layout = cells
+                .iter()
+                .any(|blocks| blocks.len() > 1 || blocks.iter().any(|b| b.contains('\n')));
+            if layout {
+                self.blocks.extend(cells.into_iter().flatten());
+            } else {
+                let line = cells
+                    .iter()
+                    .map(|blocks| blocks.join(" "))
+                    .collect::<Vec<_>>()
+                    .join("\t");
+                if !line.trim().is_empty() {
+                    self.blocks.push(line.trim_end().to_string());
+                }
+            }
+        }
+    }
+
+    fn tag(&mut self, tag: &StructuredDataTag) {
+        let mut line = String::new();
+        for child in &tag.children {
+            match child {
+                StructuredDataTagChild::Run(run) => self.run(run, &mut line),
+                StructuredDataTagChild::Paragraph(p) => self.paragraph(p),
+                StructuredDataTagChild::Table(t) => self.table(t),
+                StructuredDataTagChild::StructuredDataTag(inner) => self.tag(inner),
+                _ => {}
+            }
+        }
+        if !line.trim().is_empty() {
+            self.blocks.push(line.trim().to_string());
+        }
+    }
+
+    fn toc(&mut self, toc: &TableOfContents) {
+        let contents = |this: &mut Self, contents: &[TocContent]| {
+            for content in contents {
+                match content {
+                    TocContent::Paragraph(p) => this.paragraph(p),
+                    TocContent::Table(t) => this.table(t),
+                }
+            }
+        };
+        contents(self, &toc.before_contents);
+        for item in &toc.items {
+            self.blocks.push(item.text.clone());
+        }
+        contents(self, &toc.after_contents);
+    }
+
+    fn document(&mut self, children: &[DocumentChild]) {
+        for child in children {
+            match child {
+                DocumentChild::Paragraph(p) => self.paragraph(p),
+                DocumentChild::Table(t) => self.table(t),
+                DocumentChild::StructuredDataTag(tag) => self.tag(tag),
+                DocumentChild::TableOfContents(toc) => self.toc(toc),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Text of the first page and default headers or footers, without repeats
+fn page_margins<'a, C: 'a>(
+    extractor: &mut Extractor,
+    parts: impl Iterator<Item = &'a Vec<C>>,
+    render: impl Fn(&mut Extractor, &C),
+) -> Vec<String> {
+    let mut seen = vec![];
+    for children in parts {
+        let blocks = extractor.capture(|this| {
+            for child in children {
+                render(this, child);
+            }
+        });
+        let text = blocks.join("\n");
+        if !text.is_empty() && !seen.contains(&text) {
+            seen.push(text);
+        }
+    }
+    seen
+}
+
+pub fn extract_text_from_docx(data: &[u8]) -> Result<String> {
+    let docx = docx_rs::read_docx(data).map_err(|e| docx_err(&e.to_string()))?;
+    let section = &docx.document.section_property;
+    let mut extractor = Extractor::new(&docx);
+
+    let headers = page_margins(
+        &mut extractor,
+        [&section.first_header, &section.header, &section.even_header]
+            .into_iter()
+            .flatten()
+            .map(|h| &h.children),
+        |this, child| match child {
+            docx_rs::HeaderChild::Paragraph(p) => this.paragraph(p),
+            docx_rs::HeaderChild::Table(t) => this.table(t),
+            docx_rs::HeaderChild::StructuredDataTag(tag) => this.tag(tag),
+        },
+    );
+    let footers = page_margins(
+        &mut extractor,
+        [&section.first_footer, &section.footer, &section.even_footer]
+            .into_iter()
+            .flatten()
+            .map(|f| &f.children),
+        |this, child| match child {
+            docx_rs::FooterChild::Paragraph(p) => this.paragraph(p),
+            docx_rs::FooterChild::Table(t) => this.table(t),
+            docx_rs::FooterChild::StructuredDataTag(tag) => this.tag(tag),
+        },
+    );
+    extractor.document(&docx.document.children);
+
+    let text = headers
+        .into_iter()
+        .chain(extractor.blocks)
+        .chain(footers)
+        .flat_map(|block| {
+            block
+                .lines()
+                .map(|l| l.trim_end().to_string())
+                .filter(|l| !l.trim().is_empty())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.is_empty() {
+        return Err(docx_err("no text found"));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use docx_rs::{
+        AbstractNumbering, Header, IndentLevel, Level, LevelJc, LevelText, NumberFormat,
+        Numbering, NumberingId, Start, TableCell, TableRow,
+    };
+
+    use super::*;
+
+    fn text_paragraph(text: &str) -> Paragraph {
+        Paragraph::new().add_run(Run::new().add_text(text))
+    }
+
+    #[test]
+    fn test_extract_text_from_docx_keeps_structure() {
+        let docx = Docx::new()
+            .header(Header::new().add_paragraph(text_paragraph("jane@example.com")))
+            .add_abstract_numbering(AbstractNumbering::new(2).add_level(Level::new(
+                0,
+                Start::new(1),
+                NumberFormat::new("bullet"),
+                LevelText::new("•"),
+                LevelJc::new("left"),
+            )))
+            .add_numbering(Numbering::new(2, 2))
+            .add_paragraph(text_paragraph("Jane Doe").style("Title"))
+            .add_paragraph(text_paragraph("Skills").style("Heading2"))
+            .add_table(Table::new(vec![TableRow::new(vec![
+                TableCell::new().add_paragraph(text_paragraph("Rust")),
+                TableCell::new().add_paragraph(text_paragraph("5 years")),
+            ])]))
+            .add_paragraph(
+                text_paragraph("Led the billing rewrite")
+                    .numbering(NumberingId::new(2), IndentLevel::new(0)),
+            );
+        let mut buf = Cursor::new(vec![]);
+        docx.build().pack(&mut buf).unwrap();
+
+        let text = extract_text_from_docx(buf.get_ref()).unwrap();
+        assert_eq!(
+            text,
+            "jane@example.com\n# Jane Doe\n## Skills\nRust\t5 years\n- Led the billing rewrite"
+        );
+    }
+}
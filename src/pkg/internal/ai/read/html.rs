@@ -0,0 +1,177 @@
+//! HTML to plain text.
+//!
+//! Block elements become line breaks, headings and list items keep the same markers as
+//! the DOCX and ODT readers, and scripts, styles and other non-visible content is dropped.
+
+use scraper::{ElementRef, Html, Node};
+use standard_error::{Interpolate, StandardError};
+
+use super::decode_text;
+use crate::prelude::Result;
+
+const SKIPPED: &[&str] = &[
+    "script", "style", "noscript", "template", "head", "svg", "iframe", "button", "select",
+];
+
+const BLOCKS: &[&str] = &[
+    "p", "div", "section", "article", "header", "footer", "main", "aside", "nav", "ul", "ol",
+    "dl", "dt", "dd", "table", "blockquote", "pre", "address", "form", "fieldset", "figure",
+    "figcaption", "hr", "body",
+];
+
+#[derive(Default)]
+struct Writer {
+    lines: Vec<String>,
+    line: String,
+    list_depth: usize,
+    pre: bool,
+    /// extra elements to leave out, e.g. page boilerplate
+    skip: Option<fn(ElementRef) -> bool>,
+}
+
+impl Writer {
+    fn text(&mut self, text: &str) {
+        if self.pre {
+            for (i, part) in text.split('\n').enumerate() {
+                if i > 0 {
+                    self.break_line();
+                }
+                self.line.push_str(part);
+            }
+            return;
+        }
+        for word in text.split_whitespace() {
+            if !self.line.is_empty() && !self.line.ends_with([' ', '\t']) {
+                self.line.push(' ');
+            }
+            self.line.push_str(word);
+        }
+        if text.ends_with(char::is_whitespace) && !self.line.is_empty() {
+            self.line.push(' ');
+        }
+    }
+
+    fn break_line(&mut self) {
+        let line = self.line.trim_end().to_string();
+        self.line.clear();
+        if !line.trim().is_empty() {
+            self.lines.push(line);
+        }
+    }
+
+    fn walk(&mut self, element: ElementRef) {
+        let name = element.value().name();
+        if SKIPPED.contains(&name) || self.skip.is_some_and(|skip| skip(element)) {
+            return;
+        }
+        match name {
+            "br" => return self.break_line(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.break_line();
+                self.line.push_str(&"#".repeat((name.as_bytes()[1] - b'0') as usize));
+                self.line.push(' ');
+            }
+            "li" => {
+                self.break_line();
+                self.line.push_str(&"  ".repeat(self.list_depth.saturating_sub(1)));
+                self.line.push_str("- ");
+            }
+            "ul" | "ol" => {
+                self.break_line();
+                self.list_depth += 1;
+            }
+            "tr" => self.break_line(),
+            "td" | "th" if !self.line.trim().is_empty() => {
+                self.line = self.line.trim_end().to_string();
+                self.line.push('\t');
+            }
+            "pre" => {
+                self.break_line();
+                self.pre = true;
+            }
+            "img" => {
+                if let Some(alt) = element.value().attr("alt") {
+                    self.text(alt);
+                }
+            }
+            _ if BLOCKS.contains(&name) => self.break_line(),
+            _ => {}
+        }
+
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.walk(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        match name {
+            "ul" | "ol" => {
+                self.break_line();
+                self.list_depth -= 1;
+            }
+            "pre" => {
+                self.break_line();
+                self.pre = false;
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "li" | "tr" => self.break_line(),
+            _ if BLOCKS.contains(&name) => self.break_line(),
+            _ => {}
+        }
+    }
+}
+
+/// Renders an HTML document or fragment as readable text, one block per line
+pub fn html_to_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let mut writer = Writer::default();
+    writer.walk(document.root_element());
+    writer.break_line();
+    writer.lines.join("\n")
+}
+
+/// Renders elements of a parsed page in order, leaving out those `skip` rejects
+pub fn elements_to_text<'a>(
+    elements: impl IntoIterator<Item = ElementRef<'a>>,
+    skip: fn(ElementRef) -> bool,
+) -> String {
+    let mut writer = Writer {
+        skip: Some(skip),
+        ..Writer::default()
+    };
+    for element in elements {
+        writer.walk(element);
+        writer.break_line();
+    }
+    writer.lines.join("\n")
+}
+
+pub fn extract_text_from_html(data: &[u8]) -> Result<String> {
+    let text = html_to_text(&decode_text(data));
+    if text.trim().is_empty() {
+        return Err(StandardError::new("ERR-AI-005").interpolate_err("html: no text found".to_string()));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_text_keeps_blocks() {
+        let html = r#"<html><head><title>CV</title><style>p{color:red}</style></head>
+<body><h1>Jane  Doe</h1><p>Backend <b>engineer</b><br>Berlin</p>
+<script>track()</script><ul><li>Rust</li><li>Go</li></ul>
+<table><tr><td>2019</td><td>Acme</td></tr></table></body></html>"#;
+        assert_eq!(
+            html_to_text(html),
+            "# Jane Doe\nBackend engineer\nBerlin\n- Rust\n- Go\n2019\tAcme"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Markdown to plain text.
+//!
+//! Headings and bullets already have the shape the other readers produce, so they are
+//! kept; links, images, emphasis and code markers are reduced to their text.
+
+use standard_error::{Interpolate, StandardError};
+
+use super::decode_text;
+use crate::prelude::Result;
+
+/// Rewrites `[text](url)` as `text (url)` and `![alt](src)` as `alt`
+fn inline_links(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(open) = rest.find('[') {
+        let image = rest[..open].ends_with('!');
+        let Some(close) = rest[open..].find("](").map(|i| open + i) else {
+            break;
+        };
+        let Some(end) = rest[close..].find(')').map(|i| close + i) else {
+            break;
+        };
+        let label = &rest[open + 1..close];
+        let url = &rest[close + 2..end];
+        out.push_str(&rest[..if image { open - 1 } else { open }]);
+        out.push_str(label);
+        if !image && !url.is_empty() && url != label {
+            out.push_str(&format!(" ({})", url));
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn strip_emphasis(line: &str) -> String {
+    let mut out = line.replace("**", "").replace("__", "").replace('`', "");
+    // single `*` and `_` only count as markers next to a word boundary
+    for marker in ['*', '_'] {
+        let chars: Vec<char> = out.chars().collect();
+        out = chars
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| {
+                if **c != marker {
+                    return true;
+                }
+                let before = i.checked_sub(1).map(|j| chars[j]);
+                let after = chars.get(i + 1);
+                before.is_some_and(|b| b.is_alphanumeric())
+                    && after.is_some_and(|a| a.is_alphanumeric())
+            })
+            .map(|(_, c)| *c)
+            .collect();
+    }
+    out
+}
+
+pub fn markdown_to_text(markdown: &str) -> String {
+    let mut lines = vec![];
+    let mut fenced = false;
+    for raw in markdown.lines() {
+        let trimmed = raw.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fenced = !fenced;
+            continue;
+        }
+        if fenced {
+            if !trimmed.is_empty() {
+                lines.push(raw.trim_end().to_string());
+            }
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.chars().all(|c| "-*_=".contains(c) || c == ' ') {
+            // blank lines, thematic breaks and setext underlines
+            continue;
+        }
+        let indent = raw.len() - raw.trim_start().len();
+        let mut line = trimmed.trim_start_matches('>').trim_start().to_string();
+        if let Some(rest) = line.strip_prefix(['*', '+', '-'])
+            && rest.starts_with(' ')
+        {
+            line = format!("{}- {}", "  ".repeat(indent / 2), rest.trim_start());
+        }
+        let line = strip_emphasis(&inline_links(&line));
+        let line = if line.starts_with('|') {
+            if line.chars().all(|c| "|-: ".contains(c)) {
+                continue;
+            }
+            line.trim_matches('|')
+                .split('|')
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join("\t")
+        } else {
+            line
+        };
+        lines.push(line.trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+pub fn extract_text_from_markdown(data: &[u8]) -> Result<String> {
+    let text = markdown_to_text(&decode_text(data));
+    if text.trim().is_empty() {
+        return Err(StandardError::new("ERR-AI-005").interpolate_err("markdown: no text found".to_string()));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_text_strips_markup() {
+        let md = "# Jane Doe\n\n**Backend** engineer at [Acme](https://acme.io) ![logo](a.png)\n\n\
+                  * Rust and `tokio`\n  + snake_case APIs\n\n---\n| Year | Company |\n|---|---|\n| 2019 | Acme |\n";
+        assert_eq!(
+            markdown_to_text(md),
+            "# Jane Doe\nBackend engineer at Acme (https://acme.io) logo\n- Rust and tokio\n  - snake_case APIs\nYear\tCompany\n2019\tAcme"
+        );
+    }
+}
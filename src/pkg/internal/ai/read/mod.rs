@@ -0,0 +1,323 @@
+use crate::prelude::Result;
+use serde::{Deserialize, Serialize};
+use standard_error::{Interpolate, StandardError};
+
+mod doc;
+mod docx;
+pub mod html;
+mod markdown;
+pub mod ocr;
+mod odt;
+mod pdf;
+mod rtf;
+pub mod sandbox;
+pub mod sniff;
+use doc::extract_text_from_doc;
+use docx::extract_text_from_docx;
+use encoding_rs::{UTF_16BE, UTF_16LE, WINDOWS_1252};
+use html::extract_text_from_html;
+use markdown::extract_text_from_markdown;
+use ocr::{Ocr, Tesseract};
+use odt::extract_text_from_odt;
+use pdf::{extract_text_from_pdf, page_images};
+use rtf::extract_text_from_rtf;
+
+use crate::conf::settings;
+
+/// Scanned pages beyond this are not sent to OCR
+const MAX_OCR_PAGES: usize = 10;
+
+/// How a registered format is turned into text
+pub enum Reader {
+    Text(fn(&[u8]) -> Result<String>),
+    /// text layer with an OCR fallback for scanned pages
+    Pdf,
+    /// OCR only
+    Image,
+}
+
+/// A document format resumes can be uploaded in
+pub struct Format {
+    /// label shown to users
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    /// canonical MIME type, used when storing uploads
+    pub mime_type: &'static str,
+    /// other MIME types seen for the same format
+    pub aliases: &'static [&'static str],
+    pub reader: Reader,
+}
+
+/// Every format [`extract`] understands. Upload validation is derived from this list,
+/// so supporting a new format only means adding an entry here.
+pub const FORMATS: &[Format] = &[
+    Format {
+        name: "PDF",
+        extensions: &["pdf"],
+        mime_type: "application/pdf",
+        aliases: &[],
+        reader: Reader::Pdf,
+    },
+    Format {
+        name: "DOC",
+        extensions: &["doc"],
+        mime_type: "application/msword",
+        aliases: &[],
+        reader: Reader::Text(extract_text_from_doc),
+    },
+    Format {
+        name: "DOCX",
+        extensions: &["docx"],
+        mime_type: "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        aliases: &[],
+        reader: Reader::Text(extract_text_from_docx),
+    },
+    Format {
+        name: "ODT",
+        extensions: &["odt"],
+        mime_type: "application/vnd.oasis.opendocument.text",
+        aliases: &[],
+        reader: Reader::Text(extract_text_from_odt),
+    },
+    Format {
+        name: "RTF",
+        extensions: &["rtf"],
+        mime_type: "application/rtf",
+        aliases: &["text/rtf"],
+        reader: Reader::Text(extract_text_from_rtf),
+    },
+    Format {
+        name: "HTML",
+        extensions: &["html", "htm"],
+        mime_type: "text/html",
+        aliases: &["application/xhtml+xml"],
+        reader: Reader::Text(extract_text_from_html),
+    },
+    Format {
+        name: "Markdown",
+        extensions: &["md", "markdown"],
+        mime_type: "text/markdown",
+        aliases: &["text/x-markdown"],
+        reader: Reader::Text(extract_text_from_markdown),
+    },
+    Format {
+        name: "TXT",
+        extensions: &["txt"],
+        mime_type: "text/plain",
+        aliases: &[],
+        reader: Reader::Text(extract_text_from_plain),
+    },
+    Format {
+        name: "PNG",
+        extensions: &["png"],
+        mime_type: "image/png",
+        aliases: &[],
+        reader: Reader::Image,
+    },
+    Format {
+        name: "JPEG",
+        extensions: &["jpg", "jpeg"],
+        mime_type: "image/jpeg",
+        aliases: &[],
+        reader: Reader::Image,
+    },
+    Format {
+        name: "TIFF",
+        extensions: &["tif", "tiff"],
+        mime_type: "image/tiff",
+        aliases: &[],
+        reader: Reader::Image,
+    },
+];
+
+/// Looks up a format by file extension, ignoring case
+pub fn format_for_extension(extension: &str) -> Option<&'static Format> {
+    let extension = extension.to_lowercase();
+    FORMATS
+        .iter()
+        .find(|f| f.extensions.contains(&extension.as_str()))
+}
+
+/// Looks up a format by MIME type; parameters such as `; charset=utf-8` are ignored
+pub fn format_for_mime(content_type: &str) -> Option<&'static Format> {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    FORMATS
+        .iter()
+        .find(|f| f.mime_type == mime || f.aliases.contains(&mime.as_str()))
+}
+
+/// Decodes text of unknown encoding: a BOM wins, then UTF-8, then Windows-1252
+pub fn decode_text(data: &[u8]) -> String {
+    if let Some(rest) = data.strip_prefix(b"\xEF\xBB\xBF") {
+        return String::from_utf8_lossy(rest).into_owned();
+    }
+    if let Some(rest) = data.strip_prefix(b"\xFF\xFE") {
+        return UTF_16LE.decode_without_bom_handling(rest).0.into_owned();
+    }
+    if let Some(rest) = data.strip_prefix(b"\xFE\xFF") {
+        return UTF_16BE.decode_without_bom_handling(rest).0.into_owned();
+    }
+    match std::str::from_utf8(data) {
+        Ok(text) => text.to_string(),
+        Err(_) => WINDOWS_1252.decode_without_bom_handling(data).0.into_owned(),
+    }
+}
+
+fn extract_text_from_plain(data: &[u8]) -> Result<String> {
+    Ok(decode_text(data))
+}
+
+/// Extracted text and how trustworthy it looks, from 0 (garbage) to 1
+#[derive(Serialize, Deserialize)]
+pub struct Extraction {
+    pub text: String,
+    pub quality: f64,
+    /// set when the text came from OCR
+    pub ocr_confidence: Option<f64>,
+}
+
+/// Text of an untrusted document, parsed within the sandbox limits
+pub async fn extract_document(data: Vec<u8>, content_type: &str) -> Result<String> {
+    Ok(sandbox::extract_sandboxed(data, content_type).await?.text)
+}
+
+pub fn extract(data: Vec<u8>, content_type: &str) -> Result<Extraction> {
+    extract_with(data, content_type, &Tesseract::from_settings())
+}
+
+/// Like [`extract`], with the OCR engine used for images and scanned PDFs
+pub fn extract_with(data: Vec<u8>, content_type: &str, ocr: &dyn Ocr) -> Result<Extraction> {
+    // stored objects without a usable content type are identified by their bytes
+    let format = format_for_mime(content_type)
+        .or_else(|| sniff::sniff(&data))
+        .ok_or_else(|| StandardError::new("ERR-AI-005"))?;
+    match format.reader {
+        Reader::Pdf => extract_pdf(&data, ocr),
+        Reader::Image => {
+            let recognized = ocr.recognize(&data)?;
+            Ok(Extraction {
+                quality: text_quality(&recognized.text),
+                text: recognized.text,
+                ocr_confidence: Some(recognized.confidence),
+            })
+        }
+        Reader::Text(read) => {
+            let text = read(&data)?;
+            let quality = text_quality(&text);
+            Ok(Extraction { text, quality, ocr_confidence: None })
+        }
+    }
+}
+
+/// Uses the text layer when it reads well, otherwise OCRs the page images and keeps
+/// whichever result reads better
+fn extract_pdf(data: &[u8], ocr: &dyn Ocr) -> Result<Extraction> {
+    let layer = extract_text_from_pdf(data, settings.max_document_pages).map(|text| Extraction {
+        quality: text_quality(&text),
+        text,
+        ocr_confidence: None,
+    });
+    if let Ok(read) = &layer
+        && read.quality >= settings.min_extraction_quality
+    {
+        return layer;
+    }
+    let scanned = page_images(data, settings.max_document_pages, MAX_OCR_PAGES).and_then(|images| {
+        let mut pages = vec![];
+        for image in images {
+            pages.push(ocr.recognize(&image)?);
+        }
+        if pages.is_empty() {
+            return Err(StandardError::new("ERR-AI-009").interpolate_err("no page images".to_string()));
+        }
+        let confidence = pages.iter().map(|p| p.confidence).sum::<f64>() / pages.len() as f64;
+        let text = pages
+            .into_iter()
+            .map(|p| p.text)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        Ok(Extraction {
+            quality: text_quality(&text),
+            text,
+            ocr_confidence: Some((confidence * 100.0).round() / 100.0),
+        })
+    });
+    match (layer, scanned) {
+        (Ok(layer), Ok(scanned)) if scanned.quality <= layer.quality => Ok(layer),
+        (_, Ok(scanned)) => Ok(scanned),
+        (Ok(layer), Err(e)) => {
+            tracing::warn!("ocr fallback failed: {}", e.message);
+            Ok(layer)
+        }
+        (Err(e), Err(ocr_error)) => {
+            tracing::warn!("ocr fallback failed: {}", ocr_error.message);
+            Err(e)
+        }
+    }
+}
+
+/// Scores how much extracted text looks like language rather than decoding garbage:
+/// the share of printable characters, weighted by the share of plausible words.
+/// Broken font encodings show up as replacement or private use characters, consonant
+/// soup, or text spaced out one letter at a time.
+pub fn text_quality(text: &str) -> f64 {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if chars.is_empty() {
+        return 0.0;
+    }
+    let bad = chars
+        .iter()
+        .filter(|c| {
+            **c == '\u{FFFD}' || c.is_control() || ('\u{E000}'..='\u{F8FF}').contains(*c)
+        })
+        .count();
+    let printable = 1.0 - bad as f64 / chars.len() as f64;
+
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || ",.;:()[]/|\"'".contains(c))
+        .filter(|w| !w.is_empty() && w.chars().all(char::is_alphabetic))
+        .collect();
+    if words.is_empty() {
+        return (printable * 0.3 * 100.0).round() / 100.0;
+    }
+    let plausible = words
+        .iter()
+        .filter(|w| {
+            let lower = w.to_lowercase();
+            let len = lower.chars().count();
+            if len == 1 {
+                return matches!(lower.as_str(), "a" | "i");
+            }
+            if !lower.is_ascii() {
+                // other scripts and accented words are not judged on English vowels
+                return len <= 30;
+            }
+            let mut run = 0;
+            let mut longest_run = 0;
+            for c in lower.chars() {
+                if "aeiouy".contains(c) {
+                    run = 0;
+                } else {
+                    run += 1;
+                    longest_run = longest_run.max(run);
+                }
+            }
+            len <= 25 && run < len && longest_run <= 5
+        })
+        .count();
+    let wordlike = plausible as f64 / words.len() as f64;
+    (printable * (0.3 + 0.7 * wordlike) * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_quality_flags_garbage() {
+        assert!(text_quality("Senior backend engineer with Rust and PostgreSQL experience") > 0.9);
+        assert!(text_quality("J o h n   D o e   E n g i n e e r") < 0.6);
+        assert!(text_quality("\u{FFFD}\u{FFFD}\u{E001} xqzt\u{FFFD} bcdfgh") < 0.3);
+        assert_eq!(text_quality("  "), 0.0);
+    }
+}
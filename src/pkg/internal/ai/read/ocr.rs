@@ -0,0 +1,129 @@
+//! Optical character recognition for scanned resumes.
+//!
+//! [`Tesseract`] drives the `tesseract` command line tool, so no native library has to
+//! be linked in; the image goes in on stdin and word boxes come back as TSV.
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use standard_error::{Interpolate, StandardError};
+
+use crate::{conf::settings, prelude::Result};
+
+/// Text read from an image, with the engine's mean word confidence from 0 to 1
+#[derive(Debug, Clone)]
+pub struct Recognized {
+    pub text: String,
+    pub confidence: f64,
+}
+
+pub trait Ocr: Send + Sync {
+    /// Reads one encoded image (PNG, JPEG, TIFF...)
+    fn recognize(&self, image: &[u8]) -> Result<Recognized>;
+}
+
+pub struct Tesseract {
+    command: String,
+    languages: String,
+}
+
+impl Tesseract {
+    pub fn new(command: &str, languages: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            languages: languages.to_string(),
+        }
+    }
+
+    pub fn from_settings() -> Self {
+        Self::new(&settings.ocr_command, &settings.ocr_languages)
+    }
+}
+
+fn ocr_err(reason: impl ToString) -> StandardError {
+    StandardError::new("ERR-AI-009").interpolate_err(reason.to_string())
+}
+
+/// Rebuilds text from tesseract's TSV: words joined per line, a blank line between
+/// paragraphs. Words with a negative confidence are layout rows, not text.
+pub fn parse_tsv(tsv: &str) -> Recognized {
+    let mut text = String::new();
+    let mut confidences = vec![];
+    let mut current: Option<(&str, &str, &str, &str)> = None;
+    for row in tsv.lines().skip(1) {
+        let cols: Vec<&str> = row.split('\t').collect();
+        if cols.len() < 12 || cols[0] != "5" {
+            continue;
+        }
+        let word = cols[11].trim();
+        let Ok(confidence) = cols[10].parse::<f64>() else {
+            continue;
+        };
+        if word.is_empty() || confidence < 0.0 {
+            continue;
+        }
+        let position = (cols[1], cols[2], cols[3], cols[4]);
+        match current {
+            Some(p) if p == position => text.push(' '),
+            Some(p) if (p.0, p.1, p.2) == (position.0, position.1, position.2) => text.push('\n'),
+            Some(_) => text.push_str("\n\n"),
+            None => {}
+        }
+        current = Some(position);
+        text.push_str(word);
+        confidences.push(confidence);
+    }
+    let confidence = if confidences.is_empty() {
+        0.0
+    } else {
+        confidences.iter().sum::<f64>() / confidences.len() as f64 / 100.0
+    };
+    Recognized {
+        text,
+        confidence: (confidence * 100.0).round() / 100.0,
+    }
+}
+
+impl Ocr for Tesseract {
+    fn recognize(&self, image: &[u8]) -> Result<Recognized> {
+        let mut child = Command::new(&self.command)
+            .args(["stdin", "stdout", "-l", &self.languages, "--psm", "3", "tsv"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ocr_err(format!("cannot run {}: {}", self.command, e)))?;
+        // tesseract only starts writing once stdin is closed, so the pipe is dropped here
+        child
+            .stdin
+            .take()
+            .ok_or_else(|| ocr_err("stdin unavailable"))?
+            .write_all(image)
+            .map_err(ocr_err)?;
+        let output = child.wait_with_output().map_err(ocr_err)?;
+        if !output.status.success() {
+            return Err(ocr_err(String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(parse_tsv(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tsv_groups_lines_and_paragraphs() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   1\t1\t0\t0\t0\t0\t0\t0\t600\t800\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t10\t10\t50\t12\t96\tJane\n\
+                   5\t1\t1\t1\t1\t2\t65\t10\t40\t12\t94\tDoe\n\
+                   5\t1\t1\t1\t2\t1\t10\t30\t80\t12\t90\tEngineer\n\
+                   5\t1\t2\t1\t1\t1\t10\t60\t60\t12\t80\tSkills\n";
+        let recognized = parse_tsv(tsv);
+        assert_eq!(recognized.text, "Jane Doe\nEngineer\n\nSkills");
+        assert_eq!(recognized.confidence, 0.9);
+    }
+}
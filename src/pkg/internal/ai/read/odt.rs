@@ -0,0 +1,181 @@
+//! OpenDocument text (`.odt`) extraction.
+//!
+//! The document body is `content.xml` inside the zip container. Headings, list items and
+//! table rows are written the same way as for DOCX so section detection sees one shape.
+
+use std::io::{Cursor, Read};
+
+use standard_error::{Interpolate, StandardError};
+use xml::reader::{EventReader, XmlEvent};
+
+use crate::prelude::Result;
+
+fn odt_err(reason: &str) -> StandardError {
+    StandardError::new("ERR-AI-005").interpolate_err(format!("odt: {}", reason))
+}
+
+/// Elements whose content is not part of the readable text
+const SKIPPED: &[&str] = &[
+    "annotation",
+    "tracked-changes",
+    "note-citation",
+    "sequence-decls",
+    "forms",
+];
+
+#[derive(Default)]
+struct Walker {
+    blocks: Vec<String>,
+    line: String,
+    /// heading level of the paragraph being read
+    heading: Option<usize>,
+    list_depth: usize,
+    /// true until the first paragraph of the current list item is written
+    item_start: bool,
+    /// cells of the outermost table row being read, and how deep in tables we are
+    row: Vec<String>,
+    table_depth: usize,
+    skip_depth: usize,
+}
+
+impl Walker {
+    fn end_paragraph(&mut self) {
+        let text = self.line.trim().to_string();
+        self.line.clear();
+        let heading = self.heading.take();
+        if text.is_empty() {
+            return;
+        }
+        if self.table_depth > 0 {
+            if let Some(cell) = self.row.last_mut() {
+                if !cell.is_empty() {
+                    cell.push(' ');
+                }
+                cell.push_str(&text);
+            }
+            return;
+        }
+        let block = if let Some(level) = heading {
+            format!("{} {}", "#".repeat(level.clamp(1, 6)), text)
+        } else if self.list_depth > 0 && self.item_start {
+            format!("{}- {}", "  ".repeat(self.list_depth - 1), text)
+        } else {
+            text
+        };
+        self.item_start = false;
+        self.blocks.push(block);
+    }
+}
+
+pub fn extract_text_from_odt(data: &[u8]) -> Result<String> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(data)).map_err(|e| odt_err(&e.to_string()))?;
+    let mut content = String::new();
+    archive
+        .by_name("content.xml")
+        .map_err(|_| odt_err("missing content.xml"))?
+        .read_to_string(&mut content)
+        .map_err(|e| odt_err(&e.to_string()))?;
+
+    let mut walker = Walker::default();
+    for event in EventReader::new(content.as_bytes()) {
+        let event = event.map_err(|e| odt_err(&e.to_string()))?;
+        match event {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                if walker.skip_depth > 0 || SKIPPED.contains(&name.local_name.as_str()) {
+                    walker.skip_depth += 1;
+                    continue;
+                }
+                let attr = |key: &str| {
+                    attributes
+                        .iter()
+                        .find(|a| a.name.local_name == key)
+                        .map(|a| a.value.as_str())
+                };
+                match name.local_name.as_str() {
+                    "h" => {
+                        walker.heading =
+                            Some(attr("outline-level").and_then(|l| l.parse().ok()).unwrap_or(1))
+                    }
+                    "list" => walker.list_depth += 1,
+                    "list-item" => walker.item_start = true,
+                    "table" => walker.table_depth += 1,
+                    "table-cell" if walker.table_depth == 1 => walker.row.push(String::new()),
+                    "tab" => walker.line.push('\t'),
+                    "line-break" => walker.line.push('\n'),
+                    "s" => {
+                        let count = attr("c").and_then(|c| c.parse().ok()).unwrap_or(1usize);
+                        walker.line.push_str(&" ".repeat(count.min(100)));
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::EndElement { name } => {
+                if walker.skip_depth > 0 {
+                    walker.skip_depth -= 1;
+                    continue;
+                }
+                match name.local_name.as_str() {
+                    "p" | "h" => walker.end_paragraph(),
+                    "list" => walker.list_depth = walker.list_depth.saturating_sub(1),
+                    "table" => walker.table_depth = walker.table_depth.saturating_sub(1),
+                    "table-row" if walker.table_depth == 1 => {
+                        let row = std::mem::take(&mut walker.row).join("\t");
+                        if !row.trim().is_empty() {
+                            walker.blocks.push(row.trim_end().to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::Characters(text) | XmlEvent::CData(text) if walker.skip_depth == 0 => {
+                walker.line.push_str(&text)
+            }
+            _ => {}
+        }
+    }
+
+    let text = walker.blocks.join("\n");
+    if text.is_empty() {
+        return Err(odt_err("no text found"));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::write::FileOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_extract_text_from_odt_keeps_structure() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+    xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"
+    xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0">
+  <office:body><office:text>
+    <text:h text:outline-level="1">Jane Doe</text:h>
+    <text:p>Backend<text:s/>engineer<office:annotation><text:p>check dates</text:p></office:annotation></text:p>
+    <text:list><text:list-item><text:p>Rust</text:p></text:list-item></text:list>
+    <table:table><table:table-row>
+      <table:table-cell><text:p>2019</text:p></table:table-cell>
+      <table:table-cell><text:p>Acme</text:p></table:table-cell>
+    </table:table-row></table:table>
+  </office:text></office:body>
+</office:document-content>"#;
+        let mut buf = Cursor::new(vec![]);
+        let mut zip = zip::ZipWriter::new(&mut buf);
+        zip.start_file("content.xml", FileOptions::default()).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+        zip.finish().unwrap();
+        drop(zip);
+
+        let text = extract_text_from_odt(buf.get_ref()).unwrap();
+        assert_eq!(text, "# Jane Doe\nBackend engineer\n- Rust\n2019\tAcme");
+    }
+}
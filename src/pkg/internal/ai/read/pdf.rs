@@ -0,0 +1,1053 @@
+//! Layout-aware PDF text extraction.
+//!
+//! Page content streams are interpreted to place every glyph on the page, then the glyphs
+//! are grouped into lines and, when a vertical gutter splits the page, into columns that
+//! are read one after the other. Text is decoded through the font's ToUnicode CMap, then
+//! its encoding and `Differences`, so subset fonts with custom encodings still come out
+//! readable. When that fails, lopdf's plain extraction is used if it reads better.
+
+use std::{collections::HashMap, io::Cursor};
+
+use lopdf::{Dictionary, Document, Object, content::Content};
+use standard_error::{Interpolate, StandardError};
+use unicode_normalization::char::compose;
+
+use crate::{pkg::internal::ai::read::text_quality, prelude::Result};
+
+/// Nested form XObjects deeper than this are ignored
+const MAX_FORM_DEPTH: usize = 8;
+/// Layout output at or above this quality is used without trying the plain fallback
+const GOOD_QUALITY: f64 = 0.8;
+
+fn pdf_err(reason: &str) -> StandardError {
+    StandardError::new("ERR-AI-005").interpolate_err(format!("pdf: {}", reason))
+}
+
+/// Parses the document, refusing ones with more than `page_limit` pages
+fn load(data: &[u8], page_limit: usize) -> Result<Document> {
+    let doc = Document::load_from(Cursor::new(data)).map_err(|e| pdf_err(&e.to_string()))?;
+    let pages = doc.get_pages().len();
+    if pages > page_limit {
+        return Err(pdf_err(&format!("{} pages, the limit is {}", pages, page_limit)));
+    }
+    Ok(doc)
+}
+
+type Matrix = [f64; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// `a` then `b`, in the PDF row vector convention
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    [
+        a[0] * b[0] + a[1] * b[2],
+        a[0] * b[1] + a[1] * b[3],
+        a[2] * b[0] + a[3] * b[2],
+        a[2] * b[1] + a[3] * b[3],
+        a[4] * b[0] + a[5] * b[2] + b[4],
+        a[4] * b[1] + a[5] * b[3] + b[5],
+    ]
+}
+
+fn num(object: &Object) -> f64 {
+    object.as_float().map(f64::from).unwrap_or(0.0)
+}
+
+fn matrix(operands: &[Object]) -> Option<Matrix> {
+    (operands.len() == 6).then(|| {
+        let mut m = IDENTITY;
+        for (value, operand) in m.iter_mut().zip(operands) {
+            *value = num(operand);
+        }
+        m
+    })
+}
+
+/// A parsed ToUnicode CMap
+#[derive(Default)]
+struct CMap {
+    /// (code length in bytes, low, high)
+    codespace: Vec<(usize, u32, u32)>,
+    map: HashMap<(usize, u32), String>,
+}
+
+fn cmap_tokens(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < data.len() {
+        let c = data[i];
+        match c {
+            b'%' => {
+                while i < data.len() && data[i] != b'\n' && data[i] != b'\r' {
+                    i += 1;
+                }
+            }
+            b'<' if data.get(i + 1) == Some(&b'<') => {
+                tokens.push(b"<<".to_vec());
+                i += 2;
+            }
+            b'>' if data.get(i + 1) == Some(&b'>') => {
+                tokens.push(b">>".to_vec());
+                i += 2;
+            }
+            b'<' => {
+                let end = data[i..].iter().position(|&b| b == b'>').map_or(data.len(), |p| i + p);
+                tokens.push(data[i..end.min(data.len())].to_vec());
+                i = end + 1;
+            }
+            b'[' | b']' => {
+                tokens.push(vec![c]);
+                i += 1;
+            }
+            c if c.is_ascii_whitespace() => i += 1,
+            _ => {
+                let start = i;
+                i += 1;
+                while i < data.len() && !b"<>[]% \t\r\n/".contains(&data[i]) {
+                    i += 1;
+                }
+                tokens.push(data[start..i].to_vec());
+            }
+        }
+    }
+    tokens
+}
+
+/// `<0041>` token to its bytes
+fn hex_bytes(token: &[u8]) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = token
+        .strip_prefix(b"<")?
+        .iter()
+        .copied()
+        .filter(u8::is_ascii_hexdigit)
+        .collect();
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let pair = if pair.len() == 1 { [pair[0], b'0'] } else { [pair[0], pair[1]] };
+            u8::from_str_radix(std::str::from_utf8(&pair).ok()?, 16).ok()
+        })
+        .collect()
+}
+
+fn code_of(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |code, b| (code << 8) | *b as u32)
+}
+
+fn utf16_string(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+impl CMap {
+    fn parse(data: &[u8]) -> Self {
+        let mut cmap = CMap::default();
+        let tokens = cmap_tokens(data);
+        let mut i = 0;
+        while i < tokens.len() {
+            match tokens[i].as_slice() {
+                b"begincodespacerange" => {
+                    i += 1;
+                    while i + 1 < tokens.len() && tokens[i] != b"endcodespacerange" {
+                        if let (Some(lo), Some(hi)) = (hex_bytes(&tokens[i]), hex_bytes(&tokens[i + 1])) {
+                            cmap.codespace.push((lo.len(), code_of(&lo), code_of(&hi)));
+                        }
+                        i += 2;
+                    }
+                }
+                b"beginbfchar" => {
+                    i += 1;
+                    while i + 1 < tokens.len() && tokens[i] != b"endbfchar" {
+                        if let (Some(src), Some(dst)) = (hex_bytes(&tokens[i]), hex_bytes(&tokens[i + 1])) {
+                            cmap.map.insert((src.len(), code_of(&src)), utf16_string(&dst));
+                        }
+                        i += 2;
+                    }
+                }
+                b"beginbfrange" => {
+                    i += 1;
+                    while i + 2 < tokens.len() && tokens[i] != b"endbfrange" {
+                        let (Some(lo), Some(hi)) = (hex_bytes(&tokens[i]), hex_bytes(&tokens[i + 1])) else {
+                            i += 1;
+                            continue;
+                        };
+                        let (len, lo, hi) = (lo.len(), code_of(&lo), code_of(&hi));
+                        i += 2;
+                        if tokens[i] == b"[" {
+                            i += 1;
+                            let mut code = lo;
+                            while i < tokens.len() && tokens[i] != b"]" {
+                                if let Some(dst) = hex_bytes(&tokens[i]) {
+                                    cmap.map.insert((len, code), utf16_string(&dst));
+                                }
+                                code += 1;
+                                i += 1;
+                            }
+                            i += 1;
+                        } else {
+                            if let Some(mut dst) = hex_bytes(&tokens[i]) {
+                                // bounded so a corrupt range cannot exhaust memory
+                                for code in lo..=hi.min(lo.saturating_add(0xFFFF)) {
+                                    cmap.map.insert((len, code), utf16_string(&dst));
+                                    if let Some(last) = dst.last_mut() {
+                                        *last = last.wrapping_add(1);
+                                    }
+                                }
+                            }
+                            i += 1;
+                        }
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        cmap
+    }
+}
+
+/// Glyph names from `Differences` arrays: `uniXXXX`, `uXXXX`, plain letters and the
+/// common Latin names, with accented letters composed from their parts
+fn glyph_char(name: &str) -> Option<String> {
+    let name = name.split('.').next().unwrap_or(name);
+    if let Some(hex) = name.strip_prefix("uni").or_else(|| name.strip_prefix('u'))
+        && (4..=6).contains(&hex.len())
+        && let Ok(code) = u32::from_str_radix(hex, 16)
+    {
+        return char::from_u32(code).map(String::from);
+    }
+    if name.len() == 1 {
+        return Some(name.to_string());
+    }
+    const NAMES: &[(&str, &str)] = &[
+        ("space", " "), ("exclam", "!"), ("quotedbl", "\""), ("numbersign", "#"),
+        ("dollar", "$"), ("percent", "%"), ("ampersand", "&"), ("quotesingle", "'"),
+        ("quoteright", "’"), ("quoteleft", "‘"), ("parenleft", "("), ("parenright", ")"),
+        ("asterisk", "*"), ("plus", "+"), ("comma", ","), ("hyphen", "-"), ("period", "."),
+        ("slash", "/"), ("zero", "0"), ("one", "1"), ("two", "2"), ("three", "3"),
+        ("four", "4"), ("five", "5"), ("six", "6"), ("seven", "7"), ("eight", "8"),
+        ("nine", "9"), ("colon", ":"), ("semicolon", ";"), ("less", "<"), ("equal", "="),
+        ("greater", ">"), ("question", "?"), ("at", "@"), ("bracketleft", "["),
+        ("backslash", "\\"), ("bracketright", "]"), ("asciicircum", "^"),
+        ("underscore", "_"), ("grave", "`"), ("braceleft", "{"), ("bar", "|"),
+        ("braceright", "}"), ("asciitilde", "~"), ("bullet", "•"), ("endash", "–"),
+        ("emdash", "—"), ("quotedblleft", "“"), ("quotedblright", "”"),
+        ("quotesinglbase", "‚"), ("quotedblbase", "„"), ("ellipsis", "…"), ("fi", "fi"),
+        ("fl", "fl"), ("ff", "ff"), ("ffi", "ffi"), ("ffl", "ffl"), ("copyright", "©"),
+        ("registered", "®"), ("trademark", "™"), ("degree", "°"), ("section", "§"),
+        ("paragraph", "¶"), ("periodcentered", "·"), ("minus", "−"), ("multiply", "×"),
+        ("Euro", "€"), ("sterling", "£"), ("yen", "¥"), ("germandbls", "ß"), ("ae", "æ"),
+        ("AE", "Æ"), ("oslash", "ø"), ("Oslash", "Ø"), ("nbspace", " "), ("sfthyphen", "-"),
+    ];
+    if let Some((_, text)) = NAMES.iter().find(|(n, _)| *n == name) {
+        return Some(text.to_string());
+    }
+    const ACCENTS: &[(&str, char)] = &[
+        ("acute", '\u{301}'), ("grave", '\u{300}'), ("circumflex", '\u{302}'),
+        ("dieresis", '\u{308}'), ("tilde", '\u{303}'), ("ring", '\u{30A}'),
+        ("cedilla", '\u{327}'), ("caron", '\u{30C}'), ("macron", '\u{304}'),
+        ("breve", '\u{306}'), ("ogonek", '\u{328}'), ("dotaccent", '\u{307}'),
+    ];
+    let mut chars = name.chars();
+    let base = chars.next()?;
+    let accent = chars.as_str();
+    ACCENTS
+        .iter()
+        .find(|(a, _)| *a == accent)
+        .and_then(|(_, mark)| compose(base, *mark))
+        .map(String::from)
+}
+
+struct Font {
+    to_unicode: Option<CMap>,
+    /// byte to text for simple fonts
+    table: Option<Vec<Option<String>>>,
+    /// code length in bytes when no CMap codespace says otherwise
+    code_len: usize,
+    widths: HashMap<u32, f64>,
+    default_width: f64,
+    /// glyph space to text space, 1/1000 except for Type3 fonts
+    scale: f64,
+}
+
+/// The 256 characters of a named base encoding, decoded by lopdf
+fn base_table(doc: &Document, name: &[u8]) -> Vec<Option<String>> {
+    let mut dict = Dictionary::new();
+    dict.set("Type", Object::Name(b"Font".to_vec()));
+    dict.set("Encoding", Object::Name(name.to_vec()));
+    let encoding = dict.get_font_encoding(doc).ok();
+    (0..=255u8)
+        .map(|byte| {
+            encoding
+                .as_ref()
+                .and_then(|e| e.bytes_to_string(&[byte]).ok())
+                .filter(|s| !s.is_empty())
+        })
+        .collect()
+}
+
+fn deref<'a>(doc: &'a Document, object: &'a Object) -> &'a Object {
+    doc.dereference(object).map(|(_, o)| o).unwrap_or(object)
+}
+
+impl Font {
+    fn load(doc: &Document, dict: &Dictionary) -> Self {
+        let subtype = dict.get(b"Subtype").and_then(Object::as_name).unwrap_or(b"");
+        let to_unicode = dict
+            .get_deref(b"ToUnicode", doc)
+            .and_then(Object::as_stream)
+            .and_then(|s| s.get_plain_content())
+            .ok()
+            .map(|data| CMap::parse(&data));
+        let mut font = Font {
+            to_unicode,
+            table: None,
+            code_len: 1,
+            widths: HashMap::new(),
+            default_width: 500.0,
+            scale: 0.001,
+        };
+
+        if subtype == b"Type0" {
+            font.code_len = 2;
+            font.default_width = 1000.0;
+            let descendant = dict
+                .get_deref(b"DescendantFonts", doc)
+                .and_then(Object::as_array)
+                .ok()
+                .and_then(|fonts| fonts.first())
+                .and_then(|f| deref(doc, f).as_dict().ok());
+            if let Some(descendant) = descendant {
+                if let Ok(dw) = descendant.get(b"DW") {
+                    font.default_width = num(dw);
+                }
+                // [first [w1 w2 ...]] or [first last w]
+                let w = descendant
+                    .get_deref(b"W", doc)
+                    .and_then(Object::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+                let mut i = 0;
+                while i + 1 < w.len() {
+                    let first = num(deref(doc, &w[i])) as u32;
+                    match deref(doc, &w[i + 1]) {
+                        Object::Array(widths) => {
+                            for (offset, width) in widths.iter().enumerate() {
+                                font.widths.insert(first + offset as u32, num(deref(doc, width)));
+                            }
+                            i += 2;
+                        }
+                        last if i + 2 < w.len() => {
+                            let width = num(deref(doc, &w[i + 2]));
+                            for code in first..=(num(last) as u32).min(first + 0xFFFF) {
+                                font.widths.insert(code, width);
+                            }
+                            i += 3;
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            return font;
+        }
+
+        if subtype == b"Type3"
+            && let Ok(m) = dict.get_deref(b"FontMatrix", doc).and_then(Object::as_array)
+            && let Some(a) = m.first()
+        {
+            font.scale = num(deref(doc, a));
+        }
+        let first_char = dict.get(b"FirstChar").map(num).unwrap_or(0.0) as u32;
+        if let Ok(widths) = dict.get_deref(b"Widths", doc).and_then(Object::as_array) {
+            for (offset, width) in widths.iter().enumerate() {
+                font.widths.insert(first_char + offset as u32, num(deref(doc, width)));
+            }
+        }
+
+        let encoding = dict.get_deref(b"Encoding", doc).ok();
+        let default_base: &[u8] = if subtype == b"TrueType" {
+            b"WinAnsiEncoding"
+        } else {
+            b"StandardEncoding"
+        };
+        let base = match encoding {
+            Some(Object::Name(name)) => name.as_slice(),
+            Some(Object::Dictionary(d)) => d
+                .get(b"BaseEncoding")
+                .and_then(Object::as_name)
+                .unwrap_or(default_base),
+            _ => default_base,
+        };
+        let mut table = base_table(doc, base);
+        if let Some(Object::Dictionary(d)) = encoding
+            && let Ok(differences) = d.get_deref(b"Differences", doc).and_then(Object::as_array)
+        {
+            let mut code = 0usize;
+            for entry in differences {
+                match deref(doc, entry) {
+                    Object::Integer(i) => code = *i as usize,
+                    Object::Name(name) => {
+                        if let Some(slot) = table.get_mut(code) {
+                            *slot = glyph_char(&String::from_utf8_lossy(name));
+                        }
+                        code += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        font.table = Some(table);
+        font
+    }
+
+    /// Splits a string operand into (code, code length) pairs
+    fn codes(&self, bytes: &[u8]) -> Vec<(u32, usize)> {
+        let mut codes = vec![];
+        let mut i = 0;
+        while i < bytes.len() {
+            let len = self
+                .to_unicode
+                .as_ref()
+                .filter(|cmap| !cmap.codespace.is_empty())
+                .and_then(|cmap| {
+                    (1..=4).find(|&len| {
+                        i + len <= bytes.len()
+                            && cmap.codespace.iter().any(|(l, lo, hi)| {
+                                let code = code_of(&bytes[i..i + len]);
+                                *l == len && (*lo..=*hi).contains(&code)
+                            })
+                    })
+                })
+                .unwrap_or(self.code_len)
+                .min(bytes.len() - i);
+            codes.push((code_of(&bytes[i..i + len]), len));
+            i += len;
+        }
+        codes
+    }
+
+    fn text(&self, code: u32, len: usize) -> String {
+        if let Some(text) = self.to_unicode.as_ref().and_then(|cmap| cmap.map.get(&(len, code))) {
+            return text.clone();
+        }
+        if let Some(text) = self
+            .table
+            .as_ref()
+            .and_then(|table| table.get(code as usize).cloned().flatten())
+        {
+            return text;
+        }
+        '\u{FFFD}'.to_string()
+    }
+
+    fn width(&self, code: u32) -> f64 {
+        self.widths.get(&code).copied().unwrap_or(self.default_width) * self.scale
+    }
+}
+
+/// One glyph placed on the page, in device space
+#[derive(Debug, Clone)]
+struct Glyph {
+    x: f64,
+    x_end: f64,
+    y: f64,
+    size: f64,
+    text: String,
+}
+
+#[derive(Clone)]
+struct GraphicsState {
+    ctm: Matrix,
+    char_spacing: f64,
+    word_spacing: f64,
+    h_scale: f64,
+    leading: f64,
+    rise: f64,
+    font: Option<usize>,
+    font_size: f64,
+}
+
+impl Default for GraphicsState {
+    fn default() -> Self {
+        Self {
+            ctm: IDENTITY,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            h_scale: 1.0,
+            leading: 0.0,
+            rise: 0.0,
+            font: None,
+            font_size: 0.0,
+        }
+    }
+}
+
+struct PageReader<'a> {
+    doc: &'a Document,
+    fonts: HashMap<usize, Font>,
+    glyphs: Vec<Glyph>,
+}
+
+/// Looks up a named resource, e.g. a font, in the resource dictionaries that apply
+fn resource<'a>(
+    doc: &'a Document,
+    resources: &[&'a Dictionary],
+    category: &[u8],
+    name: &[u8],
+) -> Option<&'a Object> {
+    resources.iter().find_map(|dict| {
+        let entries = deref(doc, dict.get(category).ok()?).as_dict().ok()?;
+        Some(deref(doc, entries.get(name).ok()?))
+    })
+}
+
+impl<'a> PageReader<'a> {
+    fn run(
+        &mut self,
+        content: &Content,
+        resources: &[&'a Dictionary],
+        state: GraphicsState,
+        depth: usize,
+    ) {
+        let mut state = state;
+        let mut stack = vec![];
+        let mut tm = IDENTITY;
+        let mut tlm = IDENTITY;
+        for op in &content.operations {
+            let operands = op.operands.as_slice();
+            match op.operator.as_str() {
+                "q" => stack.push(state.clone()),
+                "Q" => {
+                    if let Some(saved) = stack.pop() {
+                        state = saved;
+                    }
+                }
+                "cm" => {
+                    if let Some(m) = matrix(operands) {
+                        state.ctm = multiply(&m, &state.ctm);
+                    }
+                }
+                "BT" => {
+                    tm = IDENTITY;
+                    tlm = IDENTITY;
+                }
+                "Tc" => state.char_spacing = operands.first().map(num).unwrap_or(0.0),
+                "Tw" => state.word_spacing = operands.first().map(num).unwrap_or(0.0),
+                "Tz" => state.h_scale = operands.first().map(num).unwrap_or(100.0) / 100.0,
+                "TL" => state.leading = operands.first().map(num).unwrap_or(0.0),
+                "Ts" => state.rise = operands.first().map(num).unwrap_or(0.0),
+                "Tf" => {
+                    if let [name, size] = operands {
+                        state.font_size = num(size);
+                        state.font = name.as_name().ok().and_then(|name| {
+                            let dict = resource(self.doc, resources, b"Font", name)?.as_dict().ok()?;
+                            // the document outlives the reader, so a dictionary's address
+                            // identifies the font even when it is inlined
+                            let key = dict as *const Dictionary as usize;
+                            self.fonts
+                                .entry(key)
+                                .or_insert_with(|| Font::load(self.doc, dict));
+                            Some(key)
+                        });
+                    }
+                }
+                "Td" | "TD" => {
+                    if let [tx, ty] = operands {
+                        if op.operator == "TD" {
+                            state.leading = -num(ty);
+                        }
+                        tlm = multiply(&[1.0, 0.0, 0.0, 1.0, num(tx), num(ty)], &tlm);
+                        tm = tlm;
+                    }
+                }
+                "Tm" => {
+                    if let Some(m) = matrix(operands) {
+                        tlm = m;
+                        tm = m;
+                    }
+                }
+                "T*" => {
+                    tlm = multiply(&[1.0, 0.0, 0.0, 1.0, 0.0, -state.leading], &tlm);
+                    tm = tlm;
+                }
+                "Tj" | "'" | "\"" | "TJ" => {
+                    if op.operator == "'" || op.operator == "\"" {
+                        if op.operator == "\"" && operands.len() == 3 {
+                            state.word_spacing = num(&operands[0]);
+                            state.char_spacing = num(&operands[1]);
+                        }
+                        tlm = multiply(&[1.0, 0.0, 0.0, 1.0, 0.0, -state.leading], &tlm);
+                        tm = tlm;
+                    }
+                    let items: Vec<&Object> = match (op.operator.as_str(), operands) {
+                        ("TJ", [Object::Array(items)]) => items.iter().collect(),
+                        (_, [.., last]) => vec![last],
+                        _ => vec![],
+                    };
+                    for item in items {
+                        match item {
+                            Object::String(bytes, _) => self.show(bytes, &state, &mut tm),
+                            // adjustments are in thousandths of text space, negative moves right
+                            other => {
+                                let tx = -num(other) / 1000.0 * state.font_size * state.h_scale;
+                                tm = multiply(&[1.0, 0.0, 0.0, 1.0, tx, 0.0], &tm);
+                            }
+                        }
+                    }
+                }
+                "Do" if depth < MAX_FORM_DEPTH => {
+                    let Some(name) = operands.first().and_then(|o| o.as_name().ok()) else {
+                        continue;
+                    };
+                    let Some(object) = resource(self.doc, resources, b"XObject", name) else {
+                        continue;
+                    };
+                    let Ok(stream) = object.as_stream() else {
+                        continue;
+                    };
+                    if stream.dict.get(b"Subtype").and_then(Object::as_name).ok() != Some(b"Form") {
+                        continue;
+                    }
+                    let Ok(data) = stream.get_plain_content() else {
+                        continue;
+                    };
+                    let Ok(form) = Content::decode(&data) else {
+                        continue;
+                    };
+                    let mut inner = state.clone();
+                    if let Some(m) = stream
+                        .dict
+                        .get(b"Matrix")
+                        .and_then(Object::as_array)
+                        .ok()
+                        .and_then(|m| matrix(m))
+                    {
+                        inner.ctm = multiply(&m, &state.ctm);
+                    }
+                    let mut form_resources = resources.to_vec();
+                    if let Ok(own) = stream.dict.get(b"Resources")
+                        && let Ok(own) = deref(self.doc, own).as_dict()
+                    {
+                        form_resources.insert(0, own);
+                    }
+                    self.run(&form, &form_resources, inner, depth + 1);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn show(&mut self, bytes: &[u8], state: &GraphicsState, tm: &mut Matrix) {
+        let Some(font) = state.font.and_then(|id| self.fonts.get(&id)) else {
+            return;
+        };
+        for (code, len) in font.codes(bytes) {
+            let params = [
+                state.font_size * state.h_scale,
+                0.0,
+                0.0,
+                state.font_size,
+                0.0,
+                state.rise,
+            ];
+            let trm = multiply(&multiply(&params, tm), &state.ctm);
+            let mut advance = font.width(code) * state.font_size + state.char_spacing;
+            if len == 1 && code == 32 {
+                advance += state.word_spacing;
+            }
+            let advance = advance * state.h_scale;
+            let end = multiply(&[1.0, 0.0, 0.0, 1.0, advance, 0.0], tm);
+            let end = multiply(&end, &state.ctm);
+            let size = trm[2].hypot(trm[3]);
+            // rotated text, such as vertical sidebar labels, has no place in a reading order
+            if trm[1].abs() <= trm[0].abs() && size > 0.0 {
+                self.glyphs.push(Glyph {
+                    x: trm[4],
+                    x_end: end[4].max(trm[4]),
+                    y: trm[5],
+                    size,
+                    text: font.text(code, len),
+                });
+            }
+            *tm = multiply(&[1.0, 0.0, 0.0, 1.0, advance, 0.0], tm);
+        }
+    }
+}
+
+/// A run of glyphs on one baseline with no wide gap inside
+struct Chunk {
+    x: f64,
+    x_end: f64,
+    y: f64,
+    size: f64,
+    text: String,
+}
+
+/// Groups glyphs into lines, top to bottom, each split into chunks at wide gaps
+fn lines(mut glyphs: Vec<Glyph>) -> Vec<Vec<Chunk>> {
+    glyphs.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+    let mut rows: Vec<Vec<Glyph>> = vec![];
+    for glyph in glyphs {
+        match rows.last_mut() {
+            Some(row) if (row[0].y - glyph.y).abs() <= row[0].size.min(glyph.size) * 0.4 => {
+                row.push(glyph)
+            }
+            _ => rows.push(vec![glyph]),
+        }
+    }
+    rows.into_iter()
+        .map(|mut row| {
+            row.sort_by(|a, b| a.x.total_cmp(&b.x));
+            // fake bold draws the same glyph twice at almost the same spot
+            row.dedup_by(|b, a| a.text == b.text && (a.x - b.x).abs() < a.size * 0.1);
+            let mut chunks: Vec<Chunk> = vec![];
+            for glyph in row {
+                if glyph.text.chars().all(char::is_whitespace) {
+                    if let Some(chunk) = chunks.last_mut() {
+                        chunk.x_end = chunk.x_end.max(glyph.x_end);
+                        if !chunk.text.ends_with(' ') {
+                            chunk.text.push(' ');
+                        }
+                    }
+                    continue;
+                }
+                match chunks.last_mut() {
+                    Some(chunk) if glyph.x - chunk.x_end < glyph.size * 1.5 => {
+                        if glyph.x - chunk.x_end > glyph.size * 0.2 && !chunk.text.ends_with(' ') {
+                            chunk.text.push(' ');
+                        }
+                        chunk.text.push_str(&glyph.text);
+                        chunk.x_end = chunk.x_end.max(glyph.x_end);
+                    }
+                    _ => chunks.push(Chunk {
+                        x: glyph.x,
+                        x_end: glyph.x_end,
+                        y: glyph.y,
+                        size: glyph.size,
+                        text: glyph.text,
+                    }),
+                }
+            }
+            for chunk in chunks.iter_mut() {
+                chunk.text = chunk.text.trim_end().to_string();
+            }
+            chunks
+        })
+        .filter(|chunks| !chunks.is_empty())
+        .collect()
+}
+
+/// The x position of a vertical gutter that splits the page into two columns, if any
+fn gutter(lines: &[Vec<Chunk>]) -> Option<f64> {
+    let chunks = lines.iter().flatten();
+    let left = chunks.clone().map(|c| c.x).fold(f64::INFINITY, f64::min);
+    let right = chunks.map(|c| c.x_end).fold(f64::NEG_INFINITY, f64::max);
+    let width = right - left;
+    if width < 100.0 || lines.len() < 6 {
+        return None;
+    }
+    // count, per point of page width, how many lines have text there
+    let slots = width.ceil() as usize + 1;
+    let mut coverage = vec![0usize; slots];
+    for line in lines {
+        for chunk in line {
+            let from = (chunk.x - left).max(0.0) as usize;
+            let to = ((chunk.x_end - left) as usize).min(slots - 1);
+            for slot in coverage.iter_mut().take(to + 1).skip(from) {
+                *slot += 1;
+            }
+        }
+    }
+    let allowed = (lines.len() / 20).max(1);
+    let (lo, hi) = ((width * 0.15) as usize, (width * 0.85) as usize);
+    let mut best: Option<(usize, usize)> = None;
+    let mut start = None;
+    for (x, count) in coverage.iter().enumerate().take(hi + 1).skip(lo) {
+        if *count <= allowed {
+            start.get_or_insert(x);
+        }
+        if let Some(s) = start
+            && (*count > allowed || x == hi)
+        {
+            if best.is_none_or(|(bs, be)| x - s > be - bs) {
+                best = Some((s, x));
+            }
+            start = None;
+        }
+    }
+    let (s, e) = best.filter(|(s, e)| e - s >= 8)?;
+    let split = left + (s + e) as f64 / 2.0;
+    let side = |pred: &dyn Fn(&Chunk) -> bool| {
+        lines.iter().filter(|line| line.iter().any(pred)).count()
+    };
+    // both sides must carry real text, not a stray page number
+    let enough = (lines.len() / 5).max(3);
+    (side(&|c| c.x_end <= split) >= enough && side(&|c| c.x >= split) >= enough).then_some(split)
+}
+
+fn render(lines: &[&Chunk]) -> String {
+    let mut text = String::new();
+    let mut previous: Option<&Chunk> = None;
+    for chunk in lines {
+        match previous {
+            Some(p) if (p.y - chunk.y).abs() <= p.size.min(chunk.size) * 0.4 => text.push('\t'),
+            Some(p) => {
+                text.push('\n');
+                // a paragraph gap reads as a blank line
+                if p.y - chunk.y > p.size.max(chunk.size) * 2.0 {
+                    text.push('\n');
+                }
+            }
+            None => {}
+        }
+        text.push_str(&chunk.text);
+        previous = Some(chunk);
+    }
+    text
+}
+
+/// Reading order for a page: full-width lines as they come, two-column stretches
+/// left column first
+fn layout(lines: Vec<Vec<Chunk>>) -> String {
+    let Some(split) = gutter(&lines) else {
+        return render(&lines.iter().flatten().collect::<Vec<_>>());
+    };
+    let mut blocks = vec![];
+    let mut left: Vec<&Chunk> = vec![];
+    let mut right: Vec<&Chunk> = vec![];
+    for line in &lines {
+        if line.iter().any(|c| c.x < split && c.x_end > split) {
+            blocks.push(render(&left));
+            blocks.push(render(&right));
+            left.clear();
+            right.clear();
+            blocks.push(render(&line.iter().collect::<Vec<_>>()));
+            continue;
+        }
+        for chunk in line {
+            if chunk.x_end <= split {
+                left.push(chunk);
+            } else {
+                right.push(chunk);
+            }
+        }
+    }
+    blocks.push(render(&left));
+    blocks.push(render(&right));
+    blocks.retain(|b| !b.is_empty());
+    blocks.join("\n\n")
+}
+
+fn layout_text(doc: &Document) -> String {
+    let mut pages = vec![];
+    for (page_number, page_id) in doc.get_pages() {
+        let content = match doc.get_page_content(page_id).and_then(|data| Content::decode(&data)) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("skipping unreadable pdf page {}: {}", page_number, e);
+                continue;
+            }
+        };
+        let mut resources = vec![];
+        if let Ok((own, inherited)) = doc.get_page_resources(page_id) {
+            resources.extend(own);
+            resources.extend(inherited.into_iter().filter_map(|id| doc.get_dictionary(id).ok()));
+        }
+        let mut reader = PageReader {
+            doc,
+            fonts: HashMap::new(),
+            glyphs: vec![],
+        };
+        reader.run(&content, &resources, GraphicsState::default(), 0);
+        pages.push(layout(lines(reader.glyphs)));
+    }
+    pages.join("\n\n").trim().to_string()
+}
+
+/// lopdf's own extraction, page by page in content order
+fn plain_text(doc: &Document) -> String {
+    let mut text = String::new();
+    for page_number in doc.get_pages().keys() {
+        match doc.extract_text(&[*page_number]) {
+            Ok(page_text) => {
+                text.push_str(&page_text);
+                text.push(' ');
+            }
+            Err(e) => tracing::warn!("failed to extract text from page {}: {}", page_number, e),
+        }
+    }
+    text.trim().to_string()
+}
+
+/// Raw pixels of a Flate compressed image, re-encoded as PNG for the OCR engine
+fn encode_png(pixels: &[u8], width: u32, height: u32, color: png::ColorType, depth: png::BitDepth) -> Option<Vec<u8>> {
+    let mut out = vec![];
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(color);
+    encoder.set_depth(depth);
+    encoder.write_header().ok()?.write_image_data(pixels).ok()?;
+    Some(out)
+}
+
+/// The images drawn on the first `max_pages` pages, encoded so an OCR engine can read
+/// them. Scanned resumes are one JPEG or bitmap per page; other encodings are skipped.
+pub fn page_images(data: &[u8], page_limit: usize, max_pages: usize) -> Result<Vec<Vec<u8>>> {
+    let doc = load(data, page_limit)?;
+    let mut images = vec![];
+    for page_id in doc.get_pages().into_values().take(max_pages) {
+        let Ok(page_images) = doc.get_page_images(page_id) else {
+            continue;
+        };
+        for image in page_images {
+            let filters = image.filters.clone().unwrap_or_default();
+            let filters: Vec<&str> = filters.iter().map(String::as_str).collect();
+            let encoded = match filters.as_slice() {
+                ["DCTDecode"] | ["JPXDecode"] => Some(image.content.to_vec()),
+                [] | ["FlateDecode"] => {
+                    let pixels = doc
+                        .get_object(image.id)
+                        .and_then(Object::as_stream)
+                        .and_then(|s| s.get_plain_content())
+                        .ok();
+                    let color = match image.color_space.as_deref() {
+                        Some("DeviceGray") | Some("CalGray") => Some(png::ColorType::Grayscale),
+                        Some("DeviceRGB") | Some("CalRGB") => Some(png::ColorType::Rgb),
+                        _ => None,
+                    };
+                    let depth = match image.bits_per_component {
+                        Some(1) => Some(png::BitDepth::One),
+                        Some(8) => Some(png::BitDepth::Eight),
+                        _ => None,
+                    };
+                    match (pixels, color, depth) {
+                        (Some(pixels), Some(color), Some(depth)) => encode_png(
+                            &pixels,
+                            image.width as u32,
+                            image.height as u32,
+                            color,
+                            depth,
+                        ),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            match encoded {
+                Some(encoded) => images.push(encoded),
+                None => tracing::debug!("skipping pdf image {:?} with filters {:?}", image.id, filters),
+            }
+        }
+    }
+    Ok(images)
+}
+
+pub fn extract_text_from_pdf(data: &[u8], page_limit: usize) -> Result<String> {
+    let doc = load(data, page_limit)?;
+    let mut text = layout_text(&doc);
+    let quality = text_quality(&text);
+    if quality < GOOD_QUALITY {
+        let plain = plain_text(&doc);
+        if text_quality(&plain) > quality {
+            tracing::debug!("layout extraction read poorly ({:.2}), using plain text", quality);
+            text = plain;
+        }
+    }
+    if text.is_empty() {
+        return Err(pdf_err("no text extracted"));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use lopdf::{Stream, content::Operation, dictionary};
+
+    use super::*;
+
+    fn text_at(x: i64, y: i64, text: &str) -> Vec<Operation> {
+        vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["F1".into(), 10.into()]),
+            Operation::new("Td", vec![x.into(), y.into()]),
+            Operation::new("Tj", vec![Object::string_literal(text)]),
+            Operation::new("ET", vec![]),
+        ]
+    }
+
+    #[test]
+    fn test_extract_text_from_pdf_reads_columns_in_order() {
+        let mut operations = text_at(50, 780, "Jane Doe Senior Backend Engineer");
+        let left = ["Experience", "Acme Corp", "Built billing", "Led a team", "Shipped APIs", "Mentored"];
+        let right = ["Skills", "Rust", "PostgreSQL", "Kubernetes", "Kafka", "Terraform"];
+        for (i, (l, r)) in left.iter().zip(right).enumerate() {
+            let y = 740 - i as i64 * 14;
+            operations.extend(text_at(50, y, l));
+            operations.extend(text_at(330, y, r));
+        }
+
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+            "Encoding" => "WinAnsiEncoding",
+        });
+        let content = Content { operations };
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+            "Resources" => dictionary! { "Font" => dictionary! { "F1" => font_id } },
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        doc.trailer.set("Root", catalog_id);
+        let mut data = vec![];
+        doc.save_to(&mut data).unwrap();
+
+        let text = extract_text_from_pdf(&data, 50).unwrap();
+        let expected = format!(
+            "Jane Doe Senior Backend Engineer\n\n{}\n\n{}",
+            left.join("\n"),
+            right.join("\n")
+        );
+        assert_eq!(text, expected);
+        assert!(text_quality(&text) > 0.9);
+    }
+
+    #[test]
+    fn test_cmap_parse_ranges_and_chars() {
+        let cmap = CMap::parse(
+            b"begincodespacerange <0000> <FFFF> endcodespacerange\n\
+              2 beginbfchar <0003> <0020> <0011> <00660069> endbfchar\n\
+              1 beginbfrange <0024> <0026> <0041> endbfrange",
+        );
+        let font = Font {
+            to_unicode: Some(cmap),
+            table: None,
+            code_len: 2,
+            widths: HashMap::new(),
+            default_width: 1000.0,
+            scale: 0.001,
+        };
+        let text: String = font
+            .codes(&[0, 0x24, 0, 0x11, 0, 3, 0, 0x26, 0, 0x99])
+            .into_iter()
+            .map(|(code, len)| font.text(code, len))
+            .collect();
+        assert_eq!(text, "Afi C\u{FFFD}");
+    }
+}
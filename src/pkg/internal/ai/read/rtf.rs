@@ -0,0 +1,228 @@
+//! Rich Text Format (`.rtf`) extraction.
+//!
+//! A small tokenizer over groups and control words: formatting is dropped, destinations
+//! that hold no body text (font tables, pictures, `\*` extensions...) are skipped, and
+//! `\'hh` bytes are decoded with the document's ANSI code page.
+
+use encoding_rs::{Encoding, WINDOWS_1252};
+use standard_error::{Interpolate, StandardError};
+
+use crate::prelude::Result;
+
+/// Destinations whose content is never body text
+const SKIPPED: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "object",
+    "fldinst",
+    "filetbl",
+    "revtbl",
+    "rsidtbl",
+    "listtable",
+    "listoverridetable",
+    "latentstyles",
+    "themedata",
+    "colorschememapping",
+    "datastore",
+    "xmlnstbl",
+    "generator",
+    "mmathPr",
+];
+
+fn rtf_err(reason: &str) -> StandardError {
+    StandardError::new("ERR-AI-005").interpolate_err(format!("rtf: {}", reason))
+}
+
+#[derive(Clone, Copy)]
+struct Group {
+    skip: bool,
+    /// characters to drop after a `\u` escape
+    uc: usize,
+}
+
+struct Reader {
+    text: String,
+    /// `\'hh` bytes waiting to be decoded together, so double-byte code pages work
+    bytes: Vec<u8>,
+    encoding: &'static Encoding,
+    /// fallback characters still to drop after a `\u` escape
+    pending_skip: usize,
+}
+
+impl Reader {
+    fn flush(&mut self) {
+        if !self.bytes.is_empty() {
+            let decoded = self.encoding.decode_without_bom_handling(&self.bytes).0;
+            self.text.push_str(&decoded);
+            self.bytes.clear();
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        self.flush();
+        self.text.push(c);
+    }
+}
+
+pub fn extract_text_from_rtf(data: &[u8]) -> Result<String> {
+    if !data.starts_with(b"{\\rtf") {
+        return Err(rtf_err("missing {\\rtf header"));
+    }
+    let mut reader = Reader {
+        text: String::new(),
+        bytes: vec![],
+        encoding: WINDOWS_1252,
+        pending_skip: 0,
+    };
+    let mut stack: Vec<Group> = vec![];
+    let mut group = Group { skip: false, uc: 1 };
+    let mut i = 0;
+
+    while i < data.len() {
+        let byte = data[i];
+        i += 1;
+        match byte {
+            b'{' => {
+                stack.push(group);
+                reader.pending_skip = 0;
+            }
+            b'}' => {
+                group = stack.pop().ok_or_else(|| rtf_err("unbalanced braces"))?;
+                reader.pending_skip = 0;
+            }
+            b'\r' | b'\n' => {}
+            b'\\' => {
+                let Some(&next) = data.get(i) else {
+                    break;
+                };
+                if !next.is_ascii_alphabetic() {
+                    i += 1;
+                    // a control symbol counts as one fallback character after \u
+                    if reader.pending_skip > 0 && next != b'*' {
+                        reader.pending_skip -= 1;
+                        if next == b'\'' {
+                            i += 2;
+                        }
+                        continue;
+                    }
+                    match next {
+                        b'*' => group.skip = true,
+                        b'\'' => {
+                            let byte = data
+                                .get(i..i + 2)
+                                .and_then(|h| std::str::from_utf8(h).ok())
+                                .and_then(|h| u8::from_str_radix(h, 16).ok());
+                            i += 2;
+                            if let Some(byte) = byte
+                                && !group.skip
+                            {
+                                reader.bytes.push(byte);
+                            }
+                        }
+                        b'\\' | b'{' | b'}' if !group.skip => reader.push(next as char),
+                        b'~' if !group.skip => reader.push('\u{a0}'),
+                        b'_' if !group.skip => reader.push('-'),
+                        b'\r' | b'\n' if !group.skip => reader.push('\n'),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                let start = i;
+                while i < data.len() && data[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                let word = std::str::from_utf8(&data[start..i]).unwrap_or_default();
+                let param_start = i;
+                if data.get(i) == Some(&b'-') {
+                    i += 1;
+                }
+                while i < data.len() && data[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let param: Option<i32> = std::str::from_utf8(&data[param_start..i])
+                    .ok()
+                    .and_then(|p| p.parse().ok());
+                if data.get(i) == Some(&b' ') {
+                    i += 1;
+                }
+
+                if SKIPPED.contains(&word) {
+                    group.skip = true;
+                    continue;
+                }
+                match word {
+                    "ansicpg" => {
+                        if let Some(encoding) = param
+                            .and_then(|cp| Encoding::for_label(format!("windows-{}", cp).as_bytes()))
+                        {
+                            reader.encoding = encoding;
+                        }
+                    }
+                    "uc" => group.uc = param.unwrap_or(1).max(0) as usize,
+                    _ if group.skip => {}
+                    "u" => {
+                        if let Some(code) = param {
+                            // values above 32767 are written as negative 16-bit numbers
+                            let code = if code < 0 { code + 65536 } else { code } as u32;
+                            reader.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                            reader.pending_skip = group.uc;
+                        }
+                    }
+                    _ if reader.pending_skip > 0 => reader.pending_skip -= 1,
+                    "par" | "line" | "row" | "sect" | "page" => reader.push('\n'),
+                    "tab" | "cell" => reader.push('\t'),
+                    "emdash" => reader.push('—'),
+                    "endash" => reader.push('–'),
+                    "bullet" => reader.push('•'),
+                    "lquote" => reader.push('‘'),
+                    "rquote" => reader.push('’'),
+                    "ldblquote" => reader.push('“'),
+                    "rdblquote" => reader.push('”'),
+                    _ => {}
+                }
+            }
+            _ if group.skip => {}
+            _ if reader.pending_skip > 0 => reader.pending_skip -= 1,
+            _ if byte >= 0x80 => reader.bytes.push(byte),
+            _ => reader.push(byte as char),
+        }
+    }
+    reader.flush();
+
+    let lines: Vec<String> = reader
+        .text
+        .lines()
+        .map(|line| {
+            let line = line.trim_matches(|c: char| c.is_whitespace());
+            match line.strip_prefix(['•', '·', '\u{F0B7}']) {
+                Some(rest) => format!("- {}", rest.trim_start()),
+                None => line.to_string(),
+            }
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return Err(rtf_err("no text found"));
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_text_from_rtf_decodes_escapes() {
+        let rtf = br"{\rtf1\ansi\ansicpg1252{\fonttbl{\f0 Arial;}}{\*\generator Writer;}
+{\info{\title Resume}}\f0\fs24 {\b Jos\'e9 Garc\u237?a}\par
+{\pntext\bullet\tab}Rust \endash  Tokio\par
+2019\cell Acme\cell\row
+}";
+        let text = extract_text_from_rtf(rtf).unwrap();
+        assert_eq!(text, "José García\n- Rust – Tokio\n2019\tAcme");
+    }
+}
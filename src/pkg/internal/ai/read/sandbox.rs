@@ -0,0 +1,204 @@
+//! Guarded parsing of untrusted uploads.
+//!
+//! Documents are parsed off the async runtime, at most `PARSE_WORKERS` at a time, and
+//! abandoned after `PARSE_TIMEOUT_SECS`. With `PARSE_ISOLATION=process` each document is
+//! parsed by a child `resumeval extract-document` process whose address space is capped
+//! at `PARSE_MEMORY_MB`, so a hostile file can be killed instead of taking the server down.
+//! Parser panics become `ERR-AI-005` errors in both modes.
+
+use std::{
+    any::Any,
+    io::{Cursor, ErrorKind},
+    os::unix::process::ExitStatusExt,
+    panic::{AssertUnwindSafe, catch_unwind},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use standard_error::{Interpolate, StandardError};
+use tokio::{io::AsyncWriteExt, process::Command, sync::Semaphore};
+
+use super::{Extraction, extract};
+use crate::{conf::settings, prelude::Result};
+
+pub const ISOLATION_PROCESS: &str = "process";
+
+lazy_static! {
+    static ref PARSE_SLOTS: Arc<Semaphore> = Arc::new(Semaphore::new(settings.parse_workers.max(1)));
+}
+
+fn parse_err(reason: impl ToString) -> StandardError {
+    StandardError::new("ERR-AI-005").interpolate_err(reason.to_string())
+}
+
+/// What an isolated parser process writes to stdout
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseOutcome {
+    Parsed(Extraction),
+    Failed { code: String, message: String },
+}
+
+fn panic_reason(panic: Box<dyn Any + Send>) -> String {
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown cause".into());
+    format!("parser panicked: {}", message)
+}
+
+/// Refuses ZIP based documents (DOCX, ODT) that unpack to more than the memory cap
+fn check_unpacked_size(data: &[u8], limit: u64) -> Result<()> {
+    if !data.starts_with(b"PK\x03\x04") {
+        return Ok(());
+    }
+    let Ok(mut archive) = zip::ZipArchive::new(Cursor::new(data)) else {
+        // left for the format reader to report
+        return Ok(());
+    };
+    let mut total: u64 = 0;
+    for i in 0..archive.len() {
+        if let Ok(entry) = archive.by_index_raw(i) {
+            total = total.saturating_add(entry.size());
+        }
+    }
+    if total > limit {
+        return Err(parse_err(format!(
+            "unpacks to {} MB, the limit is {} MB",
+            total / 1024 / 1024,
+            limit / 1024 / 1024
+        )));
+    }
+    Ok(())
+}
+
+/// Parses in the current thread with the size checks applied and panics caught
+pub fn extract_guarded(data: Vec<u8>, content_type: &str) -> Result<Extraction> {
+    check_unpacked_size(&data, settings.parse_memory_mb * 1024 * 1024)?;
+    catch_unwind(AssertUnwindSafe(|| extract(data, content_type)))
+        .unwrap_or_else(|panic| Err(parse_err(panic_reason(panic))))
+}
+
+async fn extract_in_thread(data: Vec<u8>, content_type: &str) -> Result<Extraction> {
+    let permit = PARSE_SLOTS.clone().acquire_owned().await.map_err(parse_err)?;
+    let content_type = content_type.to_string();
+    // the slot is released when the thread is done, not when the caller stops waiting,
+    // so a document that timed out still counts against the pool while it runs
+    let parsing = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        extract_guarded(data, &content_type)
+    });
+    match tokio::time::timeout(Duration::from_secs(settings.parse_timeout_secs), parsing).await {
+        Ok(Ok(extraction)) => extraction,
+        Ok(Err(e)) => Err(parse_err(e)),
+        Err(_) => Err(parse_err(format!(
+            "timed out after {}s",
+            settings.parse_timeout_secs
+        ))),
+    }
+}
+
+async fn extract_in_process(data: Vec<u8>, content_type: &str) -> Result<Extraction> {
+    let _permit = PARSE_SLOTS.clone().acquire_owned().await.map_err(parse_err)?;
+    let exe = std::env::current_exe().map_err(parse_err)?;
+    let memory_limit = settings.parse_memory_mb * 1024 * 1024;
+    let mut command = Command::new(exe);
+    command
+        .args(["extract-document", "--content-type", content_type])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // SAFETY: setrlimit is async-signal-safe and nothing else runs between fork and exec
+    unsafe {
+        command.pre_exec(move || {
+            let limit = libc::rlimit {
+                rlim_cur: memory_limit,
+                rlim_max: memory_limit,
+            };
+            if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let parsing = async {
+        let mut child = command.spawn().map_err(parse_err)?;
+        let mut stdin = child.stdin.take().ok_or_else(|| parse_err("stdin unavailable"))?;
+        match stdin.write_all(&data).await {
+            // a child that died early is reported from its exit status below
+            Err(e) if e.kind() != ErrorKind::BrokenPipe => return Err(parse_err(e)),
+            _ => {}
+        }
+        drop(stdin);
+        child.wait_with_output().await.map_err(parse_err)
+    };
+    let output = tokio::time::timeout(Duration::from_secs(settings.parse_timeout_secs), parsing)
+        .await
+        .map_err(|_| parse_err(format!("timed out after {}s", settings.parse_timeout_secs)))??;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let outcome = stdout
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .and_then(|line| serde_json::from_str::<ParseOutcome>(line).ok());
+    match outcome {
+        Some(ParseOutcome::Parsed(extraction)) => Ok(extraction),
+        Some(ParseOutcome::Failed { code, message }) => {
+            let mut err = StandardError::new(&code);
+            err.message = message;
+            Err(err)
+        }
+        None => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let cause = match output.status.signal() {
+                Some(signal) => format!("parser killed by signal {}", signal),
+                None => format!("parser exited with {}", output.status),
+            };
+            Err(parse_err(format!(
+                "{}: {}",
+                cause,
+                stderr.lines().last().unwrap_or("").trim()
+            )))
+        }
+    }
+}
+
+/// Extracts an uploaded document within the configured time, memory and concurrency limits
+pub async fn extract_sandboxed(data: Vec<u8>, content_type: &str) -> Result<Extraction> {
+    if settings.parse_isolation == ISOLATION_PROCESS {
+        extract_in_process(data, content_type).await
+    } else {
+        extract_in_thread(data, content_type).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::write::FileOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_check_unpacked_size_refuses_zip_bombs() {
+        let mut buf = Cursor::new(vec![]);
+        let mut zip = zip::ZipWriter::new(&mut buf);
+        zip.start_file("word/document.xml", FileOptions::default()).unwrap();
+        zip.write_all(&vec![b' '; 4 * 1024 * 1024]).unwrap();
+        zip.finish().unwrap();
+        drop(zip);
+
+        assert!(check_unpacked_size(buf.get_ref(), 8 * 1024 * 1024).is_ok());
+        let err = check_unpacked_size(buf.get_ref(), 1024 * 1024).unwrap_err();
+        assert_eq!(err.err_code, "ERR-AI-005");
+        assert!(check_unpacked_size(b"%PDF-1.7", 1).is_ok());
+    }
+}
@@ -0,0 +1,162 @@
+//! Content sniffing, so a file is read as what it is rather than what its name says.
+//!
+//! Binary formats are recognised by their signature. ZIP and OLE containers are opened
+//! to tell DOCX from ODT and Word documents from other compound files. Anything else
+//! that decodes as text is HTML when it starts like markup, plain text otherwise.
+
+use std::io::{Cursor, Read};
+
+use super::{FORMATS, Format, decode_text, format_for_mime};
+
+const PDF: &[u8] = b"%PDF-";
+const ZIP: &[u8] = b"PK\x03\x04";
+const OLE: &[u8] = b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1";
+const RTF: &[u8] = b"{\\rtf";
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG: &[u8] = b"\xFF\xD8\xFF";
+const TIFF_LE: &[u8] = b"II*\x00";
+const TIFF_BE: &[u8] = b"MM\x00*";
+
+/// Bytes looked at when deciding between text and binary, and for HTML markers
+const TEXT_PROBE: usize = 8192;
+/// Bytes read from an ODF `mimetype` entry, real ones are well under 50
+const MIMETYPE_LIMIT: u64 = 64;
+
+fn mime(mime_type: &str) -> Option<&'static Format> {
+    format_for_mime(mime_type)
+}
+
+fn sniff_zip(data: &[u8]) -> Option<&'static Format> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).ok()?;
+    if let Ok(entry) = archive.by_name("mimetype") {
+        // the archive is not size checked yet, so never unpack more than a mimetype needs
+        let mut declared = String::new();
+        entry.take(MIMETYPE_LIMIT).read_to_string(&mut declared).ok()?;
+        return FORMATS.iter().find(|f| f.mime_type == declared.trim());
+    }
+    if archive.by_name("word/document.xml").is_ok() {
+        return mime("application/vnd.openxmlformats-officedocument.wordprocessingml.document");
+    }
+    None
+}
+
+fn sniff_ole(data: &[u8]) -> Option<&'static Format> {
+    let file = cfb::CompoundFile::open(Cursor::new(data)).ok()?;
+    if file.is_stream("/WordDocument") {
+        return mime("application/msword");
+    }
+    None
+}
+
+fn sniff_text(data: &[u8]) -> Option<&'static Format> {
+    let probe = &data[..data.len().min(TEXT_PROBE)];
+    let utf16 = probe.starts_with(b"\xFF\xFE") || probe.starts_with(b"\xFE\xFF");
+    if !utf16 && probe.contains(&0) {
+        return None;
+    }
+    let text = decode_text(probe);
+    let printable = text
+        .chars()
+        .filter(|c| !c.is_control() || c.is_whitespace())
+        .count();
+    if printable * 10 < text.chars().count() * 9 {
+        return None;
+    }
+    let lower = text.trim_start().to_lowercase();
+    let markup = lower.starts_with("<!doctype html")
+        || lower.starts_with("<html")
+        || (lower.starts_with('<') && (lower.contains("<body") || lower.contains("<head")));
+    mime(if markup { "text/html" } else { "text/plain" })
+}
+
+/// Identifies a document from its content, or `None` when it is no supported format
+pub fn sniff(data: &[u8]) -> Option<&'static Format> {
+    // PDF writers may put junk before the header; readers accept it within the first 1KB
+    if data[..data.len().min(1024)]
+        .windows(PDF.len())
+        .any(|w| w == PDF)
+    {
+        return mime("application/pdf");
+    }
+    if data.starts_with(ZIP) {
+        return sniff_zip(data);
+    }
+    if data.starts_with(OLE) {
+        return sniff_ole(data);
+    }
+    if data.starts_with(RTF) {
+        return mime("application/rtf");
+    }
+    if data.starts_with(PNG) {
+        return mime("image/png");
+    }
+    if data.starts_with(JPEG) {
+        return mime("image/jpeg");
+    }
+    if data.starts_with(TIFF_LE) || data.starts_with(TIFF_BE) {
+        return mime("image/tiff");
+    }
+    sniff_text(data)
+}
+
+/// Checks an upload against the format its extension claims. Plain text is accepted
+/// for any text format, since Markdown cannot be told from text by its bytes and HTML
+/// fragments without `<html>`, `<head>` or `<body>` do not sniff as HTML.
+pub fn verify(claimed: &'static Format, data: &[u8]) -> Option<&'static Format> {
+    let detected = sniff(data)?;
+    if std::ptr::eq(detected, claimed) {
+        return Some(claimed);
+    }
+    if detected.mime_type == "text/plain" && claimed.mime_type.starts_with("text/") {
+        return Some(claimed);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::write::FileOptions;
+
+    use super::*;
+    use crate::pkg::internal::ai::read::format_for_extension;
+
+    fn zip_with_mimetype(declared: &[u8]) -> Vec<u8> {
+        let mut buf = Cursor::new(vec![]);
+        let mut zip = zip::ZipWriter::new(&mut buf);
+        zip.start_file("mimetype", FileOptions::default()).unwrap();
+        zip.write_all(declared).unwrap();
+        zip.finish().unwrap();
+        drop(zip);
+        buf.into_inner()
+    }
+
+    #[test]
+    fn test_sniff_zip_reads_a_bounded_mimetype() {
+        let odt = zip_with_mimetype(b"application/vnd.oasis.opendocument.text");
+        assert_eq!(sniff_zip(&odt).map(|f| f.mime_type), Some("application/vnd.oasis.opendocument.text"));
+
+        let mut bomb = b"application/vnd.oasis.opendocument.text".to_vec();
+        bomb.extend(vec![b'x'; 8 * 1024 * 1024]);
+        assert!(sniff_zip(&zip_with_mimetype(&bomb)).is_none());
+    }
+
+    #[test]
+    fn test_sniff_rejects_mismatched_extension() {
+        let pdf = format_for_extension("pdf").unwrap();
+        let md = format_for_extension("md").unwrap();
+        let html = b"<!DOCTYPE html><html><body>Jane</body></html>";
+
+        assert_eq!(sniff(b"%PDF-1.7\n...").map(|f| f.name), Some("PDF"));
+        assert_eq!(sniff(html).map(|f| f.name), Some("HTML"));
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\0\0").map(|f| f.name), Some("PNG"));
+        assert!(sniff(b"\0\x01\x02binary").is_none());
+
+        assert!(verify(pdf, html).is_none());
+        assert_eq!(verify(md, b"# Jane Doe\n- Rust").map(|f| f.name), Some("Markdown"));
+        let fragment = b"<div><h1>Jane Doe</h1><p>Rust engineer</p></div>";
+        let html_claim = format_for_extension("html").unwrap();
+        assert_eq!(verify(html_claim, fragment).map(|f| f.name), Some("HTML"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Main content extraction for arbitrary web pages, in the spirit of Readability.
+//!
+//! Paragraph-like elements are scored by how much prose they hold and credit their
+//! parent and grandparent containers. Containers are then penalised by the share of
+//! their text that sits inside links, and the best one is kept together with siblings
+//! that scored nearly as well. Navigation, footers, cookie banners and similar page
+//! furniture are dropped before scoring and when rendering.
+
+use std::collections::HashMap;
+
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html, Selector};
+
+use crate::pkg::internal::ai::read::html::elements_to_text;
+
+/// Elements that never hold the main content
+const BOILERPLATE_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "nav", "footer", "header", "aside", "form",
+    "iframe", "svg", "button", "select", "dialog",
+];
+
+/// id/class fragments of page furniture
+const NEGATIVE_HINTS: &[&str] = &[
+    "cookie", "consent", "banner", "navbar", "nav-", "menu", "footer", "header", "sidebar",
+    "share", "social", "breadcrumb", "newsletter", "popup", "modal", "related", "comment",
+    "advert", "promo", "subscribe", "skip-link", "masthead",
+];
+
+/// id/class fragments of content containers, which win over negative hints
+const POSITIVE_HINTS: &[&str] = &[
+    "content", "article", "main", "post", "job", "description", "posting", "body", "text",
+    "entry", "vacancy", "career",
+];
+
+const ROLES: &[&str] = &["navigation", "banner", "contentinfo", "dialog", "alertdialog", "menu"];
+
+/// Elements whose text is scored
+const PARAGRAPHS: &[&str] = &["p", "li", "pre", "td", "blockquote", "dd", "h2", "h3", "h4"];
+
+/// Paragraphs shorter than this are labels and buttons rather than prose
+const MIN_PARAGRAPH_CHARS: usize = 25;
+/// Extraction below this many characters is not trusted
+const MIN_CONTENT_CHARS: usize = 140;
+/// Siblings scoring at least this share of the best candidate are kept with it
+const SIBLING_SHARE: f64 = 0.2;
+
+fn hints(element: ElementRef) -> String {
+    let value = element.value();
+    format!(
+        "{} {}",
+        value.id().unwrap_or_default(),
+        value.attr("class").unwrap_or_default()
+    )
+    .to_lowercase()
+}
+
+fn class_weight(element: ElementRef) -> f64 {
+    let hints = hints(element);
+    let mut weight = 0.0;
+    if NEGATIVE_HINTS.iter().any(|h| hints.contains(h)) {
+        weight -= 25.0;
+    }
+    if POSITIVE_HINTS.iter().any(|h| hints.contains(h)) {
+        weight += 25.0;
+    }
+    weight
+}
+
+/// Page furniture that is removed before scoring and rendering
+pub fn is_boilerplate(element: ElementRef) -> bool {
+    let value = element.value();
+    if BOILERPLATE_TAGS.contains(&value.name()) {
+        return true;
+    }
+    if value.attr("hidden").is_some() || value.attr("aria-hidden") == Some("true") {
+        return true;
+    }
+    if value.attr("role").is_some_and(|role| ROLES.contains(&role)) {
+        return true;
+    }
+    let hints = hints(element);
+    NEGATIVE_HINTS.iter().any(|h| hints.contains(h))
+        && !POSITIVE_HINTS.iter().any(|h| hints.contains(h))
+}
+
+fn inside_boilerplate(element: ElementRef) -> bool {
+    std::iter::once(element)
+        .chain(element.ancestors().filter_map(ElementRef::wrap))
+        .any(is_boilerplate)
+}
+
+fn text_len(element: ElementRef) -> usize {
+    element.text().map(|t| t.trim().chars().count()).sum()
+}
+
+fn link_density(element: ElementRef, links: &Selector) -> f64 {
+    let total = text_len(element);
+    if total == 0 {
+        return 1.0;
+    }
+    let linked: usize = element.select(links).map(text_len).sum();
+    linked as f64 / total as f64
+}
+
+/// The main text of a page with headings and lists kept, or None when no part of the
+/// page reads like an article
+pub fn main_content(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let paragraphs = Selector::parse(&PARAGRAPHS.join(", ")).ok()?;
+    let links = Selector::parse("a").ok()?;
+
+    let mut scores: HashMap<NodeId, f64> = HashMap::new();
+    for paragraph in document.select(&paragraphs) {
+        if inside_boilerplate(paragraph) {
+            continue;
+        }
+        let text: String = paragraph.text().collect();
+        let length = text.trim().chars().count();
+        if length < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (length as f64 / 100.0).min(3.0);
+        let mut ancestors = paragraph.ancestors().filter_map(ElementRef::wrap);
+        for share in [1.0, 0.5] {
+            let Some(ancestor) = ancestors.next() else {
+                break;
+            };
+            let entry = scores
+                .entry(ancestor.id())
+                .or_insert_with(|| class_weight(ancestor));
+            *entry += score * share;
+        }
+    }
+
+    let element = |id: NodeId| document.tree.get(id).and_then(ElementRef::wrap);
+    let adjusted: HashMap<NodeId, f64> = scores
+        .iter()
+        .filter_map(|(id, score)| {
+            let candidate = element(*id)?;
+            Some((*id, score * (1.0 - link_density(candidate, &links))))
+        })
+        .collect();
+    let (best_id, best_score) = adjusted
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(id, score)| (*id, *score))?;
+    let best = element(best_id)?;
+
+    // content is often split across sibling containers, e.g. one per section heading
+    let threshold = (best_score * SIBLING_SHARE).max(10.0);
+    let kept: Vec<ElementRef> = match best.parent().and_then(ElementRef::wrap) {
+        Some(parent) => parent
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|sibling| {
+                sibling.id() == best_id
+                    || adjusted.get(&sibling.id()).is_some_and(|s| *s >= threshold)
+                    || (matches!(sibling.value().name(), "h1" | "h2" | "h3" | "h4")
+                        && !is_boilerplate(*sibling))
+            })
+            .collect(),
+        None => vec![best],
+    };
+    let text = elements_to_text(kept, is_boilerplate);
+    (text.chars().count() >= MIN_CONTENT_CHARS).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_content_drops_boilerplate() {
+        let html = r#"<html><body>
+          <nav><a href="/">Home</a> <a href="/jobs">Jobs</a></nav>
+          <div id="cookie-banner">We use cookies to improve your experience, please accept them all.</div>
+          <div class="layout">
+            <div class="job-description">
+              <h2>Senior Backend Engineer</h2>
+              <p>We are looking for an engineer to design, build and operate our payment APIs,
+                 working closely with product, data and infrastructure teams.</p>
+              <h3>Requirements</h3>
+              <ul><li>Five years of experience with Rust, Go or Java in production</li>
+                  <li>Comfortable with PostgreSQL, Kafka and distributed tracing</li></ul>
+            </div>
+            <div class="sidebar"><p>Similar jobs you might like, updated every single day for you.</p></div>
+          </div>
+          <footer><p>Copyright 2026 Example Inc, all rights reserved, imprint and privacy.</p></footer>
+        </body></html>"#;
+        let text = main_content(html).unwrap();
+        assert!(text.starts_with("## Senior Backend Engineer\nWe are looking for an engineer"));
+        assert!(text.contains("### Requirements\n- Five years of experience"));
+        for noise in ["Home", "cookies", "Similar jobs", "Copyright"] {
+            assert!(!text.contains(noise), "{} leaked into {}", noise, text);
+        }
+        assert!(main_content("<p>Too short</p>").is_none());
+    }
+}
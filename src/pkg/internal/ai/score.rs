@@ -0,0 +1,296 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::PgConnection;
+
+use crate::{
+    conf::settings,
+    pkg::internal::{
+        adaptors::{
+            jobs::spec::JobEntry,
+            resumes::{mutators::ResumeMutator, selectors::ResumeSelector, spec::FewShotExample},
+        },
+        ai::{
+            client::AIClient, generate::GenerateOps, heuristic, index::IndexOps,
+            prompts::PromptChoice,
+        },
+    },
+    prelude::Result,
+};
+
+/// How many past recruiter corrections are shown to the model per resume
+pub const FEW_SHOT_EXAMPLES: i64 = 3;
+/// LLM attempts per resume before it is left pending
+const SCORE_ATTEMPTS: u32 = 2;
+/// How long scoring stays offline once the provider has failed repeatedly
+const PROVIDER_COOLDOWN: Duration = Duration::from_secs(300);
+
+pub const SCORER_LLM: &str = "llm";
+pub const SCORER_HEURISTIC: &str = "heuristic";
+
+static CONSECUTIVE_FAILURES: AtomicU32 = AtomicU32::new(0);
+static OFFLINE_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+
+pub struct ScoredResume {
+    pub verdict: Verdict,
+    pub scorer: &'static str,
+    /// prompt version tag, or the heuristic version
+    pub version: String,
+    pub breakdown: Option<serde_json::Value>,
+    pub examples: Vec<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Verdict {
+    pub score: String,
+    pub status: String, //TODO: maybe change to enums for better safety, later
+    #[serde(deserialize_with = "deserialize_clean_string")]
+    pub feedback: String,
+}
+
+fn deserialize_clean_string<'de, D>(deserializer: D) -> core::result::Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    Ok(s.replace("\r\n", " ")
+        .replace('\n', " ")
+        .replace("  ", " ")
+        .trim()
+        .to_string())
+}
+
+/// Embeds the resume and picks the closest overridden verdicts on the same job or department.
+/// Nothing is embedded while there are no overrides to pick from, and scoring goes ahead
+/// without examples when the embedding cannot be created.
+pub async fn few_shot_examples(
+    conn: &mut PgConnection,
+    ai_client: &Arc<AIClient>,
+    resume_id: i32,
+    content: &str,
+    job: &JobEntry,
+) -> Result<Vec<FewShotExample>> {
+    let any = ResumeSelector::new(&mut *conn)
+        .has_override_examples(resume_id, job.id, &job.department, job.project_id.as_deref())
+        .await?;
+    if !any {
+        return Ok(vec![]);
+    }
+    let embedding = match ai_client.index_document(content).await {
+        Ok(embedding) => embedding,
+        Err(e) => {
+            tracing::warn!("skipping few-shot examples for resume {}: {}", resume_id, e.message);
+            return Ok(vec![]);
+        }
+    };
+    ResumeMutator::new(&mut *conn)
+        .store_embedding(resume_id, &embedding)
+        .await?;
+    ResumeSelector::new(&mut *conn)
+        .get_override_examples(
+            resume_id,
+            job.id,
+            &job.department,
+            job.project_id.as_deref(),
+            &embedding,
+            FEW_SHOT_EXAMPLES,
+        )
+        .await
+}
+
+pub fn format_examples(examples: &[FewShotExample]) -> String {
+    examples
+        .iter()
+        .enumerate()
+        .map(|(i, example)| {
+            format!(
+                "Example {}:\nResume excerpt:\n{}\nAI verdict: {} (score {}): {}\nRecruiter verdict: {} (score {}), because: {}\n",
+                i + 1,
+                example.excerpt.as_deref().map(str::trim).unwrap_or("(not available)"),
+                &example.status,
+                example.score.as_deref().unwrap_or("-"),
+                example.feedback.as_deref().unwrap_or(""),
+                &example.override_status,
+                example.override_score.as_deref().unwrap_or("-"),
+                example.override_reason.as_deref().unwrap_or(""),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub async fn score_resume(
+    ai_client: &Arc<AIClient>,
+    prompt: &PromptChoice,
+    content: &str,
+    job: &JobEntry,
+    examples: &[FewShotExample],
+) -> Result<Verdict> {
+    let res = query_verdict(ai_client, prompt, content, job, examples).await?;
+    parse_verdict(&res)
+}
+
+/// The model's raw answer, an error here means the provider failed
+async fn query_verdict(
+    ai_client: &Arc<AIClient>,
+    prompt: &PromptChoice,
+    content: &str,
+    job: &JobEntry,
+    examples: &[FewShotExample],
+) -> Result<String> {
+    let prompt = prompt.render_score(
+        content,
+        &serde_json::to_string(job)?,
+        &format_examples(examples),
+    )?;
+    ai_client.direct_query(&prompt, None).await
+}
+
+fn parse_verdict(res: &str) -> Result<Verdict> {
+    let cleaned_json = res.trim_start_matches("```json").trim_end_matches("```");
+    tracing::debug!("AI Result: \n {}", &cleaned_json);
+    let verdict: Verdict = serde_json::from_str(cleaned_json)?;
+    Ok(verdict)
+}
+
+fn provider_offline() -> bool {
+    let mut offline_until = OFFLINE_UNTIL.lock().unwrap_or_else(|e| e.into_inner());
+    match *offline_until {
+        Some(until) if Instant::now() < until => true,
+        Some(_) => {
+            *offline_until = None;
+            false
+        }
+        None => false,
+    }
+}
+
+/// Takes the provider offline for a while once `threshold` failures happened in a row
+fn record_provider_failure(threshold: u32) {
+    let failures = CONSECUTIVE_FAILURES.fetch_add(1, Ordering::SeqCst) + 1;
+    if failures < threshold {
+        return;
+    }
+    CONSECUTIVE_FAILURES.store(0, Ordering::SeqCst);
+    *OFFLINE_UNTIL.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now() + PROVIDER_COOLDOWN);
+    tracing::warn!(
+        "ai provider failed {} times in a row, scoring offline for {}s",
+        failures,
+        PROVIDER_COOLDOWN.as_secs()
+    );
+}
+
+fn score_offline(content: &str, job: &JobEntry) -> Result<ScoredResume> {
+    let (verdict, breakdown) = heuristic::score(content, job);
+    Ok(ScoredResume {
+        verdict,
+        scorer: SCORER_HEURISTIC,
+        version: heuristic::VERSION.to_string(),
+        breakdown: Some(serde_json::to_value(breakdown)?),
+        examples: vec![],
+    })
+}
+
+/// Scores with the evaluation's scorer. LLM scoring is retried and falls back to the
+/// heuristic scorer only once the provider has failed repeatedly; a resume that still
+/// gets no verdict is left pending. Malformed answers are retried but are no outage.
+pub async fn evaluate(
+    conn: &mut PgConnection,
+    ai_client: &Arc<AIClient>,
+    prompt: &PromptChoice,
+    scorer: &str,
+    resume_id: i32,
+    content: &str,
+    job: &JobEntry,
+) -> Result<ScoredResume> {
+    if scorer == SCORER_HEURISTIC {
+        return score_offline(content, job);
+    }
+    if provider_offline() {
+        tracing::info!("ai provider offline, scoring resume {} heuristically", resume_id);
+        return score_offline(content, job);
+    }
+    let examples = few_shot_examples(conn, ai_client, resume_id, content, job).await?;
+    let mut attempt = 1;
+    loop {
+        let failure = match query_verdict(ai_client, prompt, content, job, &examples).await {
+            Ok(res) => {
+                CONSECUTIVE_FAILURES.store(0, Ordering::SeqCst);
+                match parse_verdict(&res) {
+                    Ok(verdict) => {
+                        return Ok(ScoredResume {
+                            verdict,
+                            scorer: SCORER_LLM,
+                            version: prompt.version_tag(),
+                            breakdown: None,
+                            examples: examples.iter().map(|e| e.resume_id).collect(),
+                        });
+                    }
+                    Err(e) => e,
+                }
+            }
+            Err(e) => {
+                record_provider_failure(settings.ai_failure_threshold);
+                if provider_offline() {
+                    tracing::warn!("ai provider offline, scoring resume {} heuristically", resume_id);
+                    return score_offline(content, job);
+                }
+                e
+            }
+        };
+        if attempt >= SCORE_ATTEMPTS {
+            tracing::error!("scoring resume {} failed, leaving it pending: {}", resume_id, failure.message);
+            return Err(failure);
+        }
+        tracing::warn!("scoring resume {} failed (attempt {}), retrying: {}", resume_id, attempt, failure.message);
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples_show_the_example_resume() {
+        let example = FewShotExample {
+            resume_id: 7,
+            status: "rejected".into(),
+            score: Some("45".into()),
+            feedback: Some("No Kubernetes".into()),
+            override_status: "accepted".into(),
+            override_score: Some("75".into()),
+            override_reason: Some("Ran Nomad clusters, close enough".into()),
+            excerpt: Some("Site reliability engineer, operated Nomad clusters\n".into()),
+            similarity: 0.9,
+        };
+        let formatted = format_examples(&[example]);
+        assert!(formatted.contains("Resume excerpt:\nSite reliability engineer, operated Nomad clusters\nAI verdict"));
+        assert!(formatted.contains("Recruiter verdict: accepted (score 75)"));
+    }
+
+    #[test]
+    fn test_provider_goes_offline_only_after_repeated_failures() {
+        record_provider_failure(3);
+        record_provider_failure(3);
+        assert!(!provider_offline());
+        record_provider_failure(3);
+        assert!(provider_offline());
+        *OFFLINE_UNTIL.lock().unwrap() = None;
+    }
+
+    #[test]
+    fn test_parse_verdict_accepts_fenced_json() {
+        let verdict = parse_verdict("```json{\"score\": \"80\", \"status\": \"accepted\", \"feedback\": \"Good\\nfit\"}```")
+            .unwrap();
+        assert_eq!(verdict.status, "accepted");
+        assert_eq!(verdict.feedback, "Good fit");
+        assert!(parse_verdict("I cannot score this resume").is_err());
+    }
+}
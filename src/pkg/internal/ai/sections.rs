@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+/// Section kind first, then heading wordings that introduce it
+const HEADINGS: &[(&str, &[&str])] = &[
+    (
+        "summary",
+        &[
+            "summary", "professional summary", "profile", "professional profile", "about me",
+            "about", "objective", "career objective", "overview", "personal statement",
+        ],
+    ),
+    (
+        "experience",
+        &[
+            "experience", "work experience", "professional experience", "employment",
+            "employment history", "work history", "career history", "relevant experience",
+        ],
+    ),
+    (
+        "education",
+        &[
+            "education", "academic background", "academics", "qualifications",
+            "education and training", "academic qualifications",
+        ],
+    ),
+    (
+        "skills",
+        &[
+            "skills", "technical skills", "core skills", "key skills", "competencies",
+            "core competencies", "technologies", "tech stack", "tools", "skills and tools", "expertise",
+        ],
+    ),
+    (
+        "projects",
+        &["projects", "personal projects", "selected projects", "side projects", "portfolio"],
+    ),
+    (
+        "certifications",
+        &[
+            "certifications", "certificates", "licenses", "licenses and certifications",
+            "certifications and licenses", "courses", "training",
+        ],
+    ),
+];
+
+/// Headings that start a section we do not classify, so it does not run into the previous one
+const OTHER_HEADINGS: &[&str] = &[
+    "languages", "interests", "hobbies", "references", "awards", "honors", "publications",
+    "volunteering", "volunteer experience", "contact", "personal details", "activities",
+];
+
+/// A detected resume section, as character offsets into the full text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Section {
+    pub kind: String,
+    pub heading: String,
+    /// first character of the section body, after the heading line
+    pub start: usize,
+    pub end: usize,
+}
+
+fn normalize(line: &str) -> String {
+    line.trim()
+        .trim_start_matches('#')
+        .trim()
+        .trim_end_matches(':')
+        .trim()
+        .replace('&', "and")
+        .to_lowercase()
+}
+
+/// Classifies a line as a heading: `Some(Some(kind))` for a known section,
+/// `Some(None)` for an unclassified one, `None` for body text
+fn heading_kind(line: &str) -> Option<Option<&'static str>> {
+    let name = normalize(line);
+    if name.is_empty() || name.split_whitespace().count() > 5 {
+        return None;
+    }
+    if let Some((kind, _)) = HEADINGS.iter().find(|(_, names)| names.contains(&name.as_str())) {
+        return Some(Some(kind));
+    }
+    if OTHER_HEADINGS.contains(&name.as_str()) {
+        return Some(None);
+    }
+    None
+}
+
+/// Splits resume text into summary, experience, education, skills, projects and
+/// certifications by their headings. Text before the first heading belongs to no section.
+pub fn detect_sections(text: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = vec![];
+    let mut open: Option<Section> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let length = line.chars().count();
+        if let Some(kind) = heading_kind(line) {
+            if let Some(mut section) = open.take() {
+                section.end = offset;
+                sections.push(section);
+            }
+            open = kind.map(|kind| Section {
+                kind: kind.to_string(),
+                heading: line.trim().to_string(),
+                start: offset + length,
+                end: offset + length,
+            });
+        }
+        offset += length;
+    }
+    if let Some(mut section) = open {
+        section.end = offset;
+        sections.push(section);
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_sections_by_heading() {
+        let text = "Jane Doe\n# Summary\nBackend engineer\nWORK EXPERIENCE:\nAcme 2019-2024\n\
+                    Interests\nChess\nSkills & Tools\nRust, Go\n";
+        let sections = detect_sections(text);
+        let kinds: Vec<&str> = sections.iter().map(|s| s.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["summary", "experience", "skills"]);
+        let body: String = text
+            .chars()
+            .skip(sections[1].start)
+            .take(sections[1].end - sections[1].start)
+            .collect();
+        assert_eq!(body, "Acme 2019-2024\n");
+    }
+}
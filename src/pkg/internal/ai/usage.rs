@@ -0,0 +1,152 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use sqlx::PgPool;
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+
+use crate::{
+    conf::settings,
+    pkg::{
+        internal::adaptors::usage::{mutators::UsageMutator, spec::UsageRecord},
+        server::state::GetTxn,
+    },
+};
+
+/// USD per million tokens
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ModelPrice {
+    pub prompt: f64,
+    #[serde(default)]
+    pub completion: f64,
+}
+
+lazy_static! {
+    static ref PRICES: HashMap<String, ModelPrice> = load_prices(&settings.ai_price_table);
+}
+
+static RECORDER: OnceLock<UnboundedSender<UsageRecord>> = OnceLock::new();
+
+/// What an AI call is billed to, carried across awaits with `scoped`
+#[derive(Debug, Clone, Default)]
+pub struct UsageScope {
+    pub project_id: Option<String>,
+    pub evaluation_id: Option<i32>,
+    pub resume_id: Option<i32>,
+}
+
+tokio::task_local! {
+    static USAGE_SCOPE: UsageScope;
+}
+
+pub async fn scoped<F: Future>(scope: UsageScope, f: F) -> F::Output {
+    USAGE_SCOPE.scope(scope, f).await
+}
+
+fn load_prices(overrides: &str) -> HashMap<String, ModelPrice> {
+    let mut prices: HashMap<String, ModelPrice> = [
+        ("gpt-4o-mini", 0.15, 0.6),
+        ("gpt-4o", 2.5, 10.0),
+        ("gpt-4.1-mini", 0.4, 1.6),
+        ("text-embedding-3-small", 0.02, 0.0),
+        ("text-embedding-3-large", 0.13, 0.0),
+        ("gemini-2.5-flash", 0.3, 2.5),
+        ("gemini-2.5-pro", 1.25, 10.0),
+        ("text-embedding-004", 0.0, 0.0),
+    ]
+    .into_iter()
+    .map(|(model, prompt, completion)| (model.to_string(), ModelPrice { prompt, completion }))
+    .collect();
+    if !overrides.trim().is_empty() {
+        match serde_json::from_str::<HashMap<String, ModelPrice>>(overrides) {
+            Ok(custom) => prices.extend(custom),
+            Err(e) => tracing::error!("ignoring invalid AI_PRICE_TABLE: {}", e),
+        }
+    }
+    prices
+}
+
+pub fn estimate_cost(
+    prices: &HashMap<String, ModelPrice>,
+    model: &str,
+    prompt_tokens: u32,
+    completion_tokens: u32,
+) -> f64 {
+    match prices.get(model) {
+        Some(price) => {
+            (prompt_tokens as f64 * price.prompt + completion_tokens as f64 * price.completion)
+                / 1_000_000.0
+        }
+        None => 0.0,
+    }
+}
+
+/// Drains usage records into the database in the background so AI calls never wait on it
+pub fn start_recorder(db_pool: Arc<PgPool>) {
+    let (tx, mut rx) = unbounded_channel::<UsageRecord>();
+    if RECORDER.set(tx).is_err() {
+        return;
+    }
+    tokio::spawn(async move {
+        while let Some(record) = rx.recv().await {
+            let mut batch = vec![record];
+            while let Ok(record) = rx.try_recv() {
+                batch.push(record);
+            }
+            let result = async {
+                let mut tx = db_pool.begin_txn().await?;
+                UsageMutator::new(&mut tx).bulk_create(batch).await?;
+                tx.commit().await?;
+                Ok::<(), standard_error::StandardError>(())
+            }
+            .await;
+            if let Err(e) = result {
+                tracing::error!("failed to record ai usage: {}", e.message);
+            }
+        }
+    });
+}
+
+pub fn record(
+    operation: &str,
+    model: &str,
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    latency: Duration,
+) {
+    let scope = USAGE_SCOPE.try_with(|s| s.clone()).unwrap_or_default();
+    let record = UsageRecord {
+        provider: settings.ai_provider.clone(),
+        model: model.to_string(),
+        operation: operation.to_string(),
+        prompt_tokens: prompt_tokens as i32,
+        completion_tokens: completion_tokens as i32,
+        latency_ms: latency.as_millis() as i32,
+        cost_usd: estimate_cost(&PRICES, model, prompt_tokens, completion_tokens),
+        project_id: scope.project_id,
+        evaluation_id: scope.evaluation_id,
+        resume_id: scope.resume_id,
+    };
+    tracing::debug!("ai usage: {:?}", &record);
+    if let Some(recorder) = RECORDER.get() {
+        let _ = recorder.send(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_cost_with_overrides() {
+        let prices = load_prices(r#"{"my-model": {"prompt": 1.0, "completion": 2.0}}"#);
+        assert_eq!(estimate_cost(&prices, "my-model", 1_000_000, 500_000), 2.0);
+        assert_eq!(estimate_cost(&prices, "unknown-model", 1_000, 1_000), 0.0);
+        assert!(estimate_cost(&prices, "gpt-4o-mini", 1_000_000, 0) > 0.0);
+    }
+}
@@ -0,0 +1,10 @@
+use crate::pkg::internal::adaptors::letters::spec::CandidateLetterEntry;
+
+use super::{SendEmail, send_email};
+
+impl SendEmail for CandidateLetterEntry {
+    fn send(&self, email: &str) -> crate::prelude::Result<()> {
+        send_email(email, &self.subject, &self.body, false)?;
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use lettre::{Message, SmtpTransport, Transport};
 
 pub mod authtoken;
 pub mod invite;
+pub mod letter;
 
 use crate::{conf::settings, prelude::Result};
 
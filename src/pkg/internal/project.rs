@@ -140,6 +140,49 @@ impl Project {
         Ok(())
     }
 
+    pub async fn letter_template(&self, state: &AppState) -> Result<Option<String>> {
+        let mut tx = state.db_pool.begin_txn().await?;
+        let template: Option<String> =
+            sqlx::query_scalar("select letter_template from projects where project_id = $1")
+                .bind(&self.project_id)
+                .fetch_one(&mut *tx)
+                .await?;
+        Ok(template)
+    }
+
+    pub async fn set_letter_template(&self, state: &AppState, template: &str) -> Result<()> {
+        let mut tx = state.db_pool.begin_txn().await?;
+        sqlx::query("update projects set letter_template = $2 where project_id = $1")
+            .bind(&self.project_id)
+            .bind(template)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Whether resumes in another language than the job are translated before scoring
+    pub async fn translate_resumes(&self, state: &AppState) -> Result<bool> {
+        let mut tx = state.db_pool.begin_txn().await?;
+        let enabled: bool =
+            sqlx::query_scalar("select translate_resumes from projects where project_id = $1")
+                .bind(&self.project_id)
+                .fetch_one(&mut *tx)
+                .await?;
+        Ok(enabled)
+    }
+
+    pub async fn set_translate_resumes(&self, state: &AppState, enabled: bool) -> Result<()> {
+        let mut tx = state.db_pool.begin_txn().await?;
+        sqlx::query("update projects set translate_resumes = $2 where project_id = $1")
+            .bind(&self.project_id)
+            .bind(enabled)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
     pub async fn invite(
         &self,
         txn: &mut PgConnection,
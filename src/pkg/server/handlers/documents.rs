@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use sqlx::PgConnection;
+use standard_error::{StandardError, Status};
+
+use crate::{
+    conf::settings,
+    pkg::{
+        internal::{
+            adaptors::{
+                resume_texts::{
+                    mutators::ResumeTextMutator, selectors::ResumeTextSelector,
+                    spec::ResumeTextEntry,
+                },
+                resumes::{selectors::ResumeSelector, spec::ResumeEntry},
+            },
+            ai::{read::extract_document, sections::detect_sections},
+            auth::User,
+            minio::S3Ops,
+        },
+        server::{
+            handlers::project::current_project,
+            state::{AppState, GetTxn},
+        },
+    },
+    prelude::Result,
+};
+
+/// Stored text of a resume. Resumes parsed before text was kept are extracted once
+/// from the uploaded file and stored.
+pub async fn resume_text(
+    state: &AppState,
+    conn: &mut PgConnection,
+    resume: &ResumeEntry,
+) -> Result<ResumeTextEntry> {
+    if let Some(stored) = ResumeTextSelector::new(conn).get_by_resume(resume.id).await? {
+        return Ok(stored);
+    }
+    let (data, content_type) = state
+        .s3_client
+        .retrieve_object(&settings.s3_bucket_name, &resume.file_path)
+        .await?;
+    let text = extract_document(data, &content_type).await?;
+    ResumeTextMutator::new(conn)
+        .upsert(resume.id, &text, &detect_sections(&text))
+        .await
+}
+
+pub async fn text(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+) -> Result<Json<ResumeTextEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let text = resume_text(&state, &mut tx, &resume).await?;
+    tx.commit().await?;
+    Ok(Json(text))
+}
@@ -1,8 +1,8 @@
 use std::path::Path;
 use std::sync::Arc;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
 use reqwest::header::CONTENT_TYPE;
-use reqwest::StatusCode;
 use tokio::{task::JoinSet};
 use uuid::Uuid;
 
@@ -11,18 +11,28 @@ use axum::{
     extract::{Multipart, Path as AxumPath, State},
     response::Html,
 };
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::Serialize;
 use standard_error::{Interpolate, StandardError, Status};
 
 use crate::conf::settings;
 use crate::pkg::internal::adaptors::evaluations::spec::EvaluationEntry;
 use crate::pkg::internal::adaptors::jobs::selectors::JobSelector;
+use crate::pkg::internal::adaptors::resume_texts::mutators::ResumeTextMutator;
 use crate::pkg::internal::adaptors::resumes::mutators::{CreateResumeData, ResumeMutator};
 use crate::pkg::internal::adaptors::resumes::selectors::ResumeSelector;
 use crate::pkg::internal::adaptors::resumes::spec::ResumeEntry;
-use crate::pkg::internal::ai::generate::GenerateOps;
-use crate::pkg::internal::ai::read::extract_document;
+use crate::pkg::internal::ai::language::{detect_language, translate as translate_resume};
+use crate::pkg::internal::ai::prompts::{self, SCORE_RESUME, TRANSLATE_RESUME};
+use crate::pkg::internal::ai::sections::detect_sections;
+use crate::pkg::internal::ai::score::{self, SCORER_HEURISTIC, SCORER_LLM};
+use crate::pkg::internal::ai::usage::{UsageScope, scoped};
+use crate::pkg::internal::ai::read::{
+    FORMATS, format_for_extension,
+    sandbox::extract_sandboxed,
+    sniff::{sniff, verify},
+};
 use crate::pkg::internal::minio::S3Ops;
+use crate::pkg::server::handlers::project::current_project;
 use crate::pkg::server::state::GetTxn;
 use crate::{
     pkg::{
@@ -51,33 +61,18 @@ pub struct EvaluationDetails {
     pub pending: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Verdict {
-    pub score: String,
-    pub status: String, //TODO: maybe change to enums for better safety, later
-    #[serde(deserialize_with = "deserialize_clean_string")]
-    pub feedback: String,
-}
-
-fn deserialize_clean_string<'de, D>(deserializer: D) -> core::result::Result<String, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    Ok(s.replace("\r\n", " ")
-        .replace('\n', " ")
-        .replace("  ", " ")
-        .trim()
-        .to_string())
-}
-
 pub async fn create(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(user): Extension<Arc<User>>,
     mut multipart: Multipart,
 ) -> Result<Json<EvaluationEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let translate = project.translate_resumes(&state).await?;
+    let project_id = project.project_id;
     let mut name = String::new();
     let mut job_id_str = String::new();
+    let mut scorer = SCORER_LLM.to_string();
     let mut resume_files = Vec::new();
     while let Some(field) = multipart
         .next_field()
@@ -98,6 +93,15 @@ pub async fn create(
                     .await
                     .map_err(|e| StandardError::new(&format!("EVAL-003: {}", e)))?;
             }
+            "scorer" => {
+                scorer = field
+                    .text()
+                    .await
+                    .map_err(|e| StandardError::new(&format!("EVAL-005: {}", e)))?;
+                if ![SCORER_LLM, SCORER_HEURISTIC].contains(&scorer.as_str()) {
+                    return Err(StandardError::new("EVAL-009: Invalid scorer, use llm or heuristic"));
+                }
+            }
             "resumes" => {
                 let file_name = field.file_name().unwrap_or("unknown").to_string();
                 let data = field
@@ -109,20 +113,34 @@ pub async fn create(
                     .and_then(|ext| ext.to_str())
                     .unwrap_or("")
                     .to_lowercase();
-                if !["pdf", "doc", "docx"].contains(&file_extension.as_str()) {
-                    return Err(StandardError::new(
-                        "EVAL-006: Invalid file type. Only PDF, DOC, DOCX files are allowed",
-                    )
+                let Some(claimed) = format_for_extension(&file_extension) else {
+                    let names: Vec<&str> = FORMATS.iter().map(|f| f.name).collect();
+                    return Err(StandardError::new(&format!(
+                        "EVAL-006: Invalid file type. Supported formats: {}",
+                        names.join(", ")
+                    ))
                     .into());
-                }
+                };
                 if data.len() > 10 * 1024 * 1024 {
-                    // 10MB limit
+                    // 10MB limit, checked before sniffing opens ZIP and CFB containers
                     return Err(StandardError::new(
                         "EVAL-007: File too large. Maximum size is 10MB",
                     )
                     .into());
                 }
-                resume_files.push((file_name, data));
+                let Some(detected) = sniff(&data) else {
+                    return Err(StandardError::new(&format!(
+                        "EVAL-013: {} is not a readable {} file",
+                        file_name, claimed.name
+                    )));
+                };
+                let Some(format) = verify(claimed, &data) else {
+                    return Err(StandardError::new(&format!(
+                        "EVAL-013: {} has a .{} extension but contains {}",
+                        file_name, file_extension, detected.name
+                    )));
+                };
+                resume_files.push((file_name, data, format, detected));
             }
             _ => {
                 let _ = field
@@ -138,15 +156,18 @@ pub async fn create(
         .map_err(|_| StandardError::new("EVAL-008: Invalid job ID"))?;
 
     let mut tx = state.db_pool.begin_txn().await?;
+    if JobSelector::new(&mut tx).get_by_id(&project_id, job_id).await?.is_none() {
+        return Err(StandardError::new("EVAL-008: Invalid job ID"));
+    }
 
     let evaluation = EvaluationMutator::new(&mut tx)
-        .create(&name, job_id, &user.user_id)
+        .create(&project_id, &name, job_id, &user.user_id, &scorer)
         .await?;
 
     let upload_dir = format!("uploads/{}", &evaluation.name);
     let mut resumes: Vec<CreateResumeData> = vec![];
     let mut set = JoinSet::new();
-    for (original_filename, data) in resume_files {
+    for (original_filename, data, format, detected) in resume_files {
         let file_id = Uuid::new_v4();
         let file_extension = Path::new(&original_filename)
             .extension()
@@ -154,12 +175,7 @@ pub async fn create(
             .unwrap_or("bin");
         let filename = format!("{}-{}.{}", &original_filename, file_id, file_extension);
         let file_path = format!("{}/{}", upload_dir, filename);
-        let mime_type = match file_extension {
-            "pdf" => "application/pdf",
-            "doc" => "application/msword",
-            "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
-            _ => "application/octet-stream",
-        };
+        let mime_type = format.mime_type;
         let s3_client = state.s3_client.clone();
         let key = file_path.clone();
         let file_data: Vec<u8> = data.into();
@@ -179,6 +195,7 @@ pub async fn create(
                 file_path: key,
                 file_size: data_len as i64,
                 mime_type: mime_type.into(),
+                detected_type: detected.mime_type.into(),
             })
         });
     }
@@ -215,77 +232,101 @@ pub async fn create(
         //         }
         //     }
         // });
-        tokio::spawn(async move{
+        let scope = UsageScope {
+            project_id: Some(project_id.clone()),
+            evaluation_id: Some(evaluation.id),
+            resume_id: Some(resume.id),
+        };
+        let project_id = project_id.clone();
+        let scorer = scorer.clone();
+        tokio::spawn(scoped(scope, async move{
             let mut tx = db_pool.begin_txn().await?;
             let (data, content_type) =  s3_client.retrieve_object(&settings.s3_bucket_name, &resume.file_path).await?;
-            let content = extract_document(data, &content_type)?;
-            let job = match JobSelector::new(&mut *tx).get_by_id(evaluation.job_id).await?{
+            let extraction = match extract_sandboxed(data, &content_type).await {
+                Ok(extraction) => extraction,
+                Err(e) => {
+                    tracing::warn!("resume {} could not be read, not scoring: {}", resume.id, e.message);
+                    let feedback = format!(
+                        "The document could not be read ({}); please review it manually.",
+                        e.message
+                    );
+                    ResumeMutator::new(&mut tx).add_verdict(resume.id, "unreadable", None, Some(&feedback), None).await?;
+                    EvaluationMutator::new(&mut tx).update_counts(evaluation.id).await?;
+                    tx.commit().await?;
+                    return Ok(());
+                }
+            };
+            ResumeMutator::new(&mut tx).record_extraction(resume.id, extraction.quality, extraction.ocr_confidence).await?;
+            ResumeTextMutator::new(&mut tx)
+                .upsert(resume.id, &extraction.text, &detect_sections(&extraction.text))
+                .await?;
+            let language = detect_language(&extraction.text);
+            ResumeMutator::new(&mut tx).record_language(resume.id, language).await?;
+            if extraction.quality < settings.min_extraction_quality {
+                tracing::warn!("resume {} extracted with quality {:.2}, not scoring", resume.id, extraction.quality);
+                let feedback = format!(
+                    "The document text could not be read reliably (quality {:.2}); please review it manually.",
+                    extraction.quality
+                );
+                ResumeMutator::new(&mut tx).add_verdict(resume.id, "unreadable", None, Some(&feedback), None).await?;
+                EvaluationMutator::new(&mut tx).update_counts(evaluation.id).await?;
+                tx.commit().await?;
+                return Ok(());
+            }
+            let mut content = extraction.text;
+            let job = match JobSelector::new(&mut *tx).get_by_id(&project_id, evaluation.job_id).await?{
                 None => {
                     tracing::error!("job not found, invalid evaluation state");
                     return Err(StandardError::new("ERR-RESUME-001"));
                 },
                 Some(job) => job
             };
-            let prompt = format!(r#"
-You are a senior recruiter with deep technical expertise. Analyze the provided resume against the job description and return your assessment as valid JSON.
-
-RESUME:
-{}
-
-JOB DESCRIPTION:
-{}
-
-Evaluate the candidate objectively based on:
-- Relevant skills and experience match
-- Technical qualifications
-- Career progression and achievements
-- Overall fit for the role
-
-Return ONLY valid JSON in this exact format (no additional text):
-
-{{
-  "score": "75.5", 
-  "status": "accepted or rejected",
-  "feedback": "Your detailed reasoning here AS A SINGLE CONTIGUOUS PARAGRAPH with only english alphabets, no other characters allowed"
-}}
-
-you will output only valid JSON, never markdown, never text explanations.
-Always ensure the output is syntactically valid JSON.
-All strings must be on a single line; replace internal newlines with \n.
-Do not add comments, trailing commas, or extra whitespace.
-
-CRITICAL REQUIREMENTS:
-- score: number between 0-100 AS A STRING
-- status: either "accepted" or "rejected"  
-- feedback: MUST be a single continuous line of text with NO line breaks, NO newlines, NO special characters
-- Write the entire feedback as one flowing paragraph
-- Return valid JSON only, no markdown code blocks or explanations
-
-                "#, &content, &serde_json::to_string(&job)?);
-            let res = ai_client.direct_query(&prompt, None).await?;
-            let cleaned_json = res.trim_start_matches("```json").trim_end_matches("```");
-            tracing::debug!("AI Result: \n {}", &cleaned_json);
-            let verdict: Verdict = serde_json::from_str(cleaned_json)?;
-            tracing::debug!("Deserialized: \n {}", &cleaned_json);
+            let job_language = detect_language(&format!("{}\n{}", &job.description, &job.requirements));
+            if translate
+                && let (Some(from), Some(to)) = (language, job_language)
+                && from != to
+            {
+                let prompt = prompts::resolve(&mut tx, Some(&project_id), TRANSLATE_RESUME, None).await?;
+                match translate_resume(&ai_client, &prompt, &content, to).await {
+                    Ok(translated) => {
+                        ResumeTextMutator::new(&mut tx).record_translation(resume.id, to, &translated).await?;
+                        content = translated;
+                    }
+                    Err(e) => tracing::warn!(
+                        "scoring resume {} untranslated, {} to {} failed: {}", resume.id, from, to, e.message
+                    ),
+                }
+            }
+            let prompt = prompts::resolve(&mut tx, Some(&project_id), SCORE_RESUME, None).await?;
+            let scored = score::evaluate(
+                &mut tx, &ai_client, &prompt, &scorer, resume.id, &content, &job
+            ).await?;
+            let verdict = scored.verdict;
             ResumeMutator::new(&mut *tx).add_verdict(
-                resume.id, &verdict.status, Some(&verdict.score), Some(&verdict.feedback)
+                resume.id, &verdict.status, Some(&verdict.score), Some(&verdict.feedback),
+                Some(&scored.version)
+            ).await?;
+            ResumeMutator::new(&mut tx).record_scoring(
+                resume.id, scored.scorer, scored.breakdown.as_ref(), &scored.examples
             ).await?;
             EvaluationMutator::new(&mut *tx).update_counts(evaluation.id).await?;
             tracing::debug!("commiting verdict");
             tx.commit().await?;
             Ok::<(), StandardError>(())
-        });
+        }));
     } 
     Ok(Json(evaluation))
 }
 
 pub async fn list(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(user): Extension<Arc<User>>,
 ) -> Result<Json<Vec<EvaluationEntry>>> {
+    let project = current_project(&state, &headers, &user).await?;
     let mut tx = state.db_pool.begin_txn().await?;
     let evaluations = EvaluationSelector::new(&mut tx)
-        .get_evaluations_for_user(&user.user_id)
+        .get_evaluations_for_project(&project.project_id)
         .await?;
     Ok(Json(evaluations))
 }
@@ -300,17 +341,15 @@ pub async fn details_page(AxumPath(_evaluation_id): AxumPath<i32>) -> Result<Htm
 
 pub async fn get_details(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(user): Extension<Arc<User>>,
     AxumPath(evaluation_id): AxumPath<i32>,
 ) -> Result<Json<EvaluationDetails>> {
+    let project = current_project(&state, &headers, &user).await?;
     let mut tx = state.db_pool.begin_txn().await?;
-    let evaluations = EvaluationSelector::new(&mut tx)
-        .get_evaluations_for_user(&user.user_id)
-        .await?;
-
-    let evaluation = evaluations
-        .into_iter()
-        .find(|eval| eval.id == evaluation_id)
+    let evaluation = EvaluationSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation_id)
+        .await?
         .ok_or_else(|| StandardError::new("EVAL-404: Evaluation not found"))?;
 
     let details = EvaluationDetails {
@@ -330,32 +369,37 @@ pub async fn get_details(
 
 pub async fn get_documents(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(user): Extension<Arc<User>>,
     AxumPath(evaluation_id): AxumPath<i32>,
 ) -> Result<Json<Vec<ResumeEntry>>>{
+    let project = current_project(&state, &headers, &user).await?;
     let mut tx = state.db_pool.begin_txn().await?;
 
     let evaluation = match EvaluationSelector::new(&mut *tx)
-        .get_by_id(evaluation_id)
+        .get_by_id(&project.project_id, evaluation_id)
         .await?{
             Some(eval) => eval,
             None => {
-                return Err(StandardError::new("ERR-RESUME-001"))
+                return Err(StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))
             }
         };
-    if evaluation.created_by != user.user_id{
-        return Err(StandardError::new("ERR-RESUME-002").code(StatusCode::FORBIDDEN))
-    }
     let documents = ResumeSelector::new(&mut *tx).get_resumes_by_evaluation(evaluation.id).await?;
     Ok(Json(documents))
 }
 
 pub async fn retrieve_document(
     State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
     AxumPath(document_id): AxumPath<i32>
 ) -> Result<impl IntoResponse>{
+    let project = current_project(&state, &headers, &user).await?;
     let mut tx = state.db_pool.begin_txn().await?;
-    let resume = ResumeSelector::new(&mut tx).get_resume_by_id(document_id).await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
     
     let (file_data, content_type) = state.s3_client
         .retrieve_object(&settings.s3_bucket_name, &resume.file_path)
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use reqwest::header::CONTENT_TYPE;
+use serde::Deserialize;
+use standard_error::{StandardError, Status};
+
+use crate::{
+    pkg::{
+        internal::{
+            adaptors::{
+                evaluations::selectors::EvaluationSelector,
+                interview_kits::{
+                    mutators::InterviewKitMutator,
+                    selectors::InterviewKitSelector,
+                    spec::{InterviewKitEntry, InterviewQuestion},
+                },
+                jobs::selectors::JobSelector,
+                resumes::selectors::ResumeSelector,
+            },
+            ai::{
+                generate::GenerateOps,
+                usage::{UsageScope, scoped},
+            },
+            auth::User,
+        },
+        server::{
+            handlers::{documents::resume_text, project::current_project},
+            state::{AppState, GetTxn},
+        },
+    },
+    prelude::Result,
+};
+
+#[derive(Deserialize)]
+struct GeneratedKit {
+    questions: Vec<InterviewQuestion>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateKitInput {
+    pub questions: Vec<InterviewQuestion>,
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+) -> Result<Json<InterviewKitEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    if resume.status == "pending" || resume.score.is_none() {
+        return Err(StandardError::new("ERR-KIT-002"));
+    }
+    if resume.effective_status() != "accepted" {
+        return Err(StandardError::new("ERR-KIT-003"));
+    }
+    let evaluation = EvaluationSelector::new(&mut tx)
+        .get_by_id(&project.project_id, resume.evaluation_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let job = JobSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation.job_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let content = resume_text(&state, &mut tx, &resume).await?.text;
+    let prompt = format!(
+        r#"
+You are a senior technical interviewer preparing for an interview with a shortlisted candidate.
+Use the resume, the job description and the screening verdict below to write a tailored interview kit.
+
+RESUME:
+{}
+
+JOB DESCRIPTION:
+{}
+
+SCREENING VERDICT:
+status: {}
+score: {}
+feedback: {}
+
+Write between 8 and 12 questions across three categories:
+- technical: probe the hands-on skills the job requires, grounded in what the resume claims
+- behavioural: probe teamwork, ownership and communication using situations from the resume
+- gap: probe missing requirements, weak areas or unexplained gaps raised in the verdict
+
+Return ONLY valid JSON in this exact format (no additional text):
+
+{{
+  "questions": [
+    {{
+      "category": "technical or behavioural or gap",
+      "question": "the question to ask",
+      "expected_answer": "what a strong answer covers",
+      "rubric": "how to score the answer from weak to strong"
+    }}
+  ]
+}}
+
+you will output only valid JSON, never markdown, never text explanations.
+All strings must be on a single line; replace internal newlines with \n.
+        "#,
+        &content,
+        &serde_json::to_string(&job)?,
+        resume.effective_status(),
+        resume.effective_score().unwrap_or(""),
+        resume.feedback.as_deref().unwrap_or("")
+    );
+    let scope = UsageScope {
+        project_id: Some(project.project_id),
+        evaluation_id: Some(resume.evaluation_id),
+        resume_id: Some(resume.id),
+    };
+    let res = scoped(scope, state.ai_client.direct_query(&prompt, None)).await?;
+    let cleaned_json = res.trim_start_matches("```json").trim_end_matches("```");
+    tracing::debug!("AI Result: \n {}", &cleaned_json);
+    let generated: GeneratedKit = serde_json::from_str(cleaned_json)?;
+    let kit = InterviewKitMutator::new(&mut tx)
+        .upsert(resume.id, &user.user_id, generated.questions)
+        .await?;
+    tx.commit().await?;
+    Ok(Json(kit))
+}
+
+pub async fn get(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+) -> Result<Json<InterviewKitEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let kit = InterviewKitSelector::new(&mut tx)
+        .get_by_resume(resume.id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-KIT-001").code(StatusCode::NOT_FOUND))?;
+    Ok(Json(kit))
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+    Json(input): Json<UpdateKitInput>,
+) -> Result<Json<InterviewKitEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let kit = InterviewKitMutator::new(&mut tx)
+        .update_questions(resume.id, input.questions)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-KIT-001").code(StatusCode::NOT_FOUND))?;
+    tx.commit().await?;
+    Ok(Json(kit))
+}
+
+pub async fn export(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let kit = InterviewKitSelector::new(&mut tx)
+        .get_by_resume(resume.id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-KIT-001").code(StatusCode::NOT_FOUND))?;
+    let evaluation = EvaluationSelector::new(&mut tx)
+        .get_by_id(&project.project_id, resume.evaluation_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let job_title = JobSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation.job_id)
+        .await?
+        .map(|job| job.title)
+        .unwrap_or_default();
+    Ok((
+        [(CONTENT_TYPE, "text/markdown; charset=utf-8")],
+        kit.to_markdown(&resume.original_filename, &job_title),
+    ))
+}
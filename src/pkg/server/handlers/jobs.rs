@@ -1,17 +1,36 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
-use axum::{Extension, Json, extract::State};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
-use standard_error::{StandardError};
+use standard_error::{Interpolate, StandardError, Status};
 
 use crate::{
+    conf::settings,
     pkg::{
         internal::{
-            adaptors::jobs::{mutators::JobMutator, selectors::JobSelector, spec::JobEntry},
-            ai::{fetch::process, generate::GenerateOps, index::IndexOps},
+            adaptors::jobs::{mutators::JobMutator, selectors::JobSelector, spec::{JobEntry, JobRevisionEntry}},
+            ai::{
+                fetch::{Fetched, fetch_page, fetch_posting, process},
+                postings::{
+                    EXTRACTOR_LLM, Posting,
+                    listings::{Listing, board_api_url, discover},
+                },
+                generate::GenerateOps,
+                index::IndexOps,
+                prompts::{self, EXTRACT_JOB},
+                usage::{UsageScope, scoped},
+            },
             auth::User,
         },
-        server::state::{AppState, GetTxn},
+        server::{
+            handlers::project::current_project,
+            state::{AppState, GetTxn},
+        },
     },
     prelude::Result,
 };
@@ -40,85 +59,264 @@ pub struct GenerateJobInput {
     pub url: String,
 }
 
+#[derive(Deserialize)]
+pub struct ImportJobsInput {
+    /// a careers page, an RSS/Atom feed or a Greenhouse/Lever board
+    pub url: String,
+}
+
 pub async fn create(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Extension(user): Extension<Arc<User>>,
     Json(input): Json<CreateJobInput>,
 ) -> Result<Json<JobEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
     let mut tx = state.db_pool.begin_txn().await?;
     let job = JobMutator::new(&mut tx)
-        .create(&user.user_id, input)
+        .create(&project.project_id, &user.user_id, input)
         .await?;
     tx.commit().await?;
     //TODO: pubsub maybe...
-    let jd = serde_json::to_string(&job)?;
-    tokio::spawn(async move{
+    spawn_indexing(state, &job)?;
+    Ok(Json(job))
+}
+
+/// Embeds a new or changed job in the background, billed to the job's project
+pub fn spawn_indexing(state: AppState, job: &JobEntry) -> Result<()> {
+    let jd = serde_json::to_string(job)?;
+    let job_id = job.id;
+    let scope = UsageScope {
+        project_id: job.project_id.clone(),
+        ..Default::default()
+    };
+    tokio::spawn(scoped(scope, async move{
         let mut tx = state.db_pool.begin_txn().await?;
         let embedding = state.ai_client.index_document(&jd).await?;
-        JobMutator::new(&mut *tx).add_embedding(job.id, embedding).await?;
+        JobMutator::new(&mut *tx).add_embedding(job_id, embedding).await?;
+        tx.commit().await?;
         Ok::<(), StandardError>(())
-    });
-    Ok(Json(job))
+    }));
+    Ok(())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Position {
-    pub title: String,
-    pub department: String,
-    pub description: String,
-    pub requirements: String,
+#[derive(Debug, Serialize)]
+pub struct GeneratedJob {
+    #[serde(flatten)]
+    pub posting: Posting,
+    /// which extractor read the posting: a site name, `json-ld` or `llm`
+    pub extractor: &'static str,
 }
 
 pub async fn generate_from_url(
     State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
     Json(input): Json<GenerateJobInput>,
-) -> Result<Json<Position>> {
-    let jd = process(&input.url).await;
-    let prompt = format!(
-        r#"
-        You are a senior recruiter with immense technical background
-        Here's a job description from a typical job board like linkedin\n
-        {}\n
-        go through it and respond with following json format
-        {{
-             "title": "the job title",
-             "department": "the department in the company",
-             "description": "detailed job description",
-             "requirements": "job requirements"
-         }}
-
-        Note: the extra curly brace is not your concern, ignore that
-        NOTE: thee values here are for you to fill, don't just keep them the same
-        DO NOT DEVIATE THE FORMAT or break JSON
-        "#,
-        &jd
-    );
-    let res = state.ai_client.direct_query(&prompt, None).await?;
+) -> Result<Json<GeneratedJob>> {
+    let project = current_project(&state, &headers, &user).await?;
+    Ok(Json(read_posting(&state, &project.project_id, &input.url).await?))
+}
+
+/// Reads one posting from a URL, asking the LLM when no structured extractor matches
+async fn read_posting(state: &AppState, project_id: &str, url: &str) -> Result<GeneratedJob> {
+    read_fetched(state, Some(project_id), process(url).await?).await
+}
+
+async fn read_fetched(
+    state: &AppState,
+    project_id: Option<&str>,
+    fetched: Fetched,
+) -> Result<GeneratedJob> {
+    match fetched {
+        Fetched::Posting { posting, extractor } => Ok(GeneratedJob { posting, extractor }),
+        Fetched::Text(jd) => Ok(GeneratedJob {
+            posting: extract_with_llm(state, project_id, &jd).await?,
+            extractor: EXTRACTOR_LLM,
+        }),
+    }
+}
+
+/// Reads a posting linked from an import source. Unlike `read_posting` a page that cannot
+/// be loaded fails instead of handing the bare URL to the LLM.
+async fn read_linked_posting(state: &AppState, project_id: &str, url: &str) -> Result<Posting> {
+    let fetched = fetch_posting(url).await?;
+    let posting = read_fetched(state, Some(project_id), fetched).await?.posting;
+    if !posting.is_complete() {
+        return Err(StandardError::new("ERR-JOB-002").interpolate_err(url.to_string()));
+    }
+    Ok(posting)
+}
+
+/// Asks the LLM for the posting in a page's text, billed to the project
+pub async fn extract_with_llm(state: &AppState, project_id: Option<&str>, jd: &str) -> Result<Posting> {
+    let mut tx = state.db_pool.begin_txn().await?;
+    let prompt = prompts::resolve(&mut tx, project_id, EXTRACT_JOB, None)
+        .await?
+        .render_extract(jd)?;
+    let scope = UsageScope {
+        project_id: project_id.map(String::from),
+        ..Default::default()
+    };
+    let res = scoped(scope, state.ai_client.direct_query(&prompt, None)).await?;
     let cleaned_json = res.trim_start_matches("```json").trim_end_matches("```");
     tracing::debug!("AI Result: \n {}", &cleaned_json);
-    let position: Position = serde_json::from_str(cleaned_json)?;
-    Ok(Json(position))
+    let posting: Posting = serde_json::from_str(cleaned_json)?;
+    Ok(posting)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportFailure {
+    pub url: String,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct ImportReport {
+    /// postings the source lists, including any beyond the import cap
+    pub found: usize,
+    pub created: Vec<JobEntry>,
+    /// posting URLs that already belong to a job
+    pub skipped: Vec<String>,
+    pub failed: Vec<ImportFailure>,
+}
+
+/// Creates a job in the project for every posting a careers page, feed or board
+/// lists, skipping postings whose URL already belongs to one of its jobs
+pub async fn import(
+    state: &AppState,
+    created_by: &str,
+    project_id: &str,
+    source: &str,
+) -> Result<ImportReport> {
+    let source_url = Url::parse(source)
+        .map_err(|e| StandardError::new("ERR-FETCH-001").interpolate_err(e.to_string()))?;
+    let feed_url = board_api_url(&source_url).unwrap_or_else(|| source.to_string());
+    let body = fetch_page(&feed_url).await?;
+    let mut listings = discover(&source_url, &body);
+    let found = listings.len();
+    if found > settings.import_max_postings {
+        tracing::warn!(
+            "{} lists {} postings, importing the first {}",
+            source,
+            found,
+            settings.import_max_postings
+        );
+        listings.truncate(settings.import_max_postings);
+    }
+
+    let urls: Vec<String> = listings
+        .iter()
+        .filter_map(|listing| listing.url().map(String::from))
+        .collect();
+    let mut tx = state.db_pool.begin_txn().await?;
+    let mut seen: HashSet<String> = JobSelector::new(&mut tx)
+        .get_existing_urls(project_id, &urls)
+        .await?
+        .into_iter()
+        .collect();
+    drop(tx);
+
+    let mut report = ImportReport {
+        found,
+        created: vec![],
+        skipped: vec![],
+        failed: vec![],
+    };
+    let mut inputs = vec![];
+    for listing in listings {
+        if let Some(url) = listing.url()
+            && !seen.insert(url.to_string())
+        {
+            report.skipped.push(url.to_string());
+            continue;
+        }
+        let (posting, url) = match listing {
+            Listing::Posting { posting, url, .. } => (posting, url),
+            Listing::Link(url) => match read_linked_posting(state, project_id, &url).await {
+                Ok(posting) => (posting, Some(url)),
+                Err(e) => {
+                    tracing::warn!("could not read posting {}: {}", url, e.message);
+                    report.failed.push(ImportFailure {
+                        url,
+                        message: e.message,
+                    });
+                    continue;
+                }
+            },
+        };
+        inputs.push(CreateJobInput {
+            title: posting.title,
+            department: posting.department,
+            description: posting.description,
+            requirements: posting.requirements,
+            url,
+        });
+    }
+
+    let mut tx = state.db_pool.begin_txn().await?;
+    for input in inputs {
+        report.created.push(JobMutator::new(&mut tx).create(project_id, created_by, input).await?);
+    }
+    tx.commit().await?;
+    for job in &report.created {
+        spawn_indexing(state.clone(), job)?;
+    }
+    Ok(report)
 }
 
-pub async fn list(State(state): State<AppState>) -> Result<Json<Vec<JobEntry>>> {
+pub async fn import_jobs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Json(input): Json<ImportJobsInput>,
+) -> Result<Json<ImportReport>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let report = import(&state, &user.user_id, &project.project_id, &input.url).await?;
+    Ok(Json(report))
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+) -> Result<Json<Vec<JobEntry>>> {
+    let project = current_project(&state, &headers, &user).await?;
     let mut tx = state.db_pool.begin_txn().await?;
-    let jobs = JobSelector::new(&mut *tx).get_all().await?;
+    let jobs = JobSelector::new(&mut *tx).get_all(&project.project_id).await?;
     Ok(Json(jobs))
 }
 
+/// Earlier versions of a job, newest first, as recorded by the re-sync
+pub async fn revisions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(job_id): Path<i32>,
+) -> Result<Json<Vec<JobRevisionEntry>>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let revisions = JobSelector::new(&mut tx)
+        .get_revisions(&project.project_id, job_id)
+        .await?;
+    Ok(Json(revisions))
+}
+
 pub async fn update(
     State(state): State<AppState>,
-    Extension(_user): Extension<Arc<User>>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
     Json(input): Json<PatchJobInput>,
 ) -> Result<Json<JobEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
     let mut tx = state.db_pool.begin_txn().await?;
     let job = JobMutator::new(&mut tx)
-        .update(input.id as i32, input)
+        .update(&project.project_id, input.id as i32, input)
         .await?;
     tx.commit().await?;
 
     match job {
         Some(updated_job) => Ok(Json(updated_job)),
-        None => Err(StandardError::new("ERR-JOB-001")),
+        None => Err(StandardError::new("ERR-JOB-001").code(StatusCode::NOT_FOUND)),
     }
 }
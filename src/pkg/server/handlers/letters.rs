@@ -0,0 +1,451 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, LazyLock},
+};
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use lettre::Address;
+use standard_error::{Interpolate, StandardError, Status};
+
+use crate::{
+    pkg::{
+        internal::{
+            adaptors::{
+                evaluations::selectors::EvaluationSelector,
+                jobs::{selectors::JobSelector, spec::JobEntry},
+                letters::{
+                    mutators::{CandidateLetterMutator, CreateLetterData},
+                    selectors::CandidateLetterSelector,
+                    spec::{CandidateLetterEntry, Tone},
+                },
+                resumes::{selectors::ResumeSelector, spec::ResumeEntry},
+            },
+            ai::{
+                generate::GenerateOps,
+                usage::{UsageScope, scoped},
+            },
+            auth::User,
+            email::SendEmail,
+        },
+        server::{
+            handlers::{documents::resume_text, project::current_project},
+            state::{AppState, GetTxn},
+        },
+    },
+    prelude::Result,
+};
+
+#[derive(Deserialize)]
+pub struct DraftLetterInput {
+    #[serde(default)]
+    pub tone: Tone,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateLetterInput {
+    pub candidate_email: Option<String>,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GeneratedLetter {
+    candidate_email: Option<String>,
+    subject: String,
+    body: String,
+}
+
+#[derive(Serialize)]
+pub struct BulkDraftReport {
+    pub queued: usize,
+}
+
+#[derive(Serialize, Default)]
+pub struct BulkSendReport {
+    pub sent: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Email addresses as they appear in a resume
+fn emails_in(text: &str) -> Vec<String> {
+    static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}")
+            .expect("valid email pattern")
+    });
+    EMAIL
+        .find_iter(text)
+        .map(|m| m.as_str().trim_end_matches('.').to_string())
+        .collect()
+}
+
+/// The address to write to. The one the model picked is only trusted when the resume
+/// contains it verbatim, otherwise the first address in the resume is used.
+fn candidate_email(resume_text: &str, generated: Option<&str>) -> Option<String> {
+    let found = emails_in(resume_text);
+    generated
+        .map(str::trim)
+        .and_then(|email| found.iter().find(|f| f.eq_ignore_ascii_case(email)))
+        .or(found.first())
+        .cloned()
+}
+
+/// Refuses addresses the mailer could not deliver to
+fn ensure_deliverable(email: &str) -> Result<()> {
+    email
+        .parse::<Address>()
+        .map_err(|e| StandardError::new("ERR-LETTER-005").interpolate_err(e.to_string()))?;
+    Ok(())
+}
+
+fn ensure_evaluated(resume: &ResumeEntry) -> Result<()> {
+    if resume.status == "pending" || resume.score.is_none() {
+        return Err(StandardError::new("ERR-LETTER-003"));
+    }
+    Ok(())
+}
+
+async fn draft_letter(
+    state: &AppState,
+    tx: &mut PgConnection,
+    created_by: &str,
+    template: Option<&str>,
+    job: &JobEntry,
+    resume: &ResumeEntry,
+    tone: Tone,
+) -> Result<CandidateLetterEntry> {
+    let content = resume_text(state, tx, resume).await?.text;
+    let prompt = format!(
+        r#"
+You are writing on behalf of a hiring team to a person who applied for the role of {} ({} department).
+The application outcome is: {}.
+
+Write a short, polite, candidate-facing email about this outcome in a {} tone.
+
+INTERNAL SCREENING NOTES (for your understanding only, never quote or reveal them):
+{}
+
+RESUME (only use it to find the candidate's name and email and to acknowledge their background):
+{}
+
+PROJECT TEMPLATE (follow its structure and wording where given, fill any placeholders):
+{}
+
+Rules:
+- never mention scores, rankings, other candidates, AI or automated screening
+- never reveal internal reasoning or weaknesses from the screening notes
+- for accepted candidates, invite them to the next stage; for rejected candidates, thank them and close kindly
+- plain text only, no markdown
+
+Return ONLY valid JSON in this exact format (no additional text):
+
+{{
+  "candidate_email": "the email address found in the resume, or null",
+  "subject": "the email subject line",
+  "body": "the full email body, use \n for line breaks"
+}}
+        "#,
+        &job.title,
+        &job.department,
+        resume.effective_status(),
+        tone.as_str(),
+        resume
+            .override_reason
+            .as_deref()
+            .or(resume.feedback.as_deref())
+            .unwrap_or(""),
+        &content,
+        template.unwrap_or("none, use a standard business email structure"),
+    );
+    let scope = UsageScope {
+        project_id: job.project_id.clone(),
+        evaluation_id: Some(resume.evaluation_id),
+        resume_id: Some(resume.id),
+    };
+    let res = scoped(scope, state.ai_client.direct_query(&prompt, None)).await?;
+    let cleaned_json = res.trim_start_matches("```json").trim_end_matches("```");
+    tracing::debug!("AI Result: \n {}", &cleaned_json);
+    let generated: GeneratedLetter = serde_json::from_str(cleaned_json)?;
+    CandidateLetterMutator::new(tx)
+        .upsert_draft(
+            created_by,
+            CreateLetterData {
+                resume_id: resume.id,
+                tone,
+                candidate_email: candidate_email(&content, generated.candidate_email.as_deref()),
+                subject: generated.subject,
+                body: generated.body,
+            },
+        )
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-LETTER-004").code(StatusCode::CONFLICT))
+}
+
+pub async fn draft(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+    Json(input): Json<DraftLetterInput>,
+) -> Result<Json<CandidateLetterEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let template = project.letter_template(&state).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    ensure_evaluated(&resume)?;
+    let evaluation = EvaluationSelector::new(&mut tx)
+        .get_by_id(&project.project_id, resume.evaluation_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let job = JobSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation.job_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let letter = draft_letter(
+        &state,
+        &mut tx,
+        &user.user_id,
+        template.as_deref(),
+        &job,
+        &resume,
+        input.tone,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(Json(letter))
+}
+
+/// Drafts a letter for every evaluated applicant of an evaluation in the background.
+/// Letters that were already sent are left alone.
+pub async fn draft_all(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(evaluation_id): Path<i32>,
+    Json(input): Json<DraftLetterInput>,
+) -> Result<Json<BulkDraftReport>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let template = project.letter_template(&state).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let evaluation = EvaluationSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let job = JobSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation.job_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let sent: HashSet<i32> = CandidateLetterSelector::new(&mut tx)
+        .get_by_evaluation(evaluation.id)
+        .await?
+        .into_iter()
+        .filter(|letter| letter.status == "sent")
+        .map(|letter| letter.resume_id)
+        .collect();
+    let resumes: Vec<ResumeEntry> = ResumeSelector::new(&mut tx)
+        .get_resumes_by_evaluation(evaluation.id)
+        .await?
+        .into_iter()
+        .filter(|resume| ensure_evaluated(resume).is_ok() && !sent.contains(&resume.id))
+        .collect();
+    drop(tx);
+
+    let report = BulkDraftReport {
+        queued: resumes.len(),
+    };
+    tokio::spawn(async move {
+        for resume in resumes {
+            let drafted = async {
+                let mut tx = state.db_pool.begin_txn().await?;
+                draft_letter(
+                    &state,
+                    &mut tx,
+                    &user.user_id,
+                    template.as_deref(),
+                    &job,
+                    &resume,
+                    input.tone,
+                )
+                .await?;
+                tx.commit().await?;
+                Ok::<(), StandardError>(())
+            };
+            if let Err(e) = drafted.await {
+                tracing::warn!("could not draft letter for resume {}: {}", resume.id, e.message);
+            }
+        }
+    });
+    Ok(Json(report))
+}
+
+pub async fn get(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+) -> Result<Json<CandidateLetterEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let letter = CandidateLetterSelector::new(&mut tx)
+        .get_by_resume(resume.id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-LETTER-001").code(StatusCode::NOT_FOUND))?;
+    Ok(Json(letter))
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+    Json(input): Json<UpdateLetterInput>,
+) -> Result<Json<CandidateLetterEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    if let Some(email) = &input.candidate_email {
+        ensure_deliverable(email)?;
+    }
+    let letter = CandidateLetterMutator::new(&mut tx)
+        .update(
+            resume.id,
+            input.candidate_email.as_deref(),
+            input.subject.as_deref(),
+            input.body.as_deref(),
+        )
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-LETTER-001").code(StatusCode::NOT_FOUND))?;
+    tx.commit().await?;
+    Ok(Json(letter))
+}
+
+pub async fn send(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+) -> Result<Json<CandidateLetterEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let letter = CandidateLetterSelector::new(&mut tx)
+        .get_by_resume(resume.id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-LETTER-001").code(StatusCode::NOT_FOUND))?;
+    let letter = send_letter(&mut tx, letter).await?;
+    tx.commit().await?;
+    Ok(Json(letter))
+}
+
+/// Sends every drafted letter of an evaluation that has an address
+pub async fn send_all(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(evaluation_id): Path<i32>,
+) -> Result<Json<BulkSendReport>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let evaluation = EvaluationSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let letters = CandidateLetterSelector::new(&mut tx)
+        .get_by_evaluation(evaluation.id)
+        .await?;
+    drop(tx);
+
+    let mut report = BulkSendReport::default();
+    for letter in letters {
+        if letter.status == "sent" || letter.candidate_email.is_none() {
+            report.skipped += 1;
+            continue;
+        }
+        let resume_id = letter.resume_id;
+        let mut tx = state.db_pool.begin_txn().await?;
+        match send_letter(&mut tx, letter).await {
+            Ok(_) => {
+                tx.commit().await?;
+                report.sent += 1;
+            }
+            Err(e) if e.err_code == "ERR-LETTER-004" => report.skipped += 1,
+            Err(e) => {
+                tracing::warn!("could not send letter for resume {}: {}", resume_id, e.message);
+                report.failed += 1;
+            }
+        }
+    }
+    Ok(Json(report))
+}
+
+/// Claims the letter before mailing it so it is sent at most once; the caller commits
+/// after a successful send
+async fn send_letter(
+    tx: &mut PgConnection,
+    letter: CandidateLetterEntry,
+) -> Result<CandidateLetterEntry> {
+    let email = letter
+        .candidate_email
+        .clone()
+        .ok_or_else(|| StandardError::new("ERR-LETTER-002"))?;
+    ensure_deliverable(&email)?;
+    let sent = CandidateLetterMutator::new(tx)
+        .mark_sent(letter.resume_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-LETTER-004").code(StatusCode::CONFLICT))?;
+    letter.send(&email)?;
+    tracing::info!("sent candidate letter for resume {} to {}", letter.resume_id, &email);
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_email_must_appear_in_resume() {
+        let resume = "Jane Doe\nContact: jane.doe@example.com. Referee: boss@corp.example.org";
+        assert_eq!(
+            candidate_email(resume, Some("Jane.Doe@example.com")).as_deref(),
+            Some("jane.doe@example.com")
+        );
+        assert_eq!(
+            candidate_email(resume, Some("boss@corp.example.org")).as_deref(),
+            Some("boss@corp.example.org")
+        );
+        // a made up address falls back to the resume's own
+        assert_eq!(
+            candidate_email(resume, Some("jane@doe.com")).as_deref(),
+            Some("jane.doe@example.com")
+        );
+        assert_eq!(candidate_email("no contact details", Some("jane@doe.com")), None);
+    }
+
+    #[test]
+    fn test_undeliverable_addresses_are_refused() {
+        assert!(ensure_deliverable("jane.doe@example.com").is_ok());
+        let err = ensure_deliverable("jane.doe at example.com").unwrap_err();
+        assert_eq!(err.err_code, "ERR-LETTER-005");
+        assert!(ensure_deliverable("").is_err());
+    }
+}
@@ -1,6 +1,13 @@
 pub mod auth;
+pub mod documents;
 pub mod evaluations;
+pub mod interview;
 pub mod jobs;
+pub mod letters;
 pub mod probes;
 pub mod project;
+pub mod prompts;
+pub mod ranking;
 pub mod ui;
+pub mod usage;
+pub mod verdicts;
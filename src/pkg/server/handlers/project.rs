@@ -3,13 +3,13 @@ use std::sync::Arc;
 use axum::{
     Extension, Json,
     extract::{Query, State},
-    http::HeaderMap,
+    http::{HeaderMap, StatusCode},
     response::Redirect,
 };
 use axum_extra::extract::CookieJar;
 use serde::Deserialize;
 use serde_json::{Value, json};
-use standard_error::StandardError;
+use standard_error::{StandardError, Status};
 use validator::Validate;
 
 use crate::{
@@ -54,14 +54,7 @@ pub async fn invite(
     Extension(me): Extension<Arc<User>>,
     Json(input): Json<InviteInput>,
 ) -> Result<Json<Value>> {
-    let jar = CookieJar::from_headers(&headers);
-    let project_id = match jar.get("current_project").filter(|c| !c.value().is_empty()) {
-        Some(p) => p.value(),
-        None => {
-            return Err(StandardError::new("ERR-PROJ-001"));
-        }
-    };
-    let project = Project::retrieve(&state, project_id).await?;
+    let project = current_project(&state, &headers, &me).await?;
     let user = match User::retrieve(&state, &input.email).await? {
         Some(u) => u,
         None => {
@@ -80,6 +73,61 @@ pub async fn invite(
     })))
 }
 
+/// Resolves the `current_project` cookie to a project the user has accepted access to
+pub async fn current_project(state: &AppState, headers: &HeaderMap, user: &User) -> Result<Project> {
+    let jar = CookieJar::from_headers(headers);
+    let project_id = match jar.get("current_project").filter(|c| !c.value().is_empty()) {
+        Some(p) => p.value().to_string(),
+        None => {
+            return Err(StandardError::new("ERR-PROJ-001"));
+        }
+    };
+    Project::list(state, &user.user_id)
+        .await?
+        .into_iter()
+        .find(|p| p.project_id == project_id)
+        .ok_or_else(|| StandardError::new("ERR-PROJ-002").code(StatusCode::FORBIDDEN))
+}
+
+#[derive(Deserialize, Validate)]
+pub struct LetterTemplateInput {
+    #[validate(length(min = 1, message = "Field cannot be empty"))]
+    pub template: String,
+}
+
+pub async fn set_letter_template(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Json(input): Json<LetterTemplateInput>,
+) -> Result<Json<Value>> {
+    let project = current_project(&state, &headers, &user).await?;
+    project.set_letter_template(&state, &input.template).await?;
+    Ok(Json(json!({
+        "project_id": project.project_id,
+        "template": input.template
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct TranslationInput {
+    pub enabled: bool,
+}
+
+pub async fn set_translation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Json(input): Json<TranslationInput>,
+) -> Result<Json<Value>> {
+    let project = current_project(&state, &headers, &user).await?;
+    project.set_translate_resumes(&state, input.enabled).await?;
+    Ok(Json(json!({
+        "project_id": project.project_id,
+        "translate_resumes": input.enabled
+    })))
+}
+
 #[derive(Deserialize)]
 pub struct AcceptQuery {
     pub invite_code: String,
@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use standard_error::{Interpolate, StandardError, Status};
+
+use crate::{
+    pkg::{
+        internal::{
+            adaptors::{
+                evaluations::selectors::EvaluationSelector,
+                jobs::selectors::JobSelector,
+                prompts::{
+                    mutators::PromptMutator,
+                    selectors::PromptSelector,
+                    spec::{PromptComparisonEntry, PromptComparisonResult, PromptTemplateEntry},
+                },
+                resumes::selectors::ResumeSelector,
+            },
+            ai::{
+                prompts::{self, PROMPTS, SCORE_RESUME},
+                score::score_resume,
+                usage::{UsageScope, scoped},
+            },
+            auth::User,
+        },
+        server::{
+            handlers::{documents::resume_text, project::current_project},
+            state::{AppState, GetTxn},
+        },
+    },
+    prelude::Result,
+};
+
+#[derive(Serialize)]
+pub struct PromptInfo {
+    pub name: &'static str,
+    pub variables: &'static [&'static str],
+    pub optional_variables: &'static [&'static str],
+    pub builtin: &'static str,
+    pub active_version: i32,
+    pub versions: Vec<PromptTemplateEntry>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateVersionInput {
+    pub body: String,
+}
+
+#[derive(Deserialize)]
+pub struct CompareInput {
+    /// 0 is the builtin template
+    pub version_a: i32,
+    pub version_b: i32,
+}
+
+#[derive(Serialize)]
+pub struct ComparisonReport {
+    #[serde(flatten)]
+    pub comparison: PromptComparisonEntry,
+    pub compared: usize,
+    pub agreement_rate: Option<f64>,
+    pub mean_score_delta: Option<f64>,
+    pub results: Vec<PromptComparisonResult>,
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+) -> Result<Json<Vec<PromptInfo>>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let mut versions = PromptSelector::new(&mut tx)
+        .list_for_project(&project.project_id)
+        .await?;
+    let prompts = PROMPTS
+        .iter()
+        .map(|(name, variables)| {
+            let (own, rest): (Vec<_>, Vec<_>) = versions.drain(..).partition(|v| v.name == *name);
+            versions = rest;
+            PromptInfo {
+                name,
+                variables,
+                optional_variables: prompts::optional_variables(name),
+                builtin: prompts::builtin_source(name),
+                active_version: own.first().map(|v| v.version).unwrap_or(0),
+                versions: own,
+            }
+        })
+        .collect();
+    Ok(Json(prompts))
+}
+
+pub async fn create_version(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(name): Path<String>,
+    Json(input): Json<CreateVersionInput>,
+) -> Result<Json<PromptTemplateEntry>> {
+    prompts::validate_name(&name)?;
+    for var in prompts::variables(&name) {
+        if !prompts::uses_variable(&input.body, var) {
+            return Err(StandardError::new("ERR-PROMPT-003")
+                .interpolate_err(var.to_string())
+                .code(StatusCode::BAD_REQUEST));
+        }
+    }
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let entry = PromptMutator::new(&mut tx)
+        .create_version(&project.project_id, &name, &input.body, &user.user_id)
+        .await?;
+    tx.commit().await?;
+    Ok(Json(entry))
+}
+
+/// Rescores every resume of an evaluation with two prompt versions in the background.
+/// Stored verdicts are left untouched; results land in the comparison report.
+pub async fn compare(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(evaluation_id): Path<i32>,
+    Json(input): Json<CompareInput>,
+) -> Result<Json<PromptComparisonEntry>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let evaluation = EvaluationSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let job = JobSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation.job_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let prompt_a = prompts::resolve(
+        &mut tx,
+        Some(&project.project_id),
+        SCORE_RESUME,
+        Some(input.version_a),
+    )
+    .await?;
+    let prompt_b = prompts::resolve(
+        &mut tx,
+        Some(&project.project_id),
+        SCORE_RESUME,
+        Some(input.version_b),
+    )
+    .await?;
+    let resumes = ResumeSelector::new(&mut tx)
+        .get_resumes_by_evaluation(evaluation.id)
+        .await?;
+    let comparison = PromptMutator::new(&mut tx)
+        .create_comparison(
+            evaluation.id,
+            SCORE_RESUME,
+            &prompt_a.label(),
+            &prompt_b.label(),
+            &user.user_id,
+        )
+        .await?;
+    tx.commit().await?;
+
+    let comparison_id = comparison.id;
+    tokio::spawn(async move {
+        let compared = async {
+            for resume in resumes {
+                let scope = UsageScope {
+                    project_id: Some(project.project_id.clone()),
+                    evaluation_id: Some(evaluation.id),
+                    resume_id: Some(resume.id),
+                };
+                let outcome = scoped(scope, async {
+                    // the text scoring saw, translated where the evaluation translated it
+                    let mut tx = state.db_pool.begin_txn().await?;
+                    let stored = resume_text(&state, &mut tx, &resume).await?;
+                    tx.commit().await?;
+                    let content = stored.translated_text.unwrap_or(stored.text);
+                    let a = score_resume(&state.ai_client, &prompt_a, &content, &job, &[]).await?;
+                    let b = score_resume(&state.ai_client, &prompt_b, &content, &job, &[]).await?;
+                    Ok::<_, StandardError>((a, b))
+                })
+                .await;
+                let result = match outcome {
+                    Ok((a, b)) => PromptComparisonResult {
+                        resume_id: resume.id,
+                        status_a: Some(a.status),
+                        score_a: Some(a.score),
+                        feedback_a: Some(a.feedback),
+                        status_b: Some(b.status),
+                        score_b: Some(b.score),
+                        feedback_b: Some(b.feedback),
+                        error: None,
+                    },
+                    Err(e) => {
+                        tracing::error!("prompt comparison failed for resume {}: {}", resume.id, e.message);
+                        PromptComparisonResult {
+                            resume_id: resume.id,
+                            status_a: None,
+                            score_a: None,
+                            feedback_a: None,
+                            status_b: None,
+                            score_b: None,
+                            feedback_b: None,
+                            error: Some(e.message),
+                        }
+                    }
+                };
+                let mut tx = state.db_pool.begin_txn().await?;
+                PromptMutator::new(&mut tx)
+                    .add_comparison_result(comparison_id, &result)
+                    .await?;
+                tx.commit().await?;
+            }
+            let mut tx = state.db_pool.begin_txn().await?;
+            PromptMutator::new(&mut tx)
+                .finish_comparison(comparison_id, "completed", None)
+                .await?;
+            tx.commit().await?;
+            Ok::<(), StandardError>(())
+        };
+        if let Err(e) = compared.await {
+            tracing::error!("prompt comparison {} failed: {}", comparison_id, e.message);
+            let mut tx = state.db_pool.begin_txn().await?;
+            PromptMutator::new(&mut tx)
+                .finish_comparison(comparison_id, "failed", Some(&e.message))
+                .await?;
+            tx.commit().await?;
+        }
+        Ok::<(), StandardError>(())
+    });
+    Ok(Json(comparison))
+}
+
+pub async fn get_comparison(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(comparison_id): Path<i32>,
+) -> Result<Json<ComparisonReport>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let comparison = PromptSelector::new(&mut tx)
+        .get_comparison(comparison_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-PROMPT-004").code(StatusCode::NOT_FOUND))?;
+    // comparisons are reached through their evaluation's project
+    EvaluationSelector::new(&mut tx)
+        .get_by_id(&project.project_id, comparison.evaluation_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-PROMPT-004").code(StatusCode::NOT_FOUND))?;
+    let results = PromptSelector::new(&mut tx)
+        .get_comparison_results(comparison.id)
+        .await?;
+    let scored: Vec<_> = results.iter().filter(|r| r.error.is_none()).collect();
+    let compared = scored.len();
+    let agreement_rate = (compared > 0).then(|| {
+        scored.iter().filter(|r| r.status_a == r.status_b).count() as f64 / compared as f64
+    });
+    let deltas: Vec<f64> = scored
+        .iter()
+        .filter_map(|r| {
+            let a = r.score_a.as_deref()?.trim().parse::<f64>().ok()?;
+            let b = r.score_b.as_deref()?.trim().parse::<f64>().ok()?;
+            Some(b - a)
+        })
+        .collect();
+    let mean_score_delta =
+        (!deltas.is_empty()).then(|| deltas.iter().sum::<f64>() / deltas.len() as f64);
+    Ok(Json(ComparisonReport {
+        comparison,
+        compared,
+        agreement_rate,
+        mean_score_delta,
+        results,
+    }))
+}
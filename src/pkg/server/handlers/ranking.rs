@@ -0,0 +1,254 @@
+use std::{collections::HashSet, sync::Arc};
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use standard_error::{StandardError, Status};
+
+use crate::{
+    pkg::{
+        internal::{
+            adaptors::{
+                evaluations::{selectors::EvaluationSelector, spec::EvaluationEntry},
+                jobs::{selectors::JobSelector, spec::JobEntry},
+                rankings::{
+                    mutators::RankingMutator,
+                    selectors::RankingSelector,
+                    spec::{RankingComparison, RankingRun},
+                },
+                resumes::{selectors::ResumeSelector, spec::ResumeEntry},
+            },
+            ai::{
+                prompts::{self, PromptChoice, RANK_PAIR},
+                rank::{
+                    DEFAULT_TOP_N, MAX_BUDGET, MAX_TOP_N, STALE_RUN_MINS, bradley_terry, compare_pair,
+                    default_budget, next_round, order_by_strength,
+                },
+                usage::{UsageScope, scoped},
+            },
+            auth::User,
+        },
+        server::{
+            handlers::{documents::resume_text, project::current_project},
+            state::{AppState, GetTxn},
+        },
+    },
+    prelude::Result,
+};
+
+#[derive(Deserialize, Default)]
+pub struct RankInput {
+    pub top_n: Option<usize>,
+    pub budget: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct Ranking {
+    pub run: RankingRun,
+    pub ranked: Vec<ResumeEntry>,
+    pub comparisons: Vec<RankingComparison>,
+}
+
+async fn project_evaluation(
+    state: &AppState,
+    project_id: &str,
+    evaluation_id: i32,
+) -> Result<EvaluationEntry> {
+    let mut tx = state.db_pool.begin_txn().await?;
+    EvaluationSelector::new(&mut tx)
+        .get_by_id(project_id, evaluation_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))
+}
+
+/// Ranks the top accepted resumes with pairwise LLM comparisons in the background
+pub async fn rank(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(evaluation_id): Path<i32>,
+    input: Option<Json<RankInput>>,
+) -> Result<Json<RankingRun>> {
+    let input = input.map(|Json(input)| input).unwrap_or_default();
+    let project = current_project(&state, &headers, &user).await?;
+    let evaluation = project_evaluation(&state, &project.project_id, evaluation_id).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let top_n = input.top_n.unwrap_or(DEFAULT_TOP_N).clamp(2, MAX_TOP_N);
+    let shortlist = ResumeSelector::new(&mut tx)
+        .get_shortlist(evaluation.id, top_n as i64)
+        .await?;
+    if shortlist.len() < 2 {
+        return Err(StandardError::new("ERR-RANK-001").code(StatusCode::BAD_REQUEST));
+    }
+    let all_pairs = shortlist.len() * (shortlist.len() - 1) / 2;
+    let budget = input
+        .budget
+        .unwrap_or_else(|| default_budget(shortlist.len()))
+        .clamp(1, MAX_BUDGET.min(all_pairs));
+    let job = JobSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation.job_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let prompt = prompts::resolve(&mut tx, Some(&project.project_id), RANK_PAIR, None).await?;
+    let run = RankingMutator::new(&mut tx)
+        .start_run(
+            evaluation.id,
+            shortlist.len() as i32,
+            budget as i32,
+            &user.user_id,
+            STALE_RUN_MINS,
+        )
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RANK-002").code(StatusCode::CONFLICT))?;
+    tx.commit().await?;
+
+    let scope = UsageScope {
+        project_id: Some(project.project_id),
+        evaluation_id: Some(evaluation.id),
+        resume_id: None,
+    };
+    let background = run.clone();
+    tokio::spawn(scoped(scope, async move {
+        let run = background;
+        if let Err(e) = tournament(&state, &run, &prompt, &job, shortlist, budget).await {
+            tracing::error!("ranking run {} failed: {}", run.id, e.message);
+            let mut tx = state.db_pool.begin_txn().await?;
+            RankingMutator::new(&mut tx)
+                .finish_run(&run, "failed", &[])
+                .await?;
+            tx.commit().await?;
+        }
+        Ok::<(), StandardError>(())
+    }));
+    Ok(Json(run))
+}
+
+pub async fn get(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(evaluation_id): Path<i32>,
+) -> Result<Json<Ranking>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let evaluation = project_evaluation(&state, &project.project_id, evaluation_id).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let run = RankingSelector::new(&mut tx)
+        .get_latest_run(evaluation.id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RANK-003").code(StatusCode::NOT_FOUND))?;
+    let comparisons = RankingSelector::new(&mut tx).get_comparisons(run.id).await?;
+    let mut ranked: Vec<ResumeEntry> = ResumeSelector::new(&mut tx)
+        .get_resumes_by_evaluation(evaluation.id)
+        .await?
+        .into_iter()
+        .filter(|r| r.rank.is_some())
+        .collect();
+    ranked.sort_by_key(|r| r.rank);
+    Ok(Json(Ranking {
+        run,
+        ranked,
+        comparisons,
+    }))
+}
+
+/// Ranks the shortlist by pairwise comparisons. Errors when fewer than two resumes can be
+/// read or no comparison succeeds, the caller then fails the run and keeps the old ranks.
+async fn tournament(
+    state: &AppState,
+    run: &RankingRun,
+    prompt: &PromptChoice,
+    job: &JobEntry,
+    shortlist: Vec<ResumeEntry>,
+    budget: usize,
+) -> Result<()> {
+    let job = serde_json::to_string(job)?;
+    let mut candidates = vec![];
+    for resume in shortlist {
+        let content = async {
+            let mut tx = state.db_pool.begin_txn().await?;
+            let text = resume_text(state, &mut tx, &resume).await?;
+            tx.commit().await?;
+            Ok::<String, StandardError>(text.text)
+        }
+        .await;
+        match content {
+            Ok(content) => candidates.push((resume.id, content)),
+            Err(e) => tracing::error!("leaving resume {} out of ranking: {}", resume.id, e.message),
+        }
+    }
+    if candidates.len() < 2 {
+        return Err(StandardError::new("ERR-RANK-001"));
+    }
+
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    let mut strength = vec![1.0; candidates.len()];
+    let mut played = HashSet::new();
+    let mut results = vec![];
+    let mut used = 0;
+    while used < budget {
+        let round = next_round(&order, &played);
+        if round.is_empty() {
+            break;
+        }
+        for (a, b) in round {
+            if used >= budget {
+                break;
+            }
+            used += 1;
+            played.insert((a.min(b), a.max(b)));
+            // alternate presentation order to cancel out position bias
+            let (first, second) = if used % 2 == 0 { (b, a) } else { (a, b) };
+            let outcome = compare_pair(
+                &state.ai_client,
+                prompt,
+                &job,
+                &candidates[first].1,
+                &candidates[second].1,
+            )
+            .await;
+            let mut tx = state.db_pool.begin_txn().await?;
+            match outcome {
+                Ok((first_wins, reason)) => {
+                    let (winner, loser) = if first_wins { (first, second) } else { (second, first) };
+                    results.push((winner, loser));
+                    RankingMutator::new(&mut tx)
+                        .add_comparison(
+                            run.id,
+                            &RankingComparison {
+                                resume_a: candidates[first].0,
+                                resume_b: candidates[second].0,
+                                winner: candidates[winner].0,
+                                reason: Some(reason),
+                            },
+                        )
+                        .await?;
+                }
+                Err(e) => {
+                    tracing::error!("ranking comparison failed: {}", e.message);
+                    RankingMutator::new(&mut tx).record_failure(run.id).await?;
+                }
+            }
+            tx.commit().await?;
+        }
+        strength = bradley_terry(candidates.len(), &results);
+        order = order_by_strength(&strength);
+    }
+    // without a single decided comparison the strengths say nothing, keep the last ranking
+    if results.is_empty() {
+        return Err(StandardError::new("ERR-RANK-004"));
+    }
+
+    let ranks: Vec<(i32, f64)> = order
+        .iter()
+        .map(|&i| (candidates[i].0, strength[i]))
+        .collect();
+    let mut tx = state.db_pool.begin_txn().await?;
+    RankingMutator::new(&mut tx)
+        .finish_run(run, "completed", &ranks)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
@@ -5,7 +5,7 @@ use axum::{Extension, extract::State, response::Html};
 
 use crate::{
     pkg::{
-        internal::{auth::User, project::Project},
+        internal::{ai::read::FORMATS, auth::User, project::Project},
         server::{state::AppState, uispec::Home},
     },
     prelude::Result,
@@ -18,9 +18,22 @@ pub async fn home(
 ) -> Result<Html<String>> {
     let projects = Project::list(&state, &user.user_id).await?;
     tracing::debug!("projects: {:?}", &projects);
+    let accept = FORMATS
+        .iter()
+        .flat_map(|f| f.extensions)
+        .map(|ext| format!(".{}", ext))
+        .collect::<Vec<_>>()
+        .join(",");
+    let names: Vec<&str> = FORMATS.iter().map(|f| f.name).collect();
+    let format_names = match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => names.join(""),
+    };
     let template = Home {
         username: &user.name,
         projects,
+        accept,
+        format_names,
     };
 
     Ok(Html(template.render()?))
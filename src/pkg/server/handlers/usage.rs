@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use serde::Serialize;
+use standard_error::{StandardError, Status};
+
+use crate::{
+    pkg::{
+        internal::{
+            adaptors::{
+                evaluations::selectors::EvaluationSelector,
+                usage::{
+                    selectors::UsageSelector,
+                    spec::{ResumeUsage, UsageSummary},
+                },
+            },
+            auth::User,
+        },
+        server::{
+            handlers::project::current_project,
+            state::{AppState, GetTxn},
+        },
+    },
+    prelude::Result,
+};
+
+#[derive(Serialize)]
+pub struct EvaluationUsage {
+    pub evaluation_id: i32,
+    pub cost_usd: f64,
+    pub by_model: Vec<UsageSummary>,
+    pub by_resume: Vec<ResumeUsage>,
+}
+
+#[derive(Serialize)]
+pub struct ProjectUsage {
+    pub project_id: String,
+    pub cost_usd: f64,
+    pub by_model: Vec<UsageSummary>,
+}
+
+pub async fn evaluation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(evaluation_id): Path<i32>,
+) -> Result<Json<EvaluationUsage>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let evaluation = EvaluationSelector::new(&mut tx)
+        .get_by_id(&project.project_id, evaluation_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let by_model = UsageSelector::new(&mut tx)
+        .summary_for_evaluation(evaluation.id)
+        .await?;
+    let by_resume = UsageSelector::new(&mut tx).per_resume(evaluation.id).await?;
+    Ok(Json(EvaluationUsage {
+        evaluation_id: evaluation.id,
+        cost_usd: by_model.iter().map(|s| s.cost_usd).sum(),
+        by_model,
+        by_resume,
+    }))
+}
+
+pub async fn project(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+) -> Result<Json<ProjectUsage>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let by_model = UsageSelector::new(&mut tx)
+        .summary_for_project(&project.project_id)
+        .await?;
+    Ok(Json(ProjectUsage {
+        project_id: project.project_id,
+        cost_usd: by_model.iter().map(|s| s.cost_usd).sum(),
+        by_model,
+    }))
+}
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use standard_error::{Interpolate, StandardError, Status};
+
+use crate::{
+    pkg::{
+        internal::{
+            adaptors::{
+                evaluations::mutators::EvaluationMutator,
+                resumes::{
+                    mutators::ResumeMutator,
+                    selectors::ResumeSelector,
+                    spec::{ResumeEntry, VerdictOverrideEntry},
+                },
+            },
+            ai::{
+                index::IndexOps,
+                usage::{UsageScope, scoped},
+            },
+            auth::User,
+        },
+        server::{
+            handlers::{documents::resume_text, project::current_project},
+            state::{AppState, GetTxn},
+        },
+    },
+    prelude::Result,
+};
+
+#[derive(Deserialize)]
+pub struct OverrideVerdictInput {
+    pub status: String,
+    pub score: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+pub struct VerdictHistory {
+    pub resume: ResumeEntry,
+    pub overrides: Vec<VerdictOverrideEntry>,
+}
+
+fn invalid(reason: &str) -> StandardError {
+    StandardError::new("ERR-VERDICT-001")
+        .interpolate_err(reason.to_string())
+        .code(StatusCode::BAD_REQUEST)
+}
+
+pub async fn override_verdict(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+    Json(input): Json<OverrideVerdictInput>,
+) -> Result<Json<ResumeEntry>> {
+    if !["accepted", "rejected"].contains(&input.status.as_str()) {
+        return Err(invalid("status must be accepted or rejected"));
+    }
+    if input.reason.trim().is_empty() {
+        return Err(invalid("a reason is required"));
+    }
+    if let Some(score) = &input.score {
+        match score.trim().parse::<f64>() {
+            Ok(value) if (0.0..=100.0).contains(&value) => {}
+            _ => return Err(invalid("score must be a number between 0 and 100")),
+        }
+    }
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    if resume.status == "pending" || resume.score.is_none() {
+        return Err(StandardError::new("ERR-VERDICT-002"));
+    }
+    let resume = ResumeMutator::new(&mut tx)
+        .override_verdict(
+            &resume,
+            &user.user_id,
+            &input.status,
+            input.score.as_deref().map(str::trim),
+            input.reason.trim(),
+        )
+        .await?;
+    EvaluationMutator::new(&mut tx)
+        .update_counts(resume.evaluation_id)
+        .await?;
+    tx.commit().await?;
+    spawn_example_embedding(state, &project.project_id, resume.clone());
+    Ok(Json(resume))
+}
+
+/// Overrides become few-shot examples for later resumes, which are found by embedding.
+/// Scoring only embeds resumes once examples exist, so the first overrides are embedded here.
+fn spawn_example_embedding(state: AppState, project_id: &str, resume: ResumeEntry) {
+    let scope = UsageScope {
+        project_id: Some(project_id.to_string()),
+        evaluation_id: Some(resume.evaluation_id),
+        resume_id: Some(resume.id),
+    };
+    tokio::spawn(scoped(scope, async move {
+        let embedded = async {
+            let mut tx = state.db_pool.begin_txn().await?;
+            if ResumeSelector::new(&mut tx).has_embedding(resume.id).await? {
+                return Ok(());
+            }
+            let text = resume_text(&state, &mut tx, &resume).await?;
+            let embedding = state
+                .ai_client
+                .index_document(text.translated_text.as_deref().unwrap_or(&text.text))
+                .await?;
+            ResumeMutator::new(&mut tx)
+                .store_embedding(resume.id, &embedding)
+                .await?;
+            tx.commit().await?;
+            Ok::<(), StandardError>(())
+        };
+        if let Err(e) = embedded.await {
+            tracing::warn!("could not embed overridden resume {}: {}", resume.id, e.message);
+        }
+    }));
+}
+
+pub async fn history(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Extension(user): Extension<Arc<User>>,
+    Path(document_id): Path<i32>,
+) -> Result<Json<VerdictHistory>> {
+    let project = current_project(&state, &headers, &user).await?;
+    let mut tx = state.db_pool.begin_txn().await?;
+    let resume = ResumeSelector::new(&mut tx)
+        .get_resume_in_project(document_id, &project.project_id)
+        .await?
+        .ok_or_else(|| StandardError::new("ERR-RESUME-001").code(StatusCode::NOT_FOUND))?;
+    let overrides = ResumeSelector::new(&mut tx)
+        .get_overrides(resume.id)
+        .await?;
+    Ok(Json(VerdictHistory { resume, overrides }))
+}
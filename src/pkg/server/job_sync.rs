@@ -0,0 +1,266 @@
+//! Periodic re-sync of jobs created from a URL.
+//!
+//! Due jobs are claimed in batches and their page is read again. Pages that did not
+//! change since the last read are recognised by hash and skipped without asking the
+//! LLM. Changed postings are diffed field by field, the previous version is kept as a
+//! revision and the job is re-embedded. A posting page answering 404 or 410 closes the job.
+//!
+//! LLM extractions word the same posting differently from run to run, so their fields only
+//! count as changed when the words differ noticeably. Jobs edited by hand keep their edits.
+
+use std::{
+    collections::HashSet,
+    hash::{DefaultHasher, Hash, Hasher},
+    time::Duration,
+};
+
+use crate::{
+    conf::settings,
+    pkg::{
+        internal::{
+            adaptors::jobs::{mutators::JobMutator, spec::JobEntry},
+            ai::{
+                fetch::{Fetched, fetch_posting},
+                postings::Posting,
+            },
+        },
+        server::{
+            handlers::jobs::{extract_with_llm, spawn_indexing},
+            state::{AppState, GetTxn},
+        },
+    },
+    prelude::Result,
+};
+
+/// How often the scheduler looks for due jobs
+const TICK: Duration = Duration::from_secs(5 * 60);
+
+pub async fn run(state: AppState) {
+    if settings.job_sync_interval_mins == 0 {
+        tracing::info!("job re-sync is disabled");
+        return;
+    }
+    let interval = Duration::from_secs(settings.job_sync_interval_mins * 60);
+    let mut ticker = tokio::time::interval(TICK.min(interval));
+    loop {
+        ticker.tick().await;
+        if let Err(e) = sync_due(&state).await {
+            tracing::error!("job re-sync failed: {}", e.message);
+        }
+    }
+}
+
+/// Syncs every job that is due, a batch at a time
+pub async fn sync_due(state: &AppState) -> Result<()> {
+    loop {
+        let mut tx = state.db_pool.begin_txn().await?;
+        let jobs = JobMutator::new(&mut tx)
+            .claim_due_for_sync(settings.job_sync_interval_mins as i32, settings.job_sync_batch_size)
+            .await?;
+        tx.commit().await?;
+        if jobs.is_empty() {
+            return Ok(());
+        }
+        for job in jobs {
+            if let Err(e) = sync_job(state, &job).await {
+                tracing::warn!("could not re-sync job {}: {}", job.id, e.message);
+            }
+        }
+    }
+}
+
+async fn sync_job(state: &AppState, job: &JobEntry) -> Result<()> {
+    let Some(url) = job.url.as_deref() else {
+        return Ok(());
+    };
+    let fetched = match fetch_posting(url).await {
+        Err(e) if e.err_code == "ERR-FETCH-003" => {
+            tracing::info!("closing job {}: {}", job.id, e.message);
+            let mut tx = state.db_pool.begin_txn().await?;
+            JobMutator::new(&mut tx).close(job.id).await?;
+            tx.commit().await?;
+            return Ok(());
+        }
+        fetched => fetched?,
+    };
+    let source_hash = hash_of(&fetched);
+    if job.source_hash == Some(source_hash) {
+        return Ok(());
+    }
+    if job.edited_at.is_some() {
+        tracing::info!("job {} was edited by hand, not applying its page", job.id);
+        let mut tx = state.db_pool.begin_txn().await?;
+        JobMutator::new(&mut tx).set_source_hash(job.id, source_hash).await?;
+        tx.commit().await?;
+        return Ok(());
+    }
+    let (posting, structured) = match fetched {
+        Fetched::Posting { posting, .. } => (posting, true),
+        Fetched::Text(text) => (
+            extract_with_llm(state, job.project_id.as_deref(), &text).await?,
+            false,
+        ),
+    };
+    if !posting.is_complete() {
+        tracing::warn!("job {}: no posting found at {}, keeping it as is", job.id, url);
+        return Ok(());
+    }
+
+    let posting = keep_stored(job, posting);
+    let changed = changed_fields(job, &posting, structured);
+    let mut tx = state.db_pool.begin_txn().await?;
+    if changed.is_empty() {
+        JobMutator::new(&mut tx).set_source_hash(job.id, source_hash).await?;
+        tx.commit().await?;
+        return Ok(());
+    }
+    let Some(revised) = JobMutator::new(&mut tx)
+        .revise(job, &posting, &changed, source_hash)
+        .await?
+    else {
+        tracing::info!("job {} was edited by hand while syncing, not applying its page", job.id);
+        return Ok(());
+    };
+    tx.commit().await?;
+    tracing::info!("job {} changed: {}", job.id, changed.join(", "));
+    spawn_indexing(state.clone(), &revised)
+}
+
+/// Identifies a page's content between syncs. The hasher may change between Rust
+/// releases, which only costs one extra extraction.
+fn hash_of(fetched: &Fetched) -> i64 {
+    let mut hasher = DefaultHasher::new();
+    match fetched {
+        Fetched::Posting { posting, .. } => {
+            (&posting.title, &posting.department, &posting.description, &posting.requirements)
+                .hash(&mut hasher)
+        }
+        Fetched::Text(text) => text.hash(&mut hasher),
+    }
+    hasher.finish() as i64
+}
+
+/// Word overlap above which an LLM extraction counts as the same text reworded
+const REPHRASE_SIMILARITY: f64 = 0.8;
+
+fn normalized(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Jaccard similarity of the two texts' words
+fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Fields the synced page left empty keep what is stored
+fn keep_stored(job: &JobEntry, mut posting: Posting) -> Posting {
+    if posting.department.trim().is_empty() {
+        posting.department = job.department.clone();
+    }
+    if posting.requirements.trim().is_empty() {
+        posting.requirements = job.requirements.clone();
+    }
+    posting
+}
+
+/// Fields of the stored job the re-synced posting changes. Structured extractions are
+/// compared ignoring whitespace, LLM extractions by their words.
+fn changed_fields(job: &JobEntry, posting: &Posting, structured: bool) -> Vec<&'static str> {
+    [
+        ("title", &job.title, &posting.title),
+        ("department", &job.department, &posting.department),
+        ("description", &job.description, &posting.description),
+        ("requirements", &job.requirements, &posting.requirements),
+    ]
+    .into_iter()
+    .filter(|(_, stored, synced)| {
+        if structured {
+            normalized(stored) != normalized(synced)
+        } else {
+            similarity(stored, synced) < REPHRASE_SIMILARITY
+        }
+    })
+    .map(|(name, _, _)| name)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_fields_ignores_whitespace() {
+        let now = chrono::Utc::now().naive_utc();
+        let job = JobEntry {
+            id: 1,
+            title: "Backend Engineer".into(),
+            department: "Platform".into(),
+            description: "Build our  APIs.\n".into(),
+            requirements: "- Rust".into(),
+            url: Some("https://jobs.lever.co/acme/1".into()),
+            created_at: now,
+            updated_at: now,
+            closed_at: None,
+            last_synced_at: None,
+            source_hash: None,
+            project_id: None,
+            edited_at: None,
+        };
+        let mut posting = Posting {
+            title: "Backend Engineer".into(),
+            department: "Platform".into(),
+            description: "Build our APIs.".into(),
+            requirements: "- Rust".into(),
+        };
+        assert!(changed_fields(&job, &posting, true).is_empty());
+        posting.requirements = "- Rust\n- PostgreSQL".into();
+        assert_eq!(changed_fields(&job, &posting, true), ["requirements"]);
+
+        // an empty synced field is not a change
+        posting.department = String::new();
+        let posting = keep_stored(&job, posting);
+        assert_eq!(posting.department, "Platform");
+    }
+
+    #[test]
+    fn test_llm_rewording_is_not_a_change() {
+        let now = chrono::Utc::now().naive_utc();
+        let job = JobEntry {
+            id: 1,
+            title: "Senior Backend Engineer".into(),
+            department: "Platform".into(),
+            description: "You will build and run our public APIs and the services behind them, working closely with product.".into(),
+            requirements: "- 5+ years of Rust or Go\n- PostgreSQL".into(),
+            url: Some("https://acme.example/careers/backend".into()),
+            created_at: now,
+            updated_at: now,
+            closed_at: None,
+            last_synced_at: None,
+            source_hash: None,
+            project_id: None,
+            edited_at: None,
+        };
+        let mut posting = Posting {
+            title: "Senior Backend Engineer".into(),
+            department: "Platform".into(),
+            description: "You will build and run our public APIs and the services behind them, working closely with the product team.".into(),
+            requirements: "- 5+ years of Rust or Go.\n- PostgreSQL".into(),
+        };
+        assert!(changed_fields(&job, &posting, false).is_empty());
+        assert_eq!(changed_fields(&job, &posting, true), ["description", "requirements"]);
+        posting.requirements = "- 3+ years of Python\n- Django\n- Kubernetes".into();
+        assert_eq!(changed_fields(&job, &posting, false), ["requirements"]);
+    }
+}
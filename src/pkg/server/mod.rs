@@ -1,4 +1,5 @@
 pub mod handlers;
+pub mod job_sync;
 pub mod middlewares;
 pub mod router;
 pub mod state;
@@ -3,6 +3,7 @@ use axum::routing::post;
 use axum::{Router, routing::get};
 
 use super::handlers;
+use super::job_sync;
 use super::handlers::auth::{logout, signup, verify};
 use super::handlers::probes::{healthz, livez};
 use super::handlers::ui::home;
@@ -12,16 +13,27 @@ use crate::prelude::Result;
 
 pub async fn build_routes() -> Result<Router> {
     let state = AppState::new().await?;
+    tokio::spawn(job_sync::run(state.clone()));
     let app = Router::new()
         .route("/", get(home))
         .route("/logout", post(logout))
         .route("/project", post(handlers::project::create))
         .route("/project/invite", post(handlers::project::invite))
         .route("/project/accept", get(handlers::project::accept))
+        .route(
+            "/project/letter-template",
+            axum::routing::put(handlers::project::set_letter_template),
+        )
+        .route(
+            "/project/translation",
+            axum::routing::put(handlers::project::set_translation),
+        )
         .route("/jobs", post(handlers::jobs::create))
         .route("/jobs", get(handlers::jobs::list))
         .route("/jobs", axum::routing::patch(handlers::jobs::update))
         .route("/jobs/generate", post(handlers::jobs::generate_from_url))
+        .route("/jobs/import", post(handlers::jobs::import_jobs))
+        .route("/jobs/:id/revisions", get(handlers::jobs::revisions))
         .route("/evaluations", post(handlers::evaluations::create))
         .route("/evaluations", get(handlers::evaluations::list))
         .route("/evaluations/:id", get(handlers::evaluations::details_page))
@@ -29,6 +41,26 @@ pub async fn build_routes() -> Result<Router> {
             "/api/evaluations/:id",
             get(handlers::evaluations::get_details),
         )
+        .route(
+            "/api/evaluations/:id/usage",
+            get(handlers::usage::evaluation),
+        )
+        .route("/api/usage", get(handlers::usage::project))
+        .route("/api/prompts", get(handlers::prompts::list))
+        .route("/api/prompts/:name", post(handlers::prompts::create_version))
+        .route(
+            "/api/evaluations/:id/compare-prompts",
+            post(handlers::prompts::compare),
+        )
+        .route("/api/evaluations/:id/rank", post(handlers::ranking::rank))
+        .route(
+            "/api/evaluations/:id/ranking",
+            get(handlers::ranking::get),
+        )
+        .route(
+            "/api/prompt-comparisons/:id",
+            get(handlers::prompts::get_comparison),
+        )
         .route(
             "/api/evaluations/:id/documents",
             get(handlers::evaluations::get_documents),
@@ -37,6 +69,42 @@ pub async fn build_routes() -> Result<Router> {
             "/api/documents/:id/retrieve",
             get(handlers::evaluations::retrieve_document),
         )
+        .route(
+            "/api/documents/:id/text",
+            get(handlers::documents::text),
+        )
+        .route(
+            "/api/documents/:id/verdict",
+            get(handlers::verdicts::history).patch(handlers::verdicts::override_verdict),
+        )
+        .route(
+            "/api/documents/:id/interview-kit",
+            post(handlers::interview::create)
+                .get(handlers::interview::get)
+                .put(handlers::interview::update),
+        )
+        .route(
+            "/api/documents/:id/interview-kit/export",
+            get(handlers::interview::export),
+        )
+        .route(
+            "/api/documents/:id/letter",
+            post(handlers::letters::draft)
+                .get(handlers::letters::get)
+                .put(handlers::letters::update),
+        )
+        .route(
+            "/api/documents/:id/letter/send",
+            post(handlers::letters::send),
+        )
+        .route(
+            "/api/evaluations/:id/letters",
+            post(handlers::letters::draft_all),
+        )
+        .route(
+            "/api/evaluations/:id/letters/send",
+            post(handlers::letters::send_all),
+        )
         .layer(from_fn_with_state(state.clone(), authn::authenticate))
         .route("/signup", post(signup))
         .route("/verify", post(verify))
@@ -1,5 +1,11 @@
-use crate::{conf::settings, pkg::internal::minio::S3Ops, prelude::Result};
-use ai::clients::openai::Client as AIClient;
+use crate::{
+    conf::settings,
+    pkg::internal::{
+        ai::{client::AIClient, index::check_embedding_metadata, usage::start_recorder},
+        minio::S3Ops,
+    },
+    prelude::Result,
+};
 use aws_sdk_s3::{
     Client as S3Client,
     config::{Credentials, Region},
@@ -7,7 +13,6 @@ use aws_sdk_s3::{
 use axum::async_trait;
 use sqlx::{PgPool, Transaction};
 use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
-use standard_error::{Interpolate, StandardError};
 use std::sync::Arc;
 
 pub fn db_pool() -> Result<Pool<Postgres>> {
@@ -43,21 +48,21 @@ impl GetTxn for Arc<PgPool>{
 
 impl AppState {
     pub async fn new() -> Result<AppState> {
-        let ai = if &settings.ai_provider == "gemini" {
-            ai::clients::openai::ClientBuilder::default()
-                .http_client(
-                    reqwest::Client::builder()
-                        .http1_title_case_headers()
-                        .build()?,
-                )
-                .api_key(settings.ai_key.clone().into())
-                .base_url("https://generativelanguage.googleapis.com/v1beta/openai".into())
-                .build()
-                .map_err(|e| StandardError::new("ERR-AI-000").interpolate_err(e.to_string()))?
-        } else {
-            ai::clients::openai::Client::from_url(&settings.ai_key, &settings.ai_endpoint)
-                .map_err(|e| StandardError::new("ERR-AI-000").interpolate_err(e.to_string()))?
-        };
+        let state = Self::connect().await?;
+        check_embedding_metadata(&state).await?;
+        Ok(state)
+    }
+
+    /// Builds the clients without the startup checks, for maintenance commands
+    pub async fn connect() -> Result<AppState> {
+        let ai = AIClient::from_settings(&settings)?;
+        tracing::info!(
+            "using {} at {} (chat: {}, embeddings: {})",
+            &settings.ai_provider,
+            &settings.ai_endpoint,
+            &settings.ai_model,
+            &settings.ai_embedding_model
+        );
         let s3_config = aws_sdk_s3::config::Builder::new()
             .credentials_provider(Credentials::new(
                 &settings.s3_access_key,
@@ -72,8 +77,10 @@ impl AppState {
             .build();
         let s3_client = Arc::new(aws_sdk_s3::Client::from_conf(s3_config));
         s3_client.create_new_bucket(&settings.s3_bucket_name).await?;
+        let db_pool = Arc::new(db_pool()?);
+        start_recorder(db_pool.clone());
         Ok(AppState {
-            db_pool: Arc::new(db_pool()?),
+            db_pool,
             ai_client: Arc::new(ai),
             s3_client
         })
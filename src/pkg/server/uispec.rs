@@ -7,6 +7,10 @@ use crate::pkg::internal::project::Project;
 pub struct Home<'a> {
     pub username: &'a str,
     pub projects: Vec<Project>,
+    /// file input `accept` list, e.g. `.pdf,.docx`
+    pub accept: String,
+    /// supported upload formats for display, e.g. `PDF, DOCX and TXT`
+    pub format_names: String,
 }
 
 #[derive(Template)]